@@ -0,0 +1,206 @@
+use std::path::Path;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{api::story::Story, persist};
+
+/// Oldest entries are dropped once the feed grows past this
+const MAX_ACTIVITY: usize = 500;
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ActivityKind {
+    NewComment { author_id: Uuid, text: String },
+    DescriptionEdited,
+    StateChanged { from: i32, to: i32 },
+    OwnerAdded { owner_id: Uuid },
+}
+
+/// Something that changed on one of our stories between two refreshes
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Activity {
+    pub id: Uuid,
+    pub story_id: i32,
+    pub story_name: String,
+    pub kind: ActivityKind,
+    pub at: NaiveDateTime,
+    pub read: bool,
+}
+
+impl Activity {
+    fn new(story: &Story, kind: ActivityKind, at: NaiveDateTime) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            story_id: story.id,
+            story_name: story.name.clone(),
+            kind,
+            at,
+            read: false,
+        }
+    }
+}
+
+/// Compares stories we already had with freshly fetched ones. A story new to the list is only
+/// recorded as assigned to `user_id`, and not at all on the first fetch when everything is new.
+/// Comments written by `user_id` are skipped, since we know about those already.
+pub fn diff_stories(
+    old: &[Story],
+    new: &[Story],
+    user_id: Uuid,
+    at: NaiveDateTime,
+) -> Vec<Activity> {
+    let mut activity = Vec::new();
+
+    for story in new {
+        let Some(previous) = old.iter().find(|s| s.id == story.id) else {
+            if !old.is_empty() && story.owner_ids.contains(&user_id) {
+                let kind = ActivityKind::OwnerAdded { owner_id: user_id };
+                activity.push(Activity::new(story, kind, at));
+            }
+            continue;
+        };
+
+        for comment in &story.comments {
+            // comments migrated from a version 0 cache have no id yet, only their position
+            let known = previous
+                .comments
+                .iter()
+                .any(|c| c.id == comment.id || (c.id < 0 && c.position == comment.position));
+            if comment.deleted || comment.author_id == user_id || known {
+                continue;
+            }
+
+            let kind = ActivityKind::NewComment {
+                author_id: comment.author_id,
+                text: comment.text.clone().unwrap_or_default(),
+            };
+            activity.push(Activity::new(story, kind, at));
+        }
+
+        if story.description != previous.description {
+            activity.push(Activity::new(story, ActivityKind::DescriptionEdited, at));
+        }
+
+        if story.workflow_state_id != previous.workflow_state_id {
+            let kind = ActivityKind::StateChanged {
+                from: previous.workflow_state_id,
+                to: story.workflow_state_id,
+            };
+            activity.push(Activity::new(story, kind, at));
+        }
+
+        for owner_id in &story.owner_ids {
+            if !previous.owner_ids.contains(owner_id) {
+                let kind = ActivityKind::OwnerAdded { owner_id: *owner_id };
+                activity.push(Activity::new(story, kind, at));
+            }
+        }
+    }
+
+    activity
+}
+
+/// Adds new activity to the front of the feed, newest first
+pub fn record(feed: &mut Vec<Activity>, new: Vec<Activity>) {
+    feed.splice(0..0, new);
+    feed.truncate(MAX_ACTIVITY);
+}
+
+pub fn unread_count(feed: &[Activity], story_id: i32) -> usize {
+    feed.iter()
+        .filter(|a| !a.read && a.story_id == story_id)
+        .count()
+}
+
+pub fn mark_story_read(feed: &mut [Activity], story_id: i32) -> bool {
+    let mut changed = false;
+    for activity in feed.iter_mut().filter(|a| a.story_id == story_id && !a.read) {
+        activity.read = true;
+        changed = true;
+    }
+    changed
+}
+
+pub async fn load_activity(cache_dir: &Path) -> Vec<Activity> {
+    let path = cache_dir.join("activity.json");
+    match tokio::fs::read_to_string(&path).await {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
+        Err(_) => Vec::new(),
+    }
+}
+
+pub async fn save_activity(cache_dir: &Path, activity: &[Activity]) -> anyhow::Result<()> {
+    let path = cache_dir.join("activity.json");
+    let content = serde_json::to_string_pretty(activity)?;
+    persist::locked_update(path, move |_| Ok((content, ()))).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::story::{comment::StoryComment, create_test_story};
+
+    fn story(id: i32) -> Story {
+        Story {
+            id,
+            description: "Original".to_string(),
+            owner_ids: vec![Uuid::nil()],
+            ..create_test_story(&format!("Story {}", id))
+        }
+    }
+
+    fn comment(id: i32, author_id: Uuid) -> StoryComment {
+        StoryComment {
+            id,
+            author_id,
+            deleted: false,
+            position: id,
+            text: Some("Looks good".to_string()),
+            parent_id: None,
+            created_at: chrono::DateTime::default(),
+        }
+    }
+
+    #[test]
+    fn changes_to_known_stories_are_recorded() {
+        let teammate = Uuid::from_u128(1);
+        let old = vec![story(1), story(2)];
+
+        let mut changed = story(1);
+        changed.description = "Edited".to_string();
+        changed.workflow_state_id = 2;
+        changed.owner_ids.push(teammate);
+        changed.comments = vec![comment(10, teammate), comment(11, Uuid::nil())];
+        let new = vec![changed, story(2), story(3)];
+
+        let kinds: Vec<_> = diff_stories(&old, &new, Uuid::nil(), NaiveDateTime::default())
+            .into_iter()
+            .map(|a| (a.story_id, a.kind))
+            .collect();
+
+        assert_eq!(
+            kinds,
+            vec![
+                (
+                    1,
+                    ActivityKind::NewComment {
+                        author_id: teammate,
+                        text: "Looks good".to_string()
+                    }
+                ),
+                (1, ActivityKind::DescriptionEdited),
+                (1, ActivityKind::StateChanged { from: 1, to: 2 }),
+                (1, ActivityKind::OwnerAdded { owner_id: teammate }),
+                (3, ActivityKind::OwnerAdded { owner_id: Uuid::nil() }),
+            ]
+        );
+    }
+
+    #[test]
+    fn first_fetch_records_nothing() {
+        let new = vec![story(1), story(2)];
+        assert!(diff_stories(&[], &new, Uuid::nil(), NaiveDateTime::default()).is_empty());
+    }
+}
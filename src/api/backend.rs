@@ -0,0 +1,319 @@
+use std::{env, path::{Path, PathBuf}, sync::Arc};
+
+use futures::future::BoxFuture;
+use uuid::Uuid;
+
+use crate::api::{
+    ApiClient,
+    epic::EpicSlim,
+    error::ApiResult,
+    group::Group,
+    iteration::Iteration,
+    recording::Recording,
+    story::{
+        NewStory, Story, StoryUpdate,
+        comment::StoryComment,
+        task::{StoryTask, TaskUpdate},
+    },
+    user::WorkspaceMember,
+    workflow::Workflow,
+};
+
+/// Shared handle to whichever backend the app is talking to
+pub type Backend = Arc<dyn ShortcutBackend>;
+
+/// Everything the app asks of Shortcut. Implemented by `ApiClient` for the real thing and by
+/// `FakeBackend` for running offline.
+///
+/// Methods return boxed futures so the trait can be used as `dyn ShortcutBackend`. Methods
+/// taking `known` entities may reuse them instead of refetching ones that haven't changed.
+pub trait ShortcutBackend: Send + Sync {
+    /// The member the app acts as
+    fn user_id(&self) -> Uuid;
+
+    /// The member the API token belongs to, used before `user_id` is known
+    fn get_user_id_from_api(&self) -> BoxFuture<'_, ApiResult<Uuid>>;
+
+    fn get_members(&self) -> BoxFuture<'_, ApiResult<Vec<WorkspaceMember>>>;
+
+    fn get_workflows(&self) -> BoxFuture<'_, ApiResult<Vec<Workflow>>>;
+
+    fn get_current_iterations(
+        &self,
+        known: Vec<Iteration>,
+    ) -> BoxFuture<'_, ApiResult<Vec<Iteration>>>;
+
+    fn get_all_iterations(
+        &self,
+        known: Vec<Iteration>,
+    ) -> BoxFuture<'_, ApiResult<Vec<Iteration>>>;
+
+    fn get_all_epics_slim(
+        &self,
+        include_description: bool,
+    ) -> BoxFuture<'_, ApiResult<Vec<EpicSlim>>>;
+
+    /// Stories in the given iterations owned by `user_id`
+    fn get_owned_iteration_stories(
+        &self,
+        iteration_ids: Vec<i32>,
+        known: Vec<Story>,
+    ) -> BoxFuture<'_, ApiResult<Vec<Story>>>;
+
+    /// Every story in the given iterations, whoever owns it
+    fn get_iteration_stories(
+        &self,
+        iteration_ids: Vec<i32>,
+        known: Vec<Story>,
+    ) -> BoxFuture<'_, ApiResult<Vec<Story>>>;
+
+    fn get_story(&self, story_id: i32) -> BoxFuture<'_, ApiResult<Story>>;
+
+    /// Every unarchived story owned by `user_id`
+    fn get_active_owned_stories(&self, known: Vec<Story>) -> BoxFuture<'_, ApiResult<Vec<Story>>>;
+
+    fn get_groups(&self) -> BoxFuture<'_, ApiResult<Vec<Group>>>;
+
+    /// Every unarchived story assigned to the group
+    fn get_group_stories(
+        &self,
+        group_id: Uuid,
+        known: Vec<Story>,
+    ) -> BoxFuture<'_, ApiResult<Vec<Story>>>;
+
+    fn search_stories<'a>(&'a self, query: &'a str) -> BoxFuture<'a, ApiResult<Vec<Story>>>;
+
+    fn update_story_description(
+        &self,
+        story_id: i32,
+        new_description: String,
+    ) -> BoxFuture<'_, ApiResult<()>>;
+
+    fn update_story_state(
+        &self,
+        story_id: i32,
+        workflow_state_id: i32,
+    ) -> BoxFuture<'_, ApiResult<()>>;
+
+    fn create_story(&self, story: NewStory) -> BoxFuture<'_, ApiResult<Story>>;
+
+    /// Changes only the fields set in `update`, returning the updated story
+    fn update_story(
+        &self,
+        story_id: i32,
+        update: StoryUpdate,
+    ) -> BoxFuture<'_, ApiResult<Story>>;
+
+    fn create_story_comment(
+        &self,
+        story_id: i32,
+        text: String,
+        parent_id: Option<i32>,
+    ) -> BoxFuture<'_, ApiResult<StoryComment>>;
+
+    fn update_story_comment(
+        &self,
+        story_id: i32,
+        comment_id: i32,
+        text: String,
+    ) -> BoxFuture<'_, ApiResult<StoryComment>>;
+
+    fn create_story_task(
+        &self,
+        story_id: i32,
+        description: String,
+    ) -> BoxFuture<'_, ApiResult<StoryTask>>;
+
+    fn update_story_task(
+        &self,
+        story_id: i32,
+        task_id: i32,
+        update: TaskUpdate,
+    ) -> BoxFuture<'_, ApiResult<StoryTask>>;
+
+    fn delete_story_task(&self, story_id: i32, task_id: i32) -> BoxFuture<'_, ApiResult<()>>;
+}
+
+/// Which backend to build on startup
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BackendSource {
+    Shortcut { recording: Option<Recording> },
+    /// Seeded from a JSON/YAML fixture, or the built in dummy data when `None`
+    Fake { fixture: Option<PathBuf> },
+}
+
+impl BackendSource {
+    pub fn is_fake(&self) -> bool {
+        matches!(self, BackendSource::Fake { .. })
+    }
+
+    /// Where to keep the cache, todos and outbox. Fake data gets a scratch dir, in the real
+    /// cache its nil user id would stop the next real run from looking ours up.
+    pub fn cache_dir(&self, configured: &Path) -> PathBuf {
+        if self.is_fake() {
+            env::temp_dir().join("shortcut-notes-fake")
+        } else {
+            configured.to_path_buf()
+        }
+    }
+}
+
+impl ShortcutBackend for ApiClient {
+    fn user_id(&self) -> Uuid {
+        self.user_id
+    }
+
+    fn get_user_id_from_api(&self) -> BoxFuture<'_, ApiResult<Uuid>> {
+        Box::pin(ApiClient::get_user_id_from_api(self))
+    }
+
+    fn get_members(&self) -> BoxFuture<'_, ApiResult<Vec<WorkspaceMember>>> {
+        Box::pin(ApiClient::get_members(self))
+    }
+
+    fn get_workflows(&self) -> BoxFuture<'_, ApiResult<Vec<Workflow>>> {
+        Box::pin(ApiClient::get_workflows(self))
+    }
+
+    fn get_current_iterations(
+        &self,
+        known: Vec<Iteration>,
+    ) -> BoxFuture<'_, ApiResult<Vec<Iteration>>> {
+        Box::pin(ApiClient::get_current_iterations(self, known))
+    }
+
+    fn get_all_iterations(
+        &self,
+        known: Vec<Iteration>,
+    ) -> BoxFuture<'_, ApiResult<Vec<Iteration>>> {
+        Box::pin(ApiClient::get_all_iterations(self, known))
+    }
+
+    fn get_all_epics_slim(
+        &self,
+        include_description: bool,
+    ) -> BoxFuture<'_, ApiResult<Vec<EpicSlim>>> {
+        Box::pin(ApiClient::get_all_epics_slim(self, include_description))
+    }
+
+    fn get_owned_iteration_stories(
+        &self,
+        iteration_ids: Vec<i32>,
+        known: Vec<Story>,
+    ) -> BoxFuture<'_, ApiResult<Vec<Story>>> {
+        Box::pin(ApiClient::get_owned_iteration_stories(self, iteration_ids, known))
+    }
+
+    fn get_iteration_stories(
+        &self,
+        iteration_ids: Vec<i32>,
+        known: Vec<Story>,
+    ) -> BoxFuture<'_, ApiResult<Vec<Story>>> {
+        Box::pin(ApiClient::get_iteration_stories(self, iteration_ids, known))
+    }
+
+    fn get_story(&self, story_id: i32) -> BoxFuture<'_, ApiResult<Story>> {
+        Box::pin(ApiClient::get_story(self, story_id))
+    }
+
+    fn get_active_owned_stories(&self, known: Vec<Story>) -> BoxFuture<'_, ApiResult<Vec<Story>>> {
+        Box::pin(async move { ApiClient::get_active_owned_stories(self, &known).await })
+    }
+
+    fn get_groups(&self) -> BoxFuture<'_, ApiResult<Vec<Group>>> {
+        Box::pin(ApiClient::get_groups(self))
+    }
+
+    fn get_group_stories(
+        &self,
+        group_id: Uuid,
+        known: Vec<Story>,
+    ) -> BoxFuture<'_, ApiResult<Vec<Story>>> {
+        Box::pin(ApiClient::get_group_stories(self, group_id, known))
+    }
+
+    fn search_stories<'a>(&'a self, query: &'a str) -> BoxFuture<'a, ApiResult<Vec<Story>>> {
+        Box::pin(ApiClient::search_stories(self, query))
+    }
+
+    fn update_story_description(
+        &self,
+        story_id: i32,
+        new_description: String,
+    ) -> BoxFuture<'_, ApiResult<()>> {
+        Box::pin(ApiClient::update_story_description(
+            self,
+            story_id,
+            new_description,
+        ))
+    }
+
+    fn update_story_state(
+        &self,
+        story_id: i32,
+        workflow_state_id: i32,
+    ) -> BoxFuture<'_, ApiResult<()>> {
+        Box::pin(ApiClient::update_story_state(
+            self,
+            story_id,
+            workflow_state_id,
+        ))
+    }
+
+    fn create_story(&self, story: NewStory) -> BoxFuture<'_, ApiResult<Story>> {
+        Box::pin(async move { ApiClient::create_story(self, &story).await })
+    }
+
+    fn update_story(
+        &self,
+        story_id: i32,
+        update: StoryUpdate,
+    ) -> BoxFuture<'_, ApiResult<Story>> {
+        Box::pin(async move { ApiClient::update_story(self, story_id, &update).await })
+    }
+
+    fn create_story_comment(
+        &self,
+        story_id: i32,
+        text: String,
+        parent_id: Option<i32>,
+    ) -> BoxFuture<'_, ApiResult<StoryComment>> {
+        Box::pin(ApiClient::create_story_comment(
+            self, story_id, text, parent_id,
+        ))
+    }
+
+    fn update_story_comment(
+        &self,
+        story_id: i32,
+        comment_id: i32,
+        text: String,
+    ) -> BoxFuture<'_, ApiResult<StoryComment>> {
+        Box::pin(ApiClient::update_story_comment(
+            self, story_id, comment_id, text,
+        ))
+    }
+
+    fn create_story_task(
+        &self,
+        story_id: i32,
+        description: String,
+    ) -> BoxFuture<'_, ApiResult<StoryTask>> {
+        Box::pin(ApiClient::create_story_task(self, story_id, description))
+    }
+
+    fn update_story_task(
+        &self,
+        story_id: i32,
+        task_id: i32,
+        update: TaskUpdate,
+    ) -> BoxFuture<'_, ApiResult<StoryTask>> {
+        Box::pin(async move {
+            ApiClient::update_story_task(self, story_id, task_id, &update).await
+        })
+    }
+
+    fn delete_story_task(&self, story_id: i32, task_id: i32) -> BoxFuture<'_, ApiResult<()>> {
+        Box::pin(ApiClient::delete_story_task(self, story_id, task_id))
+    }
+}
@@ -2,6 +2,6 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
 pub struct Branch {
-    id: i32,
-    name: String,
+    pub id: i32,
+    pub name: String,
 }
@@ -1,9 +1,15 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::{api::ApiClient, custom_list::LinearListItem};
-
-#[derive(Deserialize, Serialize, Debug, Clone)]
+use crate::{
+    api::{
+        ApiClient,
+        error::{ApiResult, decode},
+    },
+    custom_list::LinearListItem,
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct EpicSlim {
     pub id: i32,
     pub name: String,
@@ -29,18 +35,15 @@ impl LinearListItem for EpicSlim {
 }
 
 impl ApiClient {
-    pub async fn get_all_epics_slim(&self, include_description: bool) -> anyhow::Result<Vec<EpicSlim>> {
+    pub async fn get_all_epics_slim(&self, include_description: bool) -> ApiResult<Vec<EpicSlim>> {
         let body = serde_json::json!({
             "includes_description": include_description
         });
 
-        let response = self.get_with_body("epics", &body).await?;
-        let epics_slim = response.json::<Vec<EpicSlim>>().await?;
-
-        Ok(epics_slim)
+        decode(self.get_with_body("epics", &body).await?).await
     }
 
-    pub async fn get_owned_epics(&self) -> anyhow::Result<Vec<Epic>> {
+    pub async fn get_owned_epics(&self) -> ApiResult<Vec<Epic>> {
         let epics_slim = self.get_all_epics_slim(false).await?;
 
         let owned_slim = epics_slim
@@ -50,8 +53,7 @@ impl ApiClient {
 
         let mut epics = Vec::new();
         for epic in owned_slim.iter().take(2) {
-            let response = self.get(&format!("epics/{}", epic.id)).await?;
-            let epic = response.json::<Epic>().await?;
+            let epic = decode(self.get(&format!("epics/{}", epic.id)).await?).await?;
             epics.push(epic);
         }
 
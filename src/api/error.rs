@@ -0,0 +1,166 @@
+use std::{fmt, time::Duration};
+
+use reqwest::{Response, StatusCode, header::RETRY_AFTER};
+use serde::de::DeserializeOwned;
+
+pub type ApiResult<T> = Result<T, ApiError>;
+
+/// Why a request to the Shortcut API failed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ApiError {
+    /// The API token is missing, invalid or revoked
+    Unauthorized,
+    NotFound,
+    RateLimited { retry_after: Option<Duration> },
+    Server { status: StatusCode },
+    /// Any other non-success status, e.g. a validation error on a 400 or 422
+    Rejected { status: StatusCode, message: String },
+    /// The response body wasn't what we expected
+    Decode(String),
+    /// The request never got a response
+    Network(String),
+}
+
+impl ApiError {
+    pub fn from_status(status: StatusCode, retry_after: Option<Duration>, body: &str) -> Self {
+        match status {
+            StatusCode::UNAUTHORIZED => ApiError::Unauthorized,
+            StatusCode::NOT_FOUND => ApiError::NotFound,
+            StatusCode::TOO_MANY_REQUESTS => ApiError::RateLimited { retry_after },
+            s if s.is_server_error() => ApiError::Server { status },
+            _ => ApiError::Rejected {
+                status,
+                message: error_message(body),
+            },
+        }
+    }
+}
+
+/// Shortcut error bodies look like `{"message": "...", ...}`, fall back to the raw body
+fn error_message(body: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|v| v.get("message")?.as_str().map(str::to_string))
+        .unwrap_or_else(|| body.trim().to_string())
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::Unauthorized => write!(f, "Shortcut rejected the API token"),
+            ApiError::NotFound => write!(f, "Not found in Shortcut"),
+            ApiError::RateLimited {
+                retry_after: Some(after),
+            } => write!(
+                f,
+                "Rate limited by Shortcut, retry in {}s",
+                after.as_secs()
+            ),
+            ApiError::RateLimited { retry_after: None } => write!(f, "Rate limited by Shortcut"),
+            ApiError::Server { status } => write!(f, "Shortcut server error ({})", status),
+            ApiError::Rejected { status, message } => {
+                write!(f, "Request rejected ({}): {}", status, message)
+            }
+            ApiError::Decode(e) => write!(f, "Unexpected response from Shortcut: {}", e),
+            ApiError::Network(e) => write!(f, "Network error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl From<reqwest::Error> for ApiError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_decode() {
+            ApiError::Decode(e.to_string())
+        } else {
+            ApiError::Network(e.to_string())
+        }
+    }
+}
+
+/// Parses a `Retry-After` header given in seconds
+pub(crate) fn retry_after(response: &Response) -> Option<Duration> {
+    parse_retry_after(response.headers().get(RETRY_AFTER)?.to_str().ok()?)
+}
+
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    value.trim().parse::<u64>().ok().map(Duration::from_secs)
+}
+
+/// Turns non-success responses into the matching `ApiError`
+pub(crate) async fn check_status(response: Response) -> ApiResult<Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let retry_after = retry_after(&response);
+    let body = response.text().await.unwrap_or_default();
+    Err(ApiError::from_status(status, retry_after, &body))
+}
+
+/// Reads the body of a successful response as JSON
+pub(crate) async fn decode<T: DeserializeOwned>(response: Response) -> ApiResult<T> {
+    let bytes = response.bytes().await?;
+    serde_json::from_slice(&bytes).map_err(|e| ApiError::Decode(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_codes_map_to_error_kinds() {
+        assert_eq!(
+            ApiError::from_status(StatusCode::UNAUTHORIZED, None, ""),
+            ApiError::Unauthorized
+        );
+        // the token is fine, it just can't do this
+        assert_eq!(
+            ApiError::from_status(StatusCode::FORBIDDEN, None, r#"{"message": "No access"}"#),
+            ApiError::Rejected {
+                status: StatusCode::FORBIDDEN,
+                message: "No access".to_string(),
+            }
+        );
+        assert_eq!(
+            ApiError::from_status(StatusCode::NOT_FOUND, None, ""),
+            ApiError::NotFound
+        );
+        assert_eq!(
+            ApiError::from_status(
+                StatusCode::TOO_MANY_REQUESTS,
+                Some(Duration::from_secs(3)),
+                ""
+            ),
+            ApiError::RateLimited {
+                retry_after: Some(Duration::from_secs(3))
+            }
+        );
+        assert_eq!(
+            ApiError::from_status(StatusCode::BAD_GATEWAY, None, ""),
+            ApiError::Server {
+                status: StatusCode::BAD_GATEWAY
+            }
+        );
+    }
+
+    #[test]
+    fn rejected_error_uses_shortcut_message() {
+        let body = r#"{"message": "Name can't be blank", "errors": {}}"#;
+        assert_eq!(
+            ApiError::from_status(StatusCode::UNPROCESSABLE_ENTITY, None, body),
+            ApiError::Rejected {
+                status: StatusCode::UNPROCESSABLE_ENTITY,
+                message: "Name can't be blank".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn retry_after_parses_seconds() {
+        assert_eq!(parse_retry_after(" 12 "), Some(Duration::from_secs(12)));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), None);
+    }
+}
@@ -0,0 +1,523 @@
+use std::{path::Path, sync::Mutex};
+
+use anyhow::Context;
+use futures::future::{self, BoxFuture};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::api::{
+    backend::ShortcutBackend,
+    epic::EpicSlim,
+    error::{ApiError, ApiResult},
+    group::Group,
+    iteration::Iteration,
+    story::{
+        NewStory, Story, StoryUpdate,
+        comment::StoryComment,
+        task::{StoryTask, TaskUpdate},
+    },
+    user::WorkspaceMember,
+    workflow::{Workflow, WorkflowStateType, get_workflow},
+};
+
+/// Workspace contents a `FakeBackend` starts out with, loaded from a JSON or YAML file.
+/// Every field except `user_id` is optional.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct Fixture {
+    /// The member the app runs as, stories are "owned" when their `owner_ids` contain it
+    pub user_id: Uuid,
+    #[serde(default)]
+    pub members: Vec<WorkspaceMember>,
+    #[serde(default)]
+    pub workflows: Vec<Workflow>,
+    #[serde(default)]
+    pub groups: Vec<Group>,
+    #[serde(default)]
+    pub iterations: Vec<Iteration>,
+    #[serde(default)]
+    pub epics: Vec<EpicSlim>,
+    #[serde(default)]
+    pub stories: Vec<Story>,
+}
+
+impl Fixture {
+    /// `.yaml`/`.yml` files are read as YAML, anything else as JSON
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read fixture {}", path.display()))?;
+
+        let is_yaml = path
+            .extension()
+            .is_some_and(|ext| ext == "yaml" || ext == "yml");
+
+        if is_yaml {
+            serde_yaml::from_str(&contents)
+                .with_context(|| format!("Invalid YAML fixture {}", path.display()))
+        } else {
+            serde_json::from_str(&contents)
+                .with_context(|| format!("Invalid JSON fixture {}", path.display()))
+        }
+    }
+}
+
+/// Sets `blocked` and `blocker` the way Shortcut does, only open stories block anything
+fn update_blocker_flags(stories: &mut [Story]) {
+    let open: Vec<i32> = stories.iter().filter(|s| !s.completed).map(|s| s.id).collect();
+    for story in stories.iter_mut() {
+        let is_open = open.contains(&story.id);
+        story.blocked = story
+            .story_links
+            .iter()
+            .any(|l| l.is_blocked_by() && open.contains(&l.other_story_id()));
+        story.blocker = is_open && story.story_links.iter().any(|l| l.is_blocking());
+    }
+}
+
+/// An in-memory Shortcut workspace. Reads are served from the fixture and mutations are
+/// applied to it, so the whole app works without a network.
+pub struct FakeBackend {
+    data: Mutex<Fixture>,
+}
+
+impl FakeBackend {
+    pub fn new(mut fixture: Fixture) -> Self {
+        update_blocker_flags(&mut fixture.stories);
+        Self {
+            data: Mutex::new(fixture),
+        }
+    }
+
+    /// A copy of the current workspace contents, mutations included
+    pub fn snapshot(&self) -> Fixture {
+        self.data().clone()
+    }
+
+    fn data(&self) -> std::sync::MutexGuard<'_, Fixture> {
+        // a panic while holding the lock can't leave the fixture half updated
+        self.data.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn with_story<T>(
+        &self,
+        story_id: i32,
+        f: impl FnOnce(&mut Story, &Fixture) -> ApiResult<T>,
+    ) -> ApiResult<T> {
+        let mut data = self.data();
+        let mut stories = std::mem::take(&mut data.stories);
+
+        let result = match stories.iter_mut().find(|s| s.id == story_id) {
+            Some(story) => {
+                let result = f(story, &data);
+                if result.is_ok() {
+                    story.updated_at = Some(chrono::Utc::now());
+                }
+                result
+            }
+            None => Err(ApiError::NotFound),
+        };
+
+        data.stories = stories;
+        result
+    }
+
+    fn get_story_now(&self, story_id: i32) -> ApiResult<Story> {
+        let story = self.data().stories.iter().find(|s| s.id == story_id).cloned();
+        story.ok_or(ApiError::NotFound)
+    }
+
+    fn owned_stories(&self, filter: impl Fn(&Story) -> bool) -> Vec<Story> {
+        let data = self.data();
+        data.stories
+            .iter()
+            .filter(|s| s.owner_ids.contains(&data.user_id) && filter(s))
+            .cloned()
+            .collect()
+    }
+}
+
+fn ready<'a, T: Send + 'a>(value: ApiResult<T>) -> BoxFuture<'a, ApiResult<T>> {
+    Box::pin(future::ready(value))
+}
+
+/// Rough stand-in for Shortcut's search language: every word has to appear in the story's
+/// name or description
+fn matches_query(story: &Story, query: &str) -> bool {
+    let haystack = format!("{} {}", story.name, story.description).to_lowercase();
+    query
+        .split_whitespace()
+        .all(|word| haystack.contains(&word.to_lowercase()))
+}
+
+impl ShortcutBackend for FakeBackend {
+    fn user_id(&self) -> Uuid {
+        self.data().user_id
+    }
+
+    fn get_user_id_from_api(&self) -> BoxFuture<'_, ApiResult<Uuid>> {
+        ready(Ok(self.user_id()))
+    }
+
+    fn get_members(&self) -> BoxFuture<'_, ApiResult<Vec<WorkspaceMember>>> {
+        ready(Ok(self.data().members.clone()))
+    }
+
+    fn get_workflows(&self) -> BoxFuture<'_, ApiResult<Vec<Workflow>>> {
+        ready(Ok(self.data().workflows.clone()))
+    }
+
+    fn get_current_iterations(
+        &self,
+        _known: Vec<Iteration>,
+    ) -> BoxFuture<'_, ApiResult<Vec<Iteration>>> {
+        let today = crate::time::today();
+        let iterations = self
+            .data()
+            .iterations
+            .iter()
+            .filter(|it| it.start_date <= today && it.end_date >= today)
+            .cloned()
+            .collect();
+
+        ready(Ok(iterations))
+    }
+
+    fn get_all_iterations(
+        &self,
+        _known: Vec<Iteration>,
+    ) -> BoxFuture<'_, ApiResult<Vec<Iteration>>> {
+        ready(Ok(self.data().iterations.clone()))
+    }
+
+    fn get_all_epics_slim(
+        &self,
+        _include_description: bool,
+    ) -> BoxFuture<'_, ApiResult<Vec<EpicSlim>>> {
+        ready(Ok(self.data().epics.clone()))
+    }
+
+    fn get_owned_iteration_stories(
+        &self,
+        iteration_ids: Vec<i32>,
+        _known: Vec<Story>,
+    ) -> BoxFuture<'_, ApiResult<Vec<Story>>> {
+        let stories = self.owned_stories(|s| {
+            s.iteration_id
+                .is_some_and(|id| iteration_ids.contains(&id))
+        });
+
+        ready(Ok(stories))
+    }
+
+    fn get_iteration_stories(
+        &self,
+        iteration_ids: Vec<i32>,
+        _known: Vec<Story>,
+    ) -> BoxFuture<'_, ApiResult<Vec<Story>>> {
+        let stories = self
+            .data()
+            .stories
+            .iter()
+            .filter(|s| s.iteration_id.is_some_and(|id| iteration_ids.contains(&id)))
+            .cloned()
+            .collect();
+
+        ready(Ok(stories))
+    }
+
+    fn get_story(&self, story_id: i32) -> BoxFuture<'_, ApiResult<Story>> {
+        ready(self.get_story_now(story_id))
+    }
+
+    fn get_active_owned_stories(&self, _known: Vec<Story>) -> BoxFuture<'_, ApiResult<Vec<Story>>> {
+        ready(Ok(self.owned_stories(|_| true)))
+    }
+
+    fn get_groups(&self) -> BoxFuture<'_, ApiResult<Vec<Group>>> {
+        ready(Ok(self.data().groups.clone()))
+    }
+
+    fn get_group_stories(
+        &self,
+        group_id: Uuid,
+        _known: Vec<Story>,
+    ) -> BoxFuture<'_, ApiResult<Vec<Story>>> {
+        let data = self.data();
+        if !data.groups.iter().any(|g| g.id == group_id) {
+            return ready(Err(ApiError::NotFound));
+        }
+
+        let stories = data
+            .stories
+            .iter()
+            .filter(|s| s.group_id == Some(group_id))
+            .cloned()
+            .collect();
+
+        ready(Ok(stories))
+    }
+
+    fn search_stories<'a>(&'a self, query: &'a str) -> BoxFuture<'a, ApiResult<Vec<Story>>> {
+        let stories = self
+            .data()
+            .stories
+            .iter()
+            .filter(|s| matches_query(s, query))
+            .cloned()
+            .collect();
+
+        ready(Ok(stories))
+    }
+
+    fn update_story_description(
+        &self,
+        story_id: i32,
+        new_description: String,
+    ) -> BoxFuture<'_, ApiResult<()>> {
+        ready(self.with_story(story_id, |story, _| {
+            story.description = new_description;
+            Ok(())
+        }))
+    }
+
+    fn update_story_state(
+        &self,
+        story_id: i32,
+        workflow_state_id: i32,
+    ) -> BoxFuture<'_, ApiResult<()>> {
+        let updated = self.with_story(story_id, |story, data| {
+            let state = get_workflow(story.workflow_id, &data.workflows)
+                .and_then(|w| w.states.iter().find(|s| s.id == workflow_state_id))
+                .ok_or_else(|| ApiError::Rejected {
+                    status: reqwest::StatusCode::UNPROCESSABLE_ENTITY,
+                    message: format!("Unknown workflow state {}", workflow_state_id),
+                })?;
+
+            story.workflow_state_id = state.id;
+            let completed = state.state_type == WorkflowStateType::Done;
+            if completed != story.completed {
+                story.completed_at = completed.then(chrono::Utc::now);
+            }
+            story.completed = completed;
+            Ok(())
+        });
+        if updated.is_ok() {
+            update_blocker_flags(&mut self.data().stories);
+        }
+        ready(updated)
+    }
+
+    fn create_story(&self, new: NewStory) -> BoxFuture<'_, ApiResult<Story>> {
+        let mut data = self.data();
+
+        // new stories start in the first state of the first workflow, like Shortcut's default
+        let Some((workflow_id, workflow_state_id)) = data.workflows.first().and_then(|w| {
+            let state = w.ordered_states().first()?.id;
+            Some((w.id, state))
+        }) else {
+            return ready(Err(ApiError::Rejected {
+                status: reqwest::StatusCode::UNPROCESSABLE_ENTITY,
+                message: "The workspace has no workflows".to_string(),
+            }));
+        };
+
+        let id = data.stories.iter().map(|s| s.id).max().unwrap_or(0) + 1;
+        let story = Story {
+            branches: vec![],
+            completed: false,
+            comments: vec![],
+            tasks: vec![],
+            story_links: vec![],
+            blocked: false,
+            blocker: false,
+            completed_at: None,
+            description: new.description,
+            epic_id: new.epic_id,
+            id,
+            iteration_id: new.iteration_id,
+            name: new.name,
+            owner_ids: new.owner_ids,
+            group_id: None,
+            updated_at: Some(chrono::Utc::now()),
+            estimate: new.estimate,
+            labels: new.labels,
+            deadline: new.deadline,
+            app_url: format!("https://app.shortcut.com/example/story/{}", id),
+            workflow_id,
+            workflow_state_id,
+            story_type: new.story_type,
+        };
+
+        data.stories.push(story.clone());
+        ready(Ok(story))
+    }
+
+    fn update_story(
+        &self,
+        story_id: i32,
+        update: StoryUpdate,
+    ) -> BoxFuture<'_, ApiResult<Story>> {
+        let result = self.with_story(story_id, |story, _| {
+            update.apply(story);
+            Ok(())
+        });
+        ready(result.and_then(|()| self.get_story_now(story_id)))
+    }
+
+    fn create_story_comment(
+        &self,
+        story_id: i32,
+        text: String,
+        parent_id: Option<i32>,
+    ) -> BoxFuture<'_, ApiResult<StoryComment>> {
+        let author_id = self.user_id();
+        let next_id = {
+            let data = self.data();
+            data.stories
+                .iter()
+                .flat_map(|s| &s.comments)
+                .map(|c| c.id)
+                .max()
+                .unwrap_or(0)
+                + 1
+        };
+
+        ready(self.with_story(story_id, |story, _| {
+            let comment = StoryComment {
+                id: next_id,
+                author_id,
+                deleted: false,
+                position: story.comments.iter().map(|c| c.position).max().unwrap_or(0) + 1,
+                text: Some(text),
+                parent_id,
+                created_at: chrono::Utc::now(),
+            };
+            story.comments.push(comment.clone());
+            Ok(comment)
+        }))
+    }
+
+    fn update_story_comment(
+        &self,
+        story_id: i32,
+        comment_id: i32,
+        text: String,
+    ) -> BoxFuture<'_, ApiResult<StoryComment>> {
+        ready(self.with_story(story_id, |story, _| {
+            let comment = story
+                .comments
+                .iter_mut()
+                .find(|c| c.id == comment_id)
+                .ok_or(ApiError::NotFound)?;
+
+            comment.text = Some(text);
+            Ok(comment.clone())
+        }))
+    }
+
+    fn create_story_task(
+        &self,
+        story_id: i32,
+        description: String,
+    ) -> BoxFuture<'_, ApiResult<StoryTask>> {
+        let next_id = {
+            let data = self.data();
+            data.stories
+                .iter()
+                .flat_map(|s| &s.tasks)
+                .map(|t| t.id)
+                .max()
+                .unwrap_or(0)
+                + 1
+        };
+
+        ready(self.with_story(story_id, |story, _| {
+            let task = StoryTask {
+                id: next_id,
+                description,
+                complete: false,
+                position: story.tasks.iter().map(|t| t.position).max().unwrap_or(0) + 1,
+            };
+            story.tasks.push(task.clone());
+            Ok(task)
+        }))
+    }
+
+    fn update_story_task(
+        &self,
+        story_id: i32,
+        task_id: i32,
+        update: TaskUpdate,
+    ) -> BoxFuture<'_, ApiResult<StoryTask>> {
+        ready(self.with_story(story_id, |story, _| {
+            let task = story
+                .tasks
+                .iter_mut()
+                .find(|t| t.id == task_id)
+                .ok_or(ApiError::NotFound)?;
+
+            update.apply(task);
+            Ok(task.clone())
+        }))
+    }
+
+    fn delete_story_task(&self, story_id: i32, task_id: i32) -> BoxFuture<'_, ApiResult<()>> {
+        ready(self.with_story(story_id, |story, _| {
+            let before = story.tasks.len();
+            story.tasks.retain(|t| t.id != task_id);
+            if story.tasks.len() == before {
+                return Err(ApiError::NotFound);
+            }
+            Ok(())
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dummy;
+
+    #[tokio::test]
+    async fn mutations_are_visible_to_later_reads() {
+        let fake = FakeBackend::new(dummy::fixture());
+        let iteration_ids = vec![dummy::iteration().id];
+
+        fake.update_story_description(101, "Rewritten".to_string())
+            .await
+            .unwrap();
+        fake.update_story_state(101, 13).await.unwrap();
+        let comment = fake
+            .create_story_comment(101, "Done!".to_string(), None)
+            .await
+            .unwrap();
+
+        let stories = fake
+            .get_owned_iteration_stories(iteration_ids, Vec::new())
+            .await
+            .unwrap();
+        let story = stories.iter().find(|s| s.id == 101).unwrap();
+        assert_eq!(story.description, "Rewritten");
+        assert!(story.completed);
+        assert!(story.comments.contains(&comment));
+        assert_eq!(comment.author_id, fake.user_id());
+    }
+
+    #[tokio::test]
+    async fn unknown_story_is_not_found() {
+        let fake = FakeBackend::new(dummy::fixture());
+        assert_eq!(
+            fake.update_story_state(999, 13).await,
+            Err(ApiError::NotFound)
+        );
+    }
+
+    #[test]
+    fn fixtures_round_trip_through_yaml() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("workspace.yaml");
+        let fixture = dummy::fixture();
+        std::fs::write(&path, serde_yaml::to_string(&fixture).unwrap()).unwrap();
+
+        assert_eq!(Fixture::read(&path).unwrap(), fixture);
+    }
+}
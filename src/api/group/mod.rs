@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::api::{
+    ApiClient,
+    error::{ApiResult, decode},
+    story::{Story, StorySlim},
+};
+
+/// A Shortcut group, shown as a "team" in the Shortcut UI
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct Group {
+    pub id: Uuid,
+    pub name: String,
+    pub mention_name: String,
+    #[serde(default)]
+    pub archived: bool,
+}
+
+impl ApiClient {
+    pub async fn get_groups(&self) -> ApiResult<Vec<Group>> {
+        decode(self.get("groups").await?).await
+    }
+
+    /// Every unarchived story assigned to the group, whoever owns it. Stories in `known` are
+    /// reused unless they changed.
+    pub async fn get_group_stories(
+        &self,
+        group_id: Uuid,
+        known: Vec<Story>,
+    ) -> ApiResult<Vec<Story>> {
+        let response = self.get(&format!("groups/{}/stories", group_id)).await?;
+        let stories_slim: Vec<StorySlim> = decode(response).await?;
+        let listed: Vec<_> = stories_slim.into_iter().filter(|s| !s.archived).collect();
+
+        self.get_changed_stories(&listed, &known).await
+    }
+}
+
+/// Matches the configured group names, mention names or ids against the workspace's groups.
+/// Returns the matched group ids and the configured entries that matched nothing.
+pub fn resolve_groups(configured: &[String], groups: &[Group]) -> (Vec<Uuid>, Vec<String>) {
+    let mut ids = Vec::new();
+    let mut unknown = Vec::new();
+
+    for entry in configured {
+        let wanted = entry.trim().trim_start_matches('@');
+        let group = groups.iter().find(|g| {
+            g.name.eq_ignore_ascii_case(wanted)
+                || g.mention_name.eq_ignore_ascii_case(wanted)
+                || g.id.to_string() == wanted
+        });
+
+        match group {
+            Some(group) if !ids.contains(&group.id) => ids.push(group.id),
+            Some(_) => {}
+            None => unknown.push(entry.clone()),
+        }
+    }
+
+    (ids, unknown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_resolve_by_name_mention_or_id() {
+        let group = |n: u128, name: &str, mention_name: &str| Group {
+            id: Uuid::from_u128(n),
+            name: name.to_string(),
+            mention_name: mention_name.to_string(),
+            archived: false,
+        };
+        let groups = vec![group(1, "Platform Team", "platform"), group(2, "Mobile", "mobile")];
+
+        let configured = vec![
+            "platform team".to_string(),
+            "@mobile".to_string(),
+            Uuid::from_u128(1).to_string(),
+            "growth".to_string(),
+        ];
+
+        let (ids, unknown) = resolve_groups(&configured, &groups);
+        assert_eq!(ids, vec![Uuid::from_u128(1), Uuid::from_u128(2)]);
+        assert_eq!(unknown, vec!["growth".to_string()]);
+    }
+}
@@ -1,18 +1,18 @@
-use anyhow::Context;
-use chrono::NaiveDate;
-use futures::future::{join_all, try_join_all};
+use chrono::{DateTime, NaiveDate, Utc};
+use futures::future::try_join_all;
 use serde::{Deserialize, Serialize};
 
 use crate::{
     api::{
         ApiClient,
+        error::{ApiResult, decode},
         story::{Story, StorySlim},
+        versioned::{Versioned, in_listed_order, partition_changed},
     },
     custom_list::LinearListItem,
-    dbg_file,
 };
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
 pub struct Iteration {
     pub id: i32,
     pub name: String,
@@ -20,6 +20,8 @@ pub struct Iteration {
     pub start_date: NaiveDate,
     pub end_date: NaiveDate,
     pub app_url: String,
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Deserialize)]
@@ -27,6 +29,15 @@ pub struct IterationSlim {
     id: i32,
     start_date: NaiveDate,
     end_date: NaiveDate,
+    #[serde(default)]
+    updated_at: Option<DateTime<Utc>>,
+}
+
+impl Iteration {
+    /// Days left until the end date, counting today, 0 once it has ended
+    pub fn days_remaining(&self, today: NaiveDate) -> i64 {
+        ((self.end_date - today).num_days() + 1).max(0)
+    }
 }
 
 impl LinearListItem for Iteration {
@@ -34,98 +45,116 @@ impl LinearListItem for Iteration {
     fn label(&self) -> &str { &self.name }
 }
 
+impl Versioned for Iteration {
+    fn entity_id(&self) -> i32 {
+        self.id
+    }
+
+    fn updated_at(&self) -> Option<DateTime<Utc>> {
+        self.updated_at
+    }
+}
+
+impl Versioned for IterationSlim {
+    fn entity_id(&self) -> i32 {
+        self.id
+    }
+
+    fn updated_at(&self) -> Option<DateTime<Utc>> {
+        self.updated_at
+    }
+}
+
 impl ApiClient {
-    pub async fn get_current_iterations(&self) -> anyhow::Result<Vec<Iteration>> {
-        let response = self.get("iterations").await?;
-        let iterations_slim = response.json::<Vec<IterationSlim>>().await?;
+    /// `known` are iterations fetched earlier, they are reused unless Shortcut has a newer
+    /// `updated_at` for them
+    pub async fn get_current_iterations(
+        &self,
+        known: Vec<Iteration>,
+    ) -> ApiResult<Vec<Iteration>> {
+        let iterations_slim: Vec<IterationSlim> = decode(self.get("iterations").await?).await?;
         let today = crate::time::today();
-        let current_iteration_ids: Vec<_> = iterations_slim
-            .iter()
+        let current: Vec<_> = iterations_slim
+            .into_iter()
             .filter(|it| it.start_date <= today && it.end_date >= today)
-            .map(|it| it.id)
             .collect();
 
-        let iterations = join_all(current_iteration_ids.iter().map(|id| async move {
-            let response = self.get(&format!("iterations/{id}")).await?;
-            // add the context to cast response to an anyhow error, but we will filter out errors
-            // so don't need a real message
-            response.json::<Iteration>().await.context("")
-        }))
-        .await
-        .into_iter()
-        .filter_map(|res| res.ok())
-        .collect();
-
-        Ok(iterations)
+        self.get_changed_iterations(&current, &known).await
     }
 
-    pub async fn get_all_iterations(&self) -> anyhow::Result<Vec<Iteration>> {
-        let response = self.get("iterations").await?;
-        let iterations_slim = response.json::<Vec<IterationSlim>>().await?;
+    pub async fn get_all_iterations(&self, known: Vec<Iteration>) -> ApiResult<Vec<Iteration>> {
+        let iterations_slim: Vec<IterationSlim> = decode(self.get("iterations").await?).await?;
 
-        let iterations = join_all(iterations_slim.iter().map(|slim| async move {
-            let response = self.get(&format!("iterations/{}", slim.id)).await?;
-            response.json::<Iteration>().await.context("")
-        }))
-        .await
-        .into_iter()
-        .filter_map(|res| res.ok())
-        .collect();
+        self.get_changed_iterations(&iterations_slim, &known).await
+    }
+
+    async fn get_changed_iterations(
+        &self,
+        listed: &[IterationSlim],
+        known: &[Iteration],
+    ) -> ApiResult<Vec<Iteration>> {
+        let (mut iterations, changed) = partition_changed(listed, known);
+        iterations.extend(try_join_all(changed.iter().map(|id| self.get_iteration(*id))).await?);
 
-        Ok(iterations)
+        Ok(in_listed_order(listed, iterations))
     }
 
+    async fn get_iteration(&self, iteration_id: i32) -> ApiResult<Iteration> {
+        decode(self.get(&format!("iterations/{}", iteration_id)).await?).await
+    }
+
+    /// Our stories in the iterations, only fetching the full body of stories that aren't in
+    /// `known` or changed since
     pub async fn get_owned_iteration_stories(
         &self,
         iteration_ids: Vec<i32>,
-    ) -> anyhow::Result<Vec<Story>> {
-        let iteration_stories = join_all(
+        known: Vec<Story>,
+    ) -> ApiResult<Vec<Story>> {
+        self.get_iteration_stories_filtered(iteration_ids, true, &known)
+            .await
+    }
+
+    /// Every story in the iterations, whoever owns it
+    pub async fn get_iteration_stories(
+        &self,
+        iteration_ids: Vec<i32>,
+        known: Vec<Story>,
+    ) -> ApiResult<Vec<Story>> {
+        self.get_iteration_stories_filtered(iteration_ids, false, &known)
+            .await
+    }
+
+    async fn get_iteration_stories_filtered(
+        &self,
+        iteration_ids: Vec<i32>,
+        owned_only: bool,
+        known: &[Story],
+    ) -> ApiResult<Vec<Story>> {
+        let iteration_stories = try_join_all(
             iteration_ids
                 .iter()
-                .map(|id| async { self.get_owned_single_iteration_stories(*id).await }),
+                .map(|id| self.get_single_iteration_stories(*id, owned_only, known)),
         )
-        .await
-        .into_iter()
-        .filter_map(|res| match res {
-            Ok(stories) => Some(stories),
-            Err(e) => {
-                dbg_file!("Failed to fetch story with error: {}", e);
-                None
-            }
-        })
-        .flatten()
-        .collect();
-
-        Ok(iteration_stories)
+        .await?;
+
+        Ok(iteration_stories.into_iter().flatten().collect())
     }
 
-    async fn get_owned_single_iteration_stories(
+    async fn get_single_iteration_stories(
         &self,
         iteration_id: i32,
-    ) -> anyhow::Result<Vec<Story>> {
+        owned_only: bool,
+        known: &[Story],
+    ) -> ApiResult<Vec<Story>> {
         let response = self
             .get(&format!("iterations/{}/stories", iteration_id))
             .await?;
-        let stories_slim = response.json::<Vec<StorySlim>>().await?;
-        let slim_owned: Vec<_> = stories_slim
-            .iter()
-            .filter(|s| s.owner_ids.contains(&self.user_id))
+        let stories_slim: Vec<StorySlim> = decode(response).await?;
+        let listed: Vec<_> = stories_slim
+            .into_iter()
+            .filter(|s| !owned_only || s.owner_ids.contains(&self.user_id))
             .collect();
 
-        let stories = {
-            let len = slim_owned.len();
-            let futures = slim_owned.into_iter().take(len).map(|slim| async move {
-                let query = format!("stories/{}", slim.id);
-                let response = self.get(&query).await?;
-                response
-                    .json::<Story>()
-                    .await
-                    .context("Failed to parse as Story")
-            });
-
-            try_join_all(futures).await?
-        };
-
-        Ok(stories)
+        self.get_changed_stories(&listed, known).await
     }
 }
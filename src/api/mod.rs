@@ -1,76 +1,108 @@
-use anyhow::Context;
 use reqwest::{Client, RequestBuilder, Response};
 use serde::Serialize;
 use uuid::Uuid;
 
+use crate::api::{
+    error::{ApiError, ApiResult, check_status},
+    recording::Recording,
+    request::RequestLimiter,
+};
+
+pub mod backend;
 pub mod branch;
 pub mod epic;
+pub mod error;
+pub mod fake;
+pub mod group;
 pub mod iteration;
+pub mod label;
+pub mod recording;
+mod request;
 pub mod story;
 pub mod user;
+pub mod versioned;
+pub mod workflow;
 
+/// Default for the `api_base_url` config option
 pub const API_BASE_URL: &str = "https://api.app.shortcut.com/api/v3";
 
 #[derive(Clone)]
 pub struct ApiClient {
     api_token: String,
+    base_url: String,
     pub user_id: Uuid,
     http_client: Client,
-}
-
-pub fn get_full_path(endpoint: &str) -> String {
-    // endpoint should not start with / as we append it when formatting
-    assert!(!endpoint.starts_with("/"));
-    format!("{}/{}", API_BASE_URL, endpoint)
+    limiter: RequestLimiter,
+    recording: Option<Recording>,
 }
 
 impl ApiClient {
-    async fn put_with_body<Body>(&self, endpoint: &str, body: &Body) -> anyhow::Result<Response>
+    fn get_full_path(&self, endpoint: &str) -> String {
+        // endpoint should not start with / as we append it when formatting
+        assert!(!endpoint.starts_with("/"));
+        format!("{}/{}", self.base_url.trim_end_matches('/'), endpoint)
+    }
+
+    /// Every request goes through here so it is rate limited, retried, recorded or replayed,
+    /// and has its status checked before the body is read
+    async fn send(&self, request: RequestBuilder) -> ApiResult<Response> {
+        let Some(recording) = &self.recording else {
+            return check_status(self.limiter.send(request).await?).await;
+        };
+
+        let built = request
+            .try_clone()
+            .ok_or_else(|| ApiError::Network("Request body can't be recorded".to_string()))?
+            .build()?;
+
+        let response = match recording {
+            Recording::Replay(_) => recording.replay(&built, &self.base_url).await?,
+            Recording::Record(_) => {
+                let response = self.limiter.send(request).await?;
+                recording.record(&built, &self.base_url, response).await?
+            }
+        };
+
+        check_status(response).await
+    }
+
+    async fn put_with_body<Body>(&self, endpoint: &str, body: &Body) -> ApiResult<Response>
     where
         Body: Serialize,
     {
-        let full_path = get_full_path(endpoint);
-        self.put_request(&full_path)
-            .json(&body)
-            .send()
-            .await
-            .with_context(|| format!("Failed to PUT {} with body", &full_path))
+        let full_path = self.get_full_path(endpoint);
+        self.send(self.put_request(&full_path).json(&body)).await
     }
 
-    async fn post_with_body<Body>(
-        &self,
-        endpoint: &str,
-        body: &Body,
-    ) -> anyhow::Result<Response>
+    async fn post_with_body<Body>(&self, endpoint: &str, body: &Body) -> ApiResult<Response>
     where
         Body: Serialize,
     {
-        let full_path = get_full_path(endpoint);
-        self.post_request(&full_path)
-            .json(&body)
-            .send()
-            .await
-            .with_context(|| format!("Failed to POST {} with body", &full_path))
+        let full_path = self.get_full_path(endpoint);
+        self.send(self.post_request(&full_path).json(&body)).await
     }
 
-    async fn get_with_body<Body>(&self, endpoint: &str, body: &Body) -> anyhow::Result<Response>
+    async fn get_with_body<Body>(&self, endpoint: &str, body: &Body) -> ApiResult<Response>
     where
         Body: Serialize,
     {
-        let full_path = get_full_path(endpoint);
-        self.get_request(&full_path)
-            .json(&body)
-            .send()
-            .await
-            .with_context(|| format!("Failed to GET {} with body", &full_path))
+        let full_path = self.get_full_path(endpoint);
+        self.send(self.get_request(&full_path).json(&body)).await
     }
 
-    async fn get(&self, endpoint: &str) -> anyhow::Result<Response> {
-        let full_path = get_full_path(endpoint);
-        self.get_request(&full_path)
-            .send()
-            .await
-            .with_context(|| format!("Failed to GET {}", &full_path))
+    async fn delete(&self, endpoint: &str) -> ApiResult<Response> {
+        let full_path = self.get_full_path(endpoint);
+        self.send(self.delete_request(&full_path)).await
+    }
+
+    async fn get(&self, endpoint: &str) -> ApiResult<Response> {
+        let full_path = self.get_full_path(endpoint);
+        self.get_url(&full_path).await
+    }
+
+    /// GET an already fully formed url, e.g. one carrying query parameters
+    async fn get_url(&self, url: &str) -> ApiResult<Response> {
+        self.send(self.get_request(url)).await
     }
 
     fn get_request(&self, path: &str) -> RequestBuilder {
@@ -94,11 +126,25 @@ impl ApiClient {
             .header("Content-Type", "application/json")
     }
 
-    pub fn new(api_token: String, user_id: Uuid) -> Self {
+    fn delete_request(&self, path: &str) -> RequestBuilder {
+        self.http_client
+            .delete(path)
+            .header("Shortcut-Token", &self.api_token)
+    }
+
+    pub fn new(api_token: String, base_url: String, user_id: Uuid) -> Self {
         Self {
             api_token,
+            base_url,
             user_id,
             http_client: Client::new(),
+            limiter: RequestLimiter::default(),
+            recording: None,
         }
     }
+
+    pub fn with_recording(mut self, recording: Option<Recording>) -> Self {
+        self.recording = recording;
+        self
+    }
 }
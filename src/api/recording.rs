@@ -0,0 +1,186 @@
+use std::path::PathBuf;
+
+use reqwest::{Request, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api::error::{ApiError, ApiResult},
+    dbg_file,
+};
+
+/// Saves every API response to disk (`--record <dir>`), or serves saved responses back instead
+/// of talking to Shortcut (`--replay <dir>`)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Recording {
+    Record(PathBuf),
+    Replay(PathBuf),
+}
+
+/// One recorded response, stored as `<dir>/<key>.json`
+#[derive(Deserialize, Serialize)]
+struct RecordedResponse {
+    /// The request this answered, only there to make the files readable
+    request: String,
+    status: u16,
+    body: serde_json::Value,
+}
+
+impl Recording {
+    pub fn dir(&self) -> &PathBuf {
+        match self {
+            Recording::Record(dir) | Recording::Replay(dir) => dir,
+        }
+    }
+
+    /// Serves the recorded response for `request`
+    pub(crate) async fn replay(&self, request: &Request, base_url: &str) -> ApiResult<Response> {
+        let description = describe(request, base_url);
+        let path = self.dir().join(file_name(request, base_url));
+
+        let contents = tokio::fs::read_to_string(&path).await.map_err(|_| {
+            ApiError::Network(format!("No recorded response for {}", description))
+        })?;
+        let recorded: RecordedResponse = serde_json::from_str(&contents)
+            .map_err(|e| ApiError::Decode(format!("{}: {}", path.display(), e)))?;
+
+        let body = match recorded.body {
+            serde_json::Value::String(text) => text,
+            json => json.to_string(),
+        };
+
+        Ok(to_response(recorded.status, body.into_bytes()))
+    }
+
+    /// Saves `response` to disk and hands back an equivalent one, since reading the body
+    /// consumes it
+    pub(crate) async fn record(
+        &self,
+        request: &Request,
+        base_url: &str,
+        response: Response,
+    ) -> ApiResult<Response> {
+        let status = response.status().as_u16();
+        let bytes = response.bytes().await?;
+
+        let recorded = RecordedResponse {
+            request: describe(request, base_url),
+            status,
+            // keep non-JSON bodies (e.g. plain text errors) as strings
+            body: serde_json::from_slice(&bytes).unwrap_or_else(|_| {
+                serde_json::Value::String(String::from_utf8_lossy(&bytes).into_owned())
+            }),
+        };
+
+        let path = self.dir().join(file_name(request, base_url));
+        let write = async {
+            tokio::fs::create_dir_all(self.dir()).await?;
+            let contents = serde_json::to_string_pretty(&recorded)?;
+            tokio::fs::write(&path, contents).await
+        };
+        if let Err(e) = write.await {
+            dbg_file!("Failed to record {}: {}", recorded.request, e);
+        }
+
+        Ok(to_response(status, bytes.to_vec()))
+    }
+}
+
+fn to_response(status: u16, body: Vec<u8>) -> Response {
+    let mut response = http::Response::new(body);
+    *response.status_mut() = StatusCode::from_u16(status).unwrap_or(StatusCode::OK);
+    Response::from(response)
+}
+
+/// e.g. `GET /stories/123`, relative to the base url so recordings work against any host
+fn describe(request: &Request, base_url: &str) -> String {
+    let url = request.url().as_str();
+    let path = url
+        .strip_prefix(base_url.trim_end_matches('/'))
+        .unwrap_or(url);
+    format!("{} {}", request.method(), path)
+}
+
+/// A readable prefix plus a hash of the whole request, bodies included, so e.g. different
+/// search queries don't overwrite each other
+fn file_name(request: &Request, base_url: &str) -> String {
+    let description = describe(request, base_url);
+    let body = request.body().and_then(|b| b.as_bytes()).unwrap_or_default();
+
+    let readable: String = description
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .take(60)
+        .collect();
+
+    format!(
+        "{}-{:016x}.json",
+        readable.trim_matches('_'),
+        fnv1a([description.as_bytes(), body].concat().as_slice())
+    )
+}
+
+/// Stable across runs and platforms, unlike std's hashers
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: reqwest::Method, url: &str, body: Option<&str>) -> Request {
+        let mut request = Request::new(method, url.parse().unwrap());
+        if let Some(body) = body {
+            *request.body_mut() = Some(body.to_string().into());
+        }
+        request
+    }
+
+    #[test]
+    fn file_names_are_relative_to_base_url_and_include_body() {
+        let base = "https://api.app.shortcut.com/api/v3";
+        let get = request(reqwest::Method::GET, &format!("{}/stories/12", base), None);
+        let local = request(reqwest::Method::GET, "http://localhost:8080/stories/12", None);
+
+        assert_eq!(
+            file_name(&get, base),
+            file_name(&local, "http://localhost:8080/")
+        );
+        assert!(file_name(&get, base).starts_with("GET__stories_12-"));
+
+        let a = request(
+            reqwest::Method::POST,
+            &format!("{}/stories/search", base),
+            Some("{\"a\":1}"),
+        );
+        let b = request(
+            reqwest::Method::POST,
+            &format!("{}/stories/search", base),
+            Some("{\"a\":2}"),
+        );
+        assert_ne!(file_name(&a, base), file_name(&b, base));
+    }
+
+    #[tokio::test]
+    async fn recorded_responses_replay() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = "http://localhost";
+        let req = request(reqwest::Method::GET, "http://localhost/member", None);
+
+        let original = to_response(200, br#"{"id":"x"}"#.to_vec());
+        Recording::Record(dir.path().to_path_buf())
+            .record(&req, base, original)
+            .await
+            .unwrap();
+
+        let replay = Recording::Replay(dir.path().to_path_buf());
+        let response = replay.replay(&req, base).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.text().await.unwrap(), r#"{"id":"x"}"#);
+
+        let missing = request(reqwest::Method::GET, "http://localhost/members", None);
+        assert!(replay.replay(&missing, base).await.is_err());
+    }
+}
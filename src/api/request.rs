@@ -0,0 +1,218 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use reqwest::{RequestBuilder, Response, StatusCode};
+use tokio::sync::{Mutex, Semaphore};
+
+use crate::{
+    api::error::{ApiError, ApiResult, retry_after},
+    dbg_file,
+};
+
+/// Shortcut allows 200 requests per minute per token
+const REQUESTS_PER_MINUTE: f64 = 200.0;
+/// How many requests can be sent back to back before the rate limiter kicks in
+const BURST_SIZE: f64 = 20.0;
+const MAX_CONCURRENT_REQUESTS: usize = 8;
+const MAX_RETRIES: u32 = 4;
+const BACKOFF_BASE: Duration = Duration::from_millis(500);
+const BACKOFF_MAX: Duration = Duration::from_secs(30);
+
+/// Classic token bucket: holds up to `capacity` tokens, refilled continuously at `refill_per_sec`
+#[derive(Debug)]
+struct TokenBucket {
+    capacity: f64,
+    tokens: f64,
+    refill_per_sec: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(capacity: f64, refill_per_sec: f64, now: Instant) -> Self {
+        Self {
+            capacity,
+            tokens: capacity,
+            refill_per_sec,
+            last_refill: now,
+        }
+    }
+
+    /// Takes a token, or returns how long to wait until one is available
+    fn try_take(&mut self, now: Instant) -> Result<(), Duration> {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - self.tokens) / self.refill_per_sec,
+            ))
+        }
+    }
+}
+
+/// Limits concurrency and request rate for every request sent by an `ApiClient`.
+/// Clones share the same limits.
+#[derive(Clone, Debug)]
+pub(crate) struct RequestLimiter {
+    permits: Arc<Semaphore>,
+    bucket: Arc<Mutex<TokenBucket>>,
+}
+
+impl Default for RequestLimiter {
+    fn default() -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(MAX_CONCURRENT_REQUESTS)),
+            bucket: Arc::new(Mutex::new(TokenBucket::new(
+                BURST_SIZE,
+                REQUESTS_PER_MINUTE / 60.0,
+                Instant::now(),
+            ))),
+        }
+    }
+}
+
+impl RequestLimiter {
+    async fn wait_for_token(&self) {
+        loop {
+            let wait = match self.bucket.lock().await.try_take(Instant::now()) {
+                Ok(()) => return,
+                Err(wait) => wait,
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Sends `request`, retrying 429s and connection failures with jittered exponential backoff.
+    /// A `Retry-After` header, up to `BACKOFF_MAX`, takes precedence over the backoff. 5xx
+    /// responses and timeouts are only retried for idempotent methods, a POST may have gone
+    /// through and sending it again would duplicate it.
+    pub(crate) async fn send(&self, request: RequestBuilder) -> ApiResult<Response> {
+        let idempotent = request
+            .try_clone()
+            .and_then(|r| r.build().ok())
+            .is_some_and(|r| r.method().is_idempotent());
+        let mut attempt = 0;
+
+        loop {
+            let this_attempt = request.try_clone().ok_or_else(|| {
+                ApiError::Network("Request body can't be retried".to_string())
+            })?;
+
+            let result = {
+                let _permit = self
+                    .permits
+                    .acquire()
+                    .await
+                    .map_err(|e| ApiError::Network(e.to_string()))?;
+                self.wait_for_token().await;
+                this_attempt.send().await
+            };
+
+            let retry_in = match &result {
+                Ok(response) if is_retryable(response.status(), idempotent) => Some(
+                    retry_after(response)
+                        .map(|wait| wait.min(BACKOFF_MAX))
+                        .unwrap_or_else(|| backoff(attempt)),
+                ),
+                Err(e) if e.is_connect() || (idempotent && e.is_timeout()) => {
+                    Some(backoff(attempt))
+                }
+                _ => None,
+            };
+
+            match retry_in {
+                Some(delay) if attempt < MAX_RETRIES => {
+                    dbg_file!(
+                        "Retrying request (attempt {}) in {:?}: {:?}",
+                        attempt + 1,
+                        delay,
+                        result.as_ref().map(|r| r.status())
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                _ => return Ok(result?),
+            }
+        }
+    }
+}
+
+fn is_retryable(status: StatusCode, idempotent: bool) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || (idempotent && status.is_server_error())
+}
+
+/// Exponential backoff with "equal jitter": somewhere between half and all of the full delay
+fn backoff(attempt: u32) -> Duration {
+    let full = BACKOFF_BASE
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(BACKOFF_MAX);
+    let half = full / 2;
+
+    half + half.mul_f64(random_fraction())
+}
+
+/// A random number in `[0, 1)`, good enough for jitter without pulling in a rand crate
+fn random_fraction() -> f64 {
+    let random = RandomState::new().build_hasher().finish();
+    (random >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_allows_burst_then_waits() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::new(2.0, 1.0, now);
+
+        assert!(bucket.try_take(now).is_ok());
+        assert!(bucket.try_take(now).is_ok());
+
+        let wait = bucket.try_take(now).unwrap_err();
+        assert_eq!(wait, Duration::from_secs(1));
+    }
+
+    #[test]
+    fn bucket_refills_over_time() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::new(1.0, 2.0, now);
+
+        assert!(bucket.try_take(now).is_ok());
+        assert!(bucket.try_take(now).is_err());
+        assert!(bucket.try_take(now + Duration::from_millis(500)).is_ok());
+    }
+
+    #[test]
+    fn backoff_grows_and_is_capped() {
+        for attempt in 0..10 {
+            let full = BACKOFF_BASE
+                .saturating_mul(2u32.saturating_pow(attempt))
+                .min(BACKOFF_MAX);
+            let delay = backoff(attempt);
+            assert!(delay >= full / 2 && delay <= full, "attempt {attempt}: {delay:?}");
+        }
+    }
+
+    #[test]
+    fn retries_rate_limits_and_server_errors_only() {
+        assert!(is_retryable(StatusCode::TOO_MANY_REQUESTS, true));
+        assert!(is_retryable(StatusCode::BAD_GATEWAY, true));
+        assert!(!is_retryable(StatusCode::NOT_FOUND, true));
+        assert!(!is_retryable(StatusCode::UNAUTHORIZED, true));
+    }
+
+    #[test]
+    fn non_idempotent_requests_retry_rate_limits_only() {
+        assert!(is_retryable(StatusCode::TOO_MANY_REQUESTS, false));
+        assert!(!is_retryable(StatusCode::BAD_GATEWAY, false));
+    }
+}
@@ -1,13 +1,162 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::api::{
+    ApiClient,
+    error::{ApiResult, decode},
+};
+
 #[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
 pub struct StoryComment {
-    author_id: Uuid,
-    deleted: bool,
+    pub id: i32,
+    pub author_id: Uuid,
+    pub deleted: bool,
     // numerical position of comment oldest -> newest
-    position: i32,
-    text: Option<String>,
-    // TODO: show replies in threads/nested
-    // parent_id: Option<i32>
+    pub position: i32,
+    pub text: Option<String>,
+    pub parent_id: Option<i32>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Flattens comments into display order: each top level comment followed by its replies
+/// (recursively), oldest first. Returns each comment with its nesting depth.
+///
+/// Deleted comments are kept only when they still have visible replies, so threads don't lose
+/// their context.
+pub fn comment_threads(comments: &[StoryComment]) -> Vec<(usize, &StoryComment)> {
+    let mut sorted: Vec<_> = comments.iter().collect();
+    sorted.sort_by_key(|c| c.position);
+
+    // a reply whose parent isn't in the list is shown as a top level comment
+    let is_root = |c: &StoryComment| match c.parent_id {
+        Some(parent_id) => !comments.iter().any(|p| p.id == parent_id),
+        None => true,
+    };
+
+    let mut threads = Vec::new();
+    for root in sorted.iter().filter(|c| is_root(c)) {
+        push_thread(root, 0, &sorted, &mut threads);
+    }
+
+    threads
+}
+
+fn push_thread<'a>(
+    comment: &'a StoryComment,
+    depth: usize,
+    sorted: &[&'a StoryComment],
+    out: &mut Vec<(usize, &'a StoryComment)>,
+) {
+    let mut replies = Vec::new();
+    for reply in sorted.iter().filter(|c| c.parent_id == Some(comment.id)) {
+        push_thread(reply, depth + 1, sorted, &mut replies);
+    }
+
+    if comment.deleted && replies.is_empty() {
+        return;
+    }
+
+    out.push((depth, comment));
+    out.extend(replies);
+}
+
+impl ApiClient {
+    pub async fn create_story_comment(
+        &self,
+        story_id: i32,
+        text: String,
+        parent_id: Option<i32>,
+    ) -> ApiResult<StoryComment> {
+        let body = serde_json::json!({
+            "text": text,
+            "parent_id": parent_id,
+        });
+
+        let response = self
+            .post_with_body(&format!("stories/{}/comments", story_id), &body)
+            .await?;
+
+        decode(response).await
+    }
+
+    pub async fn update_story_comment(
+        &self,
+        story_id: i32,
+        comment_id: i32,
+        text: String,
+    ) -> ApiResult<StoryComment> {
+        let body = serde_json::json!({
+            "text": text,
+        });
+
+        let response = self
+            .put_with_body(
+                &format!("stories/{}/comments/{}", story_id, comment_id),
+                &body,
+            )
+            .await?;
+
+        decode(response).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(id: i32, position: i32, parent_id: Option<i32>) -> StoryComment {
+        StoryComment {
+            id,
+            author_id: Uuid::nil(),
+            deleted: false,
+            position,
+            text: Some(format!("comment {}", id)),
+            parent_id,
+            created_at: chrono::DateTime::default(),
+        }
+    }
+
+    fn ids(threads: &[(usize, &StoryComment)]) -> Vec<(usize, i32)> {
+        threads.iter().map(|(depth, c)| (*depth, c.id)).collect()
+    }
+
+    #[test]
+    fn replies_follow_their_parent() {
+        let comments = vec![
+            comment(1, 0, None),
+            comment(2, 1, None),
+            comment(3, 2, Some(1)),
+            comment(4, 3, Some(3)),
+        ];
+
+        assert_eq!(
+            ids(&comment_threads(&comments)),
+            [(0, 1), (1, 3), (2, 4), (0, 2)]
+        );
+    }
+
+    #[test]
+    fn orphaned_reply_is_shown_at_top_level() {
+        let comments = vec![comment(1, 0, None), comment(2, 1, Some(99))];
+
+        assert_eq!(ids(&comment_threads(&comments)), [(0, 1), (0, 2)]);
+    }
+
+    #[test]
+    fn deleted_comment_without_replies_is_hidden() {
+        let mut deleted = comment(1, 0, None);
+        deleted.deleted = true;
+        let comments = vec![deleted, comment(2, 1, None)];
+
+        assert_eq!(ids(&comment_threads(&comments)), [(0, 2)]);
+    }
+
+    #[test]
+    fn deleted_comment_with_replies_is_kept() {
+        let mut deleted = comment(1, 0, None);
+        deleted.deleted = true;
+        let comments = vec![deleted, comment(2, 1, Some(1))];
+
+        assert_eq!(ids(&comment_threads(&comments)), [(0, 1), (1, 2)]);
+    }
 }
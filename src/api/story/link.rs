@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LinkVerb {
+    #[serde(rename = "blocks")]
+    Blocks,
+    #[serde(rename = "duplicates")]
+    Duplicates,
+    #[serde(rename = "relates to")]
+    RelatesTo,
+    /// Verbs Shortcut added after this was written
+    #[serde(other)]
+    Other,
+}
+
+/// Which end of the link the story it was listed on is, links read "subject verb object"
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkSide {
+    Subject,
+    Object,
+}
+
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct StoryLink {
+    pub id: i32,
+    pub subject_id: i32,
+    pub object_id: i32,
+    pub verb: LinkVerb,
+    #[serde(rename = "type")]
+    pub side: LinkSide,
+}
+
+impl StoryLink {
+    /// The story at the other end of the link
+    pub fn other_story_id(&self) -> i32 {
+        match self.side {
+            LinkSide::Subject => self.object_id,
+            LinkSide::Object => self.subject_id,
+        }
+    }
+
+    /// How the story relates to the other one, e.g. "is blocked by"
+    pub fn label(&self) -> &'static str {
+        match (self.verb, self.side) {
+            (LinkVerb::Blocks, LinkSide::Subject) => "blocks",
+            (LinkVerb::Blocks, LinkSide::Object) => "is blocked by",
+            (LinkVerb::Duplicates, LinkSide::Subject) => "duplicates",
+            (LinkVerb::Duplicates, LinkSide::Object) => "is duplicated by",
+            (LinkVerb::RelatesTo, _) => "relates to",
+            (LinkVerb::Other, _) => "is linked to",
+        }
+    }
+
+    pub fn is_blocked_by(&self) -> bool {
+        self.verb == LinkVerb::Blocks && self.side == LinkSide::Object
+    }
+
+    pub fn is_blocking(&self) -> bool {
+        self.verb == LinkVerb::Blocks && self.side == LinkSide::Subject
+    }
+}
+
+/// Links in display order, what blocks the story first
+pub fn ordered_links(links: &[StoryLink]) -> Vec<&StoryLink> {
+    let mut sorted: Vec<_> = links.iter().collect();
+    sorted.sort_by_key(|l| (!l.is_blocked_by(), !l.is_blocking(), l.verb as u8, l.id));
+    sorted
+}
+
+/// Number of stories blocking the story and number it blocks
+pub fn blocker_counts(links: &[StoryLink]) -> (usize, usize) {
+    (
+        links.iter().filter(|l| l.is_blocked_by()).count(),
+        links.iter().filter(|l| l.is_blocking()).count(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn links_read_from_the_listed_story() {
+        let json = r#"[
+            {"id": 1, "subject_id": 7, "object_id": 5, "verb": "blocks", "type": "object"},
+            {"id": 2, "subject_id": 5, "object_id": 9, "verb": "relates to", "type": "subject"},
+            {"id": 3, "subject_id": 5, "object_id": 8, "verb": "blocks", "type": "subject"},
+            {"id": 4, "subject_id": 5, "object_id": 6, "verb": "supersedes", "type": "subject"}
+        ]"#;
+        let links: Vec<StoryLink> = serde_json::from_str(json).unwrap();
+
+        let ordered: Vec<_> = ordered_links(&links)
+            .iter()
+            .map(|l| (l.label(), l.other_story_id()))
+            .collect();
+        assert_eq!(
+            ordered,
+            vec![
+                ("is blocked by", 7),
+                ("blocks", 8),
+                ("relates to", 9),
+                ("is linked to", 6)
+            ]
+        );
+        assert_eq!(blocker_counts(&links), (1, 1));
+    }
+}
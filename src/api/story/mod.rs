@@ -1,57 +1,281 @@
-use anyhow::Context;
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use futures::future::try_join_all;
+use serde::{Deserialize, Deserializer, Serialize};
 use slugify::slugify;
+use reqwest::Url;
 use uuid::Uuid;
 
-use crate::api::{ApiClient, branch::Branch, iteration::Iteration, story::comment::StoryComment};
+use crate::api::{
+    ApiClient,
+    branch::Branch,
+    error::{ApiError, ApiResult, decode},
+    iteration::Iteration,
+    label::Label,
+    story::{comment::StoryComment, link::StoryLink, task::StoryTask},
+    versioned::{Versioned, in_listed_order, partition_changed},
+};
 
 pub mod comment;
+pub mod link;
+pub mod task;
+
+/// Default template for git branch names, following Shortcut's own convention
+pub const DEFAULT_BRANCH_NAME_TEMPLATE: &str = "{mention}/sc-{id}/{slug}";
+
+/// Shortcut truncates the story name part of its suggested branch names
+const BRANCH_SLUG_MAX_LEN: usize = 50;
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum StoryType {
+    #[default]
+    Feature,
+    Bug,
+    Chore,
+}
+
+impl StoryType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StoryType::Feature => "feature",
+            StoryType::Bug => "bug",
+            StoryType::Chore => "chore",
+        }
+    }
+}
 
 #[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
 pub struct Story {
+    // search results may leave out branches and comments
+    #[serde(default)]
     pub branches: Vec<Branch>,
     pub completed: bool,
+    #[serde(default)]
     pub comments: Vec<StoryComment>,
+    #[serde(default)]
+    pub tasks: Vec<StoryTask>,
+    #[serde(default)]
+    pub story_links: Vec<StoryLink>,
+    /// Set by Shortcut while a story blocking this one is still open
+    #[serde(default)]
+    pub blocked: bool,
+    /// Set by Shortcut while this story blocks another one and is still open
+    #[serde(default)]
+    pub blocker: bool,
     pub description: String,
     pub epic_id: Option<i32>,
     pub id: i32,
     pub iteration_id: Option<i32>,
     pub name: String,
+    #[serde(default)]
+    pub owner_ids: Vec<Uuid>,
+    #[serde(default)]
+    pub group_id: Option<Uuid>,
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub estimate: Option<i32>,
+    #[serde(default)]
+    pub labels: Vec<Label>,
+    #[serde(default)]
+    pub deadline: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub completed_at: Option<DateTime<Utc>>,
     pub app_url: String,
+    #[serde(default)]
+    pub workflow_id: i32,
+    #[serde(default)]
+    pub workflow_state_id: i32,
+    pub story_type: StoryType,
+}
+
+/// Fields to change with `update_story`, anything left as `None` is left as it is. The
+/// nested options are `Some(None)` to clear the field.
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug, Default)]
+pub struct StoryUpdate {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub story_type: Option<StoryType>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "nested_option")]
+    pub estimate: Option<Option<i32>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub labels: Option<Vec<Label>>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "nested_option")]
+    pub deadline: Option<Option<DateTime<Utc>>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// Keeps an explicit `null` as `Some(None)`, serde would otherwise read it as a missing field
+fn nested_option<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::deserialize(deserializer).map(Some)
+}
+
+impl StoryUpdate {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Makes the same changes to a local copy of the story that Shortcut makes to its own
+    pub fn apply(&self, story: &mut Story) {
+        if let Some(name) = &self.name {
+            story.name = name.clone();
+        }
+        if let Some(story_type) = self.story_type {
+            story.story_type = story_type;
+        }
+        if let Some(estimate) = self.estimate {
+            story.estimate = estimate;
+        }
+        if let Some(labels) = &self.labels {
+            story.labels = labels.clone();
+        }
+        if let Some(deadline) = self.deadline {
+            story.deadline = deadline;
+        }
+        if let Some(description) = &self.description {
+            story.description = description.clone();
+        }
+    }
+
+    /// The values `story` has now for the fields this update changes, to tell later whether
+    /// someone else changed them in the meantime
+    pub fn original_values(&self, story: &Story) -> StoryUpdate {
+        StoryUpdate {
+            name: self.name.as_ref().map(|_| story.name.clone()),
+            story_type: self.story_type.map(|_| story.story_type),
+            estimate: self.estimate.map(|_| story.estimate),
+            labels: self.labels.as_ref().map(|_| story.labels.clone()),
+            deadline: self.deadline.map(|_| story.deadline),
+            description: self.description.as_ref().map(|_| story.description.clone()),
+        }
+    }
+
+    /// Whether `story` already has every value this update sets
+    pub fn matches(&self, story: &Story) -> bool {
+        let mut updated = story.clone();
+        self.apply(&mut updated);
+        updated == *story
+    }
+}
+
+/// A story to create with `create_story`
+#[derive(Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct NewStory {
+    pub name: String,
+    pub story_type: StoryType,
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<i32>,
+    pub labels: Vec<Label>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iteration_id: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub epic_id: Option<i32>,
+    pub owner_ids: Vec<Uuid>,
+}
+
+/// Stop following `next` tokens once this many search results have been collected
+const SEARCH_MAX_RESULTS: usize = 100;
+
+#[derive(Deserialize)]
+struct StorySearchPage {
+    data: Vec<Story>,
+    /// Path of the next page relative to the API host, `None` on the last page
+    next: Option<String>,
 }
 
 #[derive(Deserialize)]
 pub struct StorySlim {
     pub id: i32,
     pub owner_ids: Vec<Uuid>,
+    #[serde(default)]
+    pub archived: bool,
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl Versioned for Story {
+    fn entity_id(&self) -> i32 {
+        self.id
+    }
+
+    fn updated_at(&self) -> Option<DateTime<Utc>> {
+        self.updated_at
+    }
+}
+
+impl Versioned for StorySlim {
+    fn entity_id(&self) -> i32 {
+        self.id
+    }
+
+    fn updated_at(&self) -> Option<DateTime<Utc>> {
+        self.updated_at
+    }
 }
 
 impl ApiClient {
-    pub async fn get_active_owned_stories(&self) -> anyhow::Result<Vec<Story>> {
+    /// Full bodies for the listed stories, reusing those in `known` that haven't changed
+    pub(crate) async fn get_changed_stories(
+        &self,
+        listed: &[StorySlim],
+        known: &[Story],
+    ) -> ApiResult<Vec<Story>> {
+        let (mut stories, changed) = partition_changed(listed, known);
+        let fetched = try_join_all(changed.iter().map(|id| self.get_story(*id))).await?;
+        stories.extend(fetched);
+
+        Ok(in_listed_order(listed, stories))
+    }
+
+    pub async fn get_story(&self, story_id: i32) -> ApiResult<Story> {
+        decode(self.get(&format!("stories/{}", story_id)).await?).await
+    }
+
+    /// Every unarchived story we own, reusing those in `known` that haven't changed
+    pub async fn get_active_owned_stories(&self, known: &[Story]) -> ApiResult<Vec<Story>> {
         let body = serde_json::json!({
             "archived": false,
             "owner_ids": [self.user_id],
         });
 
-        let stories_slim = {
-            let response = self.post_with_body("stories/search", &body).await?;
-            response
-                .json::<Vec<StorySlim>>()
-                .await
-                .context("Failed to fetch owned stories from API")?
-        };
-
-        let stories = {
-            let mut stories = Vec::with_capacity(stories_slim.len());
-            for slim in stories_slim.into_iter() {
-                let query = format!("stories/{}", slim.id);
-                let response = self.get(&query).await?;
-                let story = response.json::<Story>().await?;
-                stories.push(story);
+        let listed: Vec<StorySlim> =
+            decode(self.post_with_body("stories/search", &body).await?).await?;
+        self.get_changed_stories(&listed, known).await
+    }
+
+    /// Searches every story in the workspace using Shortcut's search query language,
+    /// e.g. `owner:someone state:"In Review" login`.
+    pub async fn search_stories(&self, query: &str) -> ApiResult<Vec<Story>> {
+        let mut url = Url::parse_with_params(
+            &self.get_full_path("search/stories"),
+            &[("query", query), ("detail", "full"), ("page_size", "25")],
+        )
+        .map_err(|e| ApiError::Network(e.to_string()))?;
+        let mut stories = Vec::new();
+
+        loop {
+            let page: StorySearchPage = decode(self.get_url(url.as_str()).await?).await?;
+
+            stories.extend(page.data);
+
+            match page.next {
+                Some(next) if stories.len() < SEARCH_MAX_RESULTS => {
+                    url = next_page_url(&self.base_url, &next)?;
+                }
+                _ => break,
             }
-            stories
-        };
+        }
 
+        stories.truncate(SEARCH_MAX_RESULTS);
         Ok(stories)
     }
 
@@ -59,17 +283,37 @@ impl ApiClient {
         &self,
         story_id: i32,
         new_description: String,
-    ) -> anyhow::Result<()> {
+    ) -> ApiResult<()> {
         let body = serde_json::json!({
             "description": new_description,
         });
 
-        let response = self
-            .put_with_body(&format!("stories/{}", story_id), &body)
+        // ignore the returned Story, we don't need and no need to parse the body of the response
+        self.put_with_body(&format!("stories/{}", story_id), &body)
             .await?;
+        Ok(())
+    }
 
-        // ignore the returned Story, we don't need and no need to parse the body of the response
-        response.error_for_status()?;
+    pub async fn create_story(&self, story: &NewStory) -> ApiResult<Story> {
+        decode(self.post_with_body("stories", story).await?).await
+    }
+
+    /// Sends only the fields set in `update`, returning the story as Shortcut has it afterwards
+    pub async fn update_story(&self, story_id: i32, update: &StoryUpdate) -> ApiResult<Story> {
+        decode(self.put_with_body(&format!("stories/{}", story_id), update).await?).await
+    }
+
+    pub async fn update_story_state(
+        &self,
+        story_id: i32,
+        workflow_state_id: i32,
+    ) -> ApiResult<()> {
+        let body = serde_json::json!({
+            "workflow_state_id": workflow_state_id,
+        });
+
+        self.put_with_body(&format!("stories/{}", story_id), &body)
+            .await?;
         Ok(())
     }
 }
@@ -83,6 +327,55 @@ impl Story {
     pub fn get_file_name(&self) -> String {
         self.name.to_string()
     }
+
+    /// Branch to use for this story's worktree.
+    ///
+    /// Reuses the most recently created branch already linked to the story, otherwise renders
+    /// `template`, which supports the `{mention}`, `{id}`, `{slug}` and `{type}` placeholders.
+    /// If the mention name isn't known, its segment is dropped from the branch name.
+    pub fn branch_name(&self, template: &str, mention_name: Option<&str>) -> String {
+        if let Some(branch) = self.branches.iter().max_by_key(|b| b.id) {
+            return branch.name.clone();
+        }
+
+        let mut slug = slugify!(&self.name);
+        if slug.len() > BRANCH_SLUG_MAX_LEN {
+            slug.truncate(BRANCH_SLUG_MAX_LEN);
+            if let Some(idx) = slug.rfind('-') {
+                slug.truncate(idx);
+            }
+        }
+
+        let rendered = template
+            .replace("{mention}", mention_name.unwrap_or_default())
+            .replace("{id}", &self.id.to_string())
+            .replace("{slug}", &slug)
+            .replace("{type}", self.story_type.as_str());
+
+        // clean up separators left behind by empty placeholders
+        rendered
+            .split('/')
+            .map(|part| part.trim_matches('-'))
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// Number of stories blocking this one and number it blocks. Links stay once the blocking
+    /// story is done, only Shortcut's flags say whether they still block anything.
+    pub fn blocker_counts(&self) -> (usize, usize) {
+        let (blocked_by, blocking) = link::blocker_counts(&self.story_links);
+        (
+            if self.blocked { blocked_by } else { 0 },
+            if self.blocker { blocking } else { 0 },
+        )
+    }
+}
+
+fn next_page_url(base_url: &str, next: &str) -> ApiResult<Url> {
+    Url::parse(base_url)
+        .and_then(|base| base.join(next))
+        .map_err(|e| ApiError::Decode(format!("Invalid search pagination token {}: {}", next, e)))
 }
 
 pub fn get_story_associated_iteration<'a>(
@@ -92,3 +385,143 @@ pub fn get_story_associated_iteration<'a>(
     let iteration_id = iteration_id?;
     iterations.into_iter().find(|it| it.id == iteration_id)
 }
+
+/// Points of the completed stories and of all of them, stories without an estimate count as 0
+pub fn story_points<'a>(stories: impl IntoIterator<Item = &'a Story>) -> (i32, i32) {
+    stories.into_iter().fold((0, 0), |(done, total), story| {
+        let points = story.estimate.unwrap_or(0);
+        let done = if story.completed { done + points } else { done };
+        (done, total + points)
+    })
+}
+
+/// A story with nothing optional set, tests fill in what they need with struct update syntax
+#[cfg(test)]
+pub(crate) fn create_test_story(name: &str) -> Story {
+    Story {
+        id: 123,
+        name: name.to_string(),
+        owner_ids: vec![],
+        group_id: None,
+        updated_at: None,
+        estimate: None,
+        labels: vec![],
+        deadline: None,
+        description: String::new(),
+        completed: false,
+        branches: vec![],
+        comments: vec![],
+        tasks: vec![],
+        story_links: vec![],
+        blocked: false,
+        blocker: false,
+        completed_at: None,
+        epic_id: None,
+        iteration_id: None,
+        app_url: "https://example.com".to_string(),
+        workflow_id: 1,
+        workflow_state_id: 1,
+        story_type: StoryType::Bug,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::API_BASE_URL;
+
+    #[test]
+    fn points_count_completed_stories_as_done() {
+        let mut done = create_test_story("Done");
+        done.completed = true;
+        done.estimate = Some(3);
+        let mut open = create_test_story("Open");
+        open.estimate = Some(5);
+        let unestimated = create_test_story("Unestimated");
+
+        assert_eq!(story_points([&done, &open, &unestimated]), (3, 8));
+    }
+
+    #[test]
+    fn done_blockers_are_not_counted() {
+        let mut story = create_test_story("Blocked");
+        story.story_links = serde_json::from_str(
+            r#"[{"id": 1, "subject_id": 7, "object_id": 123, "verb": "blocks", "type": "object"}]"#,
+        )
+        .unwrap();
+        assert_eq!(story.blocker_counts(), (0, 0));
+
+        story.blocked = true;
+        assert_eq!(story.blocker_counts(), (1, 0));
+    }
+
+    #[test]
+    fn branch_name_uses_default_template() {
+        let story = create_test_story("Fix login redirect");
+        assert_eq!(
+            story.branch_name(DEFAULT_BRANCH_NAME_TEMPLATE, Some("jane")),
+            "jane/sc-123/fix-login-redirect"
+        );
+    }
+
+    #[test]
+    fn branch_name_supports_type_placeholder() {
+        let story = create_test_story("Fix login redirect");
+        assert_eq!(
+            story.branch_name("{type}/sc-{id}-{slug}", None),
+            "bug/sc-123-fix-login-redirect"
+        );
+    }
+
+    #[test]
+    fn branch_name_drops_unknown_mention() {
+        let story = create_test_story("Fix login redirect");
+        assert_eq!(
+            story.branch_name(DEFAULT_BRANCH_NAME_TEMPLATE, None),
+            "sc-123/fix-login-redirect"
+        );
+    }
+
+    #[test]
+    fn branch_name_truncates_long_slugs_on_word_boundary() {
+        let story = create_test_story(
+            "This story has a really long name that keeps going well past the limit",
+        );
+        let branch = story.branch_name("{slug}", None);
+        assert!(branch.len() <= BRANCH_SLUG_MAX_LEN);
+        assert!(!branch.ends_with('-'));
+        assert!("this-story-has-a-really-long-name-that-keeps-going".starts_with(&branch));
+    }
+
+    #[test]
+    fn branch_name_reuses_latest_existing_branch() {
+        let mut story = create_test_story("Fix login redirect");
+        story.branches = vec![
+            Branch {
+                id: 1,
+                name: "old-branch".to_string(),
+            },
+            Branch {
+                id: 2,
+                name: "jane/sc-123/fix-login".to_string(),
+            },
+        ];
+        assert_eq!(
+            story.branch_name(DEFAULT_BRANCH_NAME_TEMPLATE, Some("jane")),
+            "jane/sc-123/fix-login"
+        );
+    }
+
+    #[test]
+    fn next_page_url_is_resolved_against_api_host() {
+        let url = next_page_url(
+            API_BASE_URL,
+            "/api/v3/search/stories?query=login&next=abc123",
+        )
+        .unwrap();
+        assert_eq!(
+            url.as_str(),
+            "https://api.app.shortcut.com/api/v3/search/stories?query=login&next=abc123"
+        );
+    }
+}
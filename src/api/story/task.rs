@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+
+use crate::api::{
+    ApiClient,
+    error::{ApiResult, decode},
+};
+
+/// One item of a story's checklist
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct StoryTask {
+    pub id: i32,
+    pub description: String,
+    pub complete: bool,
+    pub position: i32,
+}
+
+/// Fields of a task to change, the ones left `None` stay as they are
+#[derive(Serialize, Clone, Default, PartialEq, Eq, Debug)]
+pub struct TaskUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub complete: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl TaskUpdate {
+    pub fn apply(&self, task: &mut StoryTask) {
+        if let Some(complete) = self.complete {
+            task.complete = complete;
+        }
+        if let Some(description) = &self.description {
+            task.description = description.clone();
+        }
+    }
+}
+
+/// Tasks in checklist order
+pub fn ordered_tasks(tasks: &[StoryTask]) -> Vec<&StoryTask> {
+    let mut sorted: Vec<_> = tasks.iter().collect();
+    sorted.sort_by_key(|t| t.position);
+    sorted
+}
+
+/// Completed and total number of tasks
+pub fn task_counts(tasks: &[StoryTask]) -> (usize, usize) {
+    (tasks.iter().filter(|t| t.complete).count(), tasks.len())
+}
+
+impl ApiClient {
+    pub async fn create_story_task(
+        &self,
+        story_id: i32,
+        description: String,
+    ) -> ApiResult<StoryTask> {
+        let body = serde_json::json!({
+            "description": description,
+        });
+
+        let response = self
+            .post_with_body(&format!("stories/{}/tasks", story_id), &body)
+            .await?;
+
+        decode(response).await
+    }
+
+    pub async fn update_story_task(
+        &self,
+        story_id: i32,
+        task_id: i32,
+        update: &TaskUpdate,
+    ) -> ApiResult<StoryTask> {
+        let response = self
+            .put_with_body(&format!("stories/{}/tasks/{}", story_id, task_id), update)
+            .await?;
+
+        decode(response).await
+    }
+
+    pub async fn delete_story_task(&self, story_id: i32, task_id: i32) -> ApiResult<()> {
+        self.delete(&format!("stories/{}/tasks/{}", story_id, task_id))
+            .await?;
+        Ok(())
+    }
+}
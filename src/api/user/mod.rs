@@ -1,27 +1,92 @@
-use anyhow::Context;
-use reqwest::Client;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::api::get_full_path;
+use crate::api::{
+    ApiClient,
+    error::{ApiResult, decode},
+};
 
 #[derive(Deserialize)]
 pub struct Member {
     id: Uuid,
 }
 
-pub async fn get_user_id_from_api(api_token: &str) -> anyhow::Result<Uuid> {
-    let full_path = get_full_path("member");
-    // cant do with api client as it isnt instantiated at the point of calling this
-    let response = Client::new()
-        .get(&full_path)
-        .header("Shortcut-Token", api_token)
-        .header("Content-Type", "application/json")
-        .send()
-        .await
-        .with_context(|| format!("Failed to GET {}", &full_path))?;
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct MemberProfile {
+    pub name: String,
+    pub mention_name: String,
+}
+
+impl MemberProfile {
+    /// Up to two letters for avatar badges, e.g. "Jane van Doe" -> "JD"
+    pub fn initials(&self) -> String {
+        let words: Vec<_> = self.name.split_whitespace().collect();
+        let letters: String = match words.as_slice() {
+            [] => self.mention_name.chars().take(2).collect(),
+            [only] => only.chars().take(2).collect(),
+            [first, .., last] => first.chars().take(1).chain(last.chars().take(1)).collect(),
+        };
+
+        letters.to_uppercase()
+    }
+}
+
+/// A member of the workspace, as returned by the `members` endpoint
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct WorkspaceMember {
+    pub id: Uuid,
+    pub profile: MemberProfile,
+}
+
+impl ApiClient {
+    /// The member the API token belongs to, works before `user_id` is known
+    pub async fn get_user_id_from_api(&self) -> ApiResult<Uuid> {
+        let user: Member = decode(self.get("member").await?).await?;
+        Ok(user.id)
+    }
+
+    pub async fn get_members(&self) -> ApiResult<Vec<WorkspaceMember>> {
+        decode(self.get("members").await?).await
+    }
+}
+
+pub fn get_member<'a>(
+    member_id: Uuid,
+    members: impl IntoIterator<Item = &'a WorkspaceMember>,
+) -> Option<&'a WorkspaceMember> {
+    members.into_iter().find(|m| m.id == member_id)
+}
+
+/// The known members out of `owner_ids`, in the same order
+pub fn get_owners<'a>(owner_ids: &[Uuid], members: &'a [WorkspaceMember]) -> Vec<&'a WorkspaceMember> {
+    owner_ids
+        .iter()
+        .filter_map(|id| get_member(*id, members))
+        .collect()
+}
+
+pub fn get_member_name<'a>(
+    member_id: Uuid,
+    members: impl IntoIterator<Item = &'a WorkspaceMember>,
+) -> Option<&'a str> {
+    get_member(member_id, members).map(|m| m.profile.name.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let user = response.json::<Member>().await?;
+    fn profile(name: &str, mention_name: &str) -> MemberProfile {
+        MemberProfile {
+            name: name.to_string(),
+            mention_name: mention_name.to_string(),
+        }
+    }
 
-    Ok(user.id)
+    #[test]
+    fn initials_use_first_and_last_name() {
+        assert_eq!(profile("Jane van Doe", "jane").initials(), "JD");
+        assert_eq!(profile("alex", "alex").initials(), "AL");
+        assert_eq!(profile("", "sam").initials(), "SA");
+    }
 }
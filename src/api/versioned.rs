@@ -0,0 +1,79 @@
+use chrono::{DateTime, Utc};
+
+/// An entity Shortcut stamps with `updated_at`, in either its slim or full form
+pub trait Versioned {
+    fn entity_id(&self) -> i32;
+    fn updated_at(&self) -> Option<DateTime<Utc>>;
+}
+
+/// Splits a slim listing into the `known` entities that haven't changed since they were
+/// fetched, and the ids that need their full body fetched. Entities without an `updated_at`
+/// are always refetched.
+pub fn partition_changed<S: Versioned, T: Versioned + Clone>(
+    listed: &[S],
+    known: &[T],
+) -> (Vec<T>, Vec<i32>) {
+    let mut unchanged = Vec::new();
+    let mut changed = Vec::new();
+
+    for slim in listed {
+        let cached = known.iter().find(|k| k.entity_id() == slim.entity_id());
+
+        match (cached, slim.updated_at()) {
+            (Some(cached), Some(updated_at)) if cached.updated_at() == Some(updated_at) => {
+                unchanged.push(cached.clone());
+            }
+            _ => changed.push(slim.entity_id()),
+        }
+    }
+
+    (unchanged, changed)
+}
+
+/// Puts `entities` back into the order of the listing they came from
+pub fn in_listed_order<S: Versioned, T: Versioned>(listed: &[S], mut entities: Vec<T>) -> Vec<T> {
+    entities.sort_by_key(|e| listed.iter().position(|s| s.entity_id() == e.entity_id()));
+    entities
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Entity(i32, Option<i64>);
+
+    impl Versioned for Entity {
+        fn entity_id(&self) -> i32 {
+            self.0
+        }
+
+        fn updated_at(&self) -> Option<DateTime<Utc>> {
+            self.1.and_then(|secs| DateTime::from_timestamp(secs, 0))
+        }
+    }
+
+    #[test]
+    fn only_changed_or_unknown_entities_are_refetched() {
+        let listed = vec![
+            Entity(1, Some(100)),
+            Entity(2, Some(250)),
+            Entity(3, Some(300)),
+            Entity(4, None),
+        ];
+        let known = vec![Entity(1, Some(100)), Entity(2, Some(200)), Entity(4, None)];
+
+        let (unchanged, changed) = partition_changed(&listed, &known);
+        assert_eq!(unchanged, vec![Entity(1, Some(100))]);
+        assert_eq!(changed, vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn entities_are_put_back_in_listed_order() {
+        let listed = vec![Entity(3, None), Entity(1, None), Entity(2, None)];
+        let entities = vec![Entity(1, None), Entity(2, None), Entity(3, None)];
+
+        let ids: Vec<_> = in_listed_order(&listed, entities).iter().map(|e| e.0).collect();
+        assert_eq!(ids, vec![3, 1, 2]);
+    }
+}
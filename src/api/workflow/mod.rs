@@ -0,0 +1,144 @@
+use fuzzy_matcher::{FuzzyMatcher, skim::SkimMatcherV2};
+use serde::{Deserialize, Serialize};
+
+use crate::api::{
+    ApiClient,
+    error::{ApiResult, decode},
+};
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum WorkflowStateType {
+    Backlog,
+    Unstarted,
+    Started,
+    Done,
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct WorkflowState {
+    pub id: i32,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub state_type: WorkflowStateType,
+    pub position: i32,
+}
+
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct Workflow {
+    pub id: i32,
+    pub name: String,
+    pub states: Vec<WorkflowState>,
+}
+
+impl Workflow {
+    /// States in the order they appear on the Shortcut board.
+    pub fn ordered_states(&self) -> Vec<&WorkflowState> {
+        let mut states: Vec<_> = self.states.iter().collect();
+        states.sort_by_key(|s| s.position);
+        states
+    }
+
+    /// Finds a state by name, preferring a case-insensitive exact match and
+    /// falling back to the best fuzzy match (so `sc state review` finds "In Review").
+    pub fn find_state_by_name(&self, name: &str) -> Option<&WorkflowState> {
+        if let Some(state) = self
+            .states
+            .iter()
+            .find(|s| s.name.eq_ignore_ascii_case(name))
+        {
+            return Some(state);
+        }
+
+        let matcher = SkimMatcherV2::default();
+        self.states
+            .iter()
+            .filter_map(|s| matcher.fuzzy_match(&s.name, name).map(|score| (score, s)))
+            .max_by_key(|(score, _)| *score)
+            .map(|(_, s)| s)
+    }
+}
+
+impl ApiClient {
+    pub async fn get_workflows(&self) -> ApiResult<Vec<Workflow>> {
+        decode(self.get("workflows").await?).await
+    }
+}
+
+pub fn get_workflow<'a>(
+    workflow_id: i32,
+    workflows: impl IntoIterator<Item = &'a Workflow>,
+) -> Option<&'a Workflow> {
+    workflows.into_iter().find(|wf| wf.id == workflow_id)
+}
+
+pub fn get_workflow_state<'a>(
+    state_id: i32,
+    workflows: impl IntoIterator<Item = &'a Workflow>,
+) -> Option<&'a WorkflowState> {
+    workflows
+        .into_iter()
+        .flat_map(|wf| wf.states.iter())
+        .find(|s| s.id == state_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(id: i32, name: &str, position: i32) -> WorkflowState {
+        WorkflowState {
+            id,
+            name: name.to_string(),
+            state_type: WorkflowStateType::Started,
+            position,
+        }
+    }
+
+    fn workflow() -> Workflow {
+        Workflow {
+            id: 1,
+            name: "Engineering".to_string(),
+            states: vec![
+                state(13, "Done", 3),
+                state(10, "Ready", 0),
+                state(12, "In Review", 2),
+                state(11, "In Progress", 1),
+            ],
+        }
+    }
+
+    #[test]
+    fn ordered_states_sorts_by_position() {
+        let wf = workflow();
+        let names: Vec<_> = wf.ordered_states().iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["Ready", "In Progress", "In Review", "Done"]);
+    }
+
+    #[test]
+    fn find_state_prefers_exact_match() {
+        let wf = workflow();
+        assert_eq!(wf.find_state_by_name("done").map(|s| s.id), Some(13));
+    }
+
+    #[test]
+    fn find_state_falls_back_to_fuzzy_match() {
+        let wf = workflow();
+        assert_eq!(wf.find_state_by_name("review").map(|s| s.id), Some(12));
+    }
+
+    #[test]
+    fn find_state_returns_none_without_match() {
+        let wf = workflow();
+        assert!(wf.find_state_by_name("xyz").is_none());
+    }
+
+    #[test]
+    fn unknown_state_type_deserializes_as_other() {
+        let json = r#"{"id": 1, "name": "Triage", "type": "triage", "position": 0}"#;
+        let state: WorkflowState = serde_json::from_str(json).unwrap();
+        assert_eq!(state.state_type, WorkflowStateType::Other);
+    }
+}
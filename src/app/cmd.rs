@@ -1,4 +1,4 @@
-use std::fs::{self, File};
+use std::fs::File;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::{
@@ -7,17 +7,28 @@ use std::{
 };
 
 use anyhow::{Context, Result};
+use futures::future::try_join_all;
 use slugify::slugify;
+use tempfile::NamedTempFile;
 use tokio::sync::mpsc::UnboundedSender;
 
 use crate::app::model::Model;
-use crate::error::ErrorInfo;
 use crate::{
-    api::{ApiClient, story::Story},
-    app::msg::Msg,
+    api::{
+        backend::{Backend, BackendSource},
+        error::{ApiError, ApiResult},
+        group::resolve_groups,
+        story::Story,
+    },
+    app::{
+        init::fetch_info_from_api,
+        msg::{Msg, Reconnection},
+    },
     config::{Config, Mux},
     dbg_file,
-    note::Note,
+    error::ErrorInfo,
+    note::{self, Note, tasks::TaskSync},
+    outbox::{Mutation, PendingMutation, Saved},
     zellij, tmux,
 };
 
@@ -34,11 +45,57 @@ pub enum Cmd {
     FetchStories {
         iteration_ids: Vec<i32>,
     },
+    /// Edits name, type, estimate, labels, deadline and description together in one file
+    EditStory {
+        story_id: i32,
+    },
+    /// Sends the mutation, or queues it when offline, see `submit_mutation`
+    SubmitMutation {
+        story_id: i32,
+        mutation: Mutation,
+    },
+    /// Shows a story linked from the one in the description modal, or opens its note.
+    /// Fetched first when it isn't one we have loaded.
+    OpenLinkedStory {
+        story_id: i32,
+        as_note: bool,
+    },
+    /// Syncs the task blocks in the notes of these stories with Shortcut
+    SyncNoteTasks(Vec<Story>),
+    /// Adds a task per line written in the editor
+    AddTasks {
+        story_id: i32,
+    },
+    CreateStory {
+        iteration_id: Option<i32>,
+        epic_id: Option<i32>,
+    },
     EditStoryContent {
         story_id: i32,
         description: String,
     },
+    EditComment {
+        story_id: i32,
+        /// `Some` when editing an existing comment, `None` to post a new one
+        comment_id: Option<i32>,
+        parent_id: Option<i32>,
+        text: String,
+    },
     FetchEpics,
+    /// Current and all iterations, loading the current ones' stories once they arrive
+    FetchIterations,
+    /// Everyone's stories in the given iterations, or the backlog of the given groups if any
+    FetchTeamStories {
+        iteration_ids: Vec<i32>,
+        groups: Vec<String>,
+    },
+    SearchStories {
+        query: String,
+    },
+    UpdateStoryState {
+        story_id: i32,
+        workflow_state_id: i32,
+    },
     SelectStory(Option<Story>),
     ActionMenuVisibility(bool),
     CreateGitWorktree {
@@ -69,29 +126,183 @@ pub enum Cmd {
         name: String,
     },
     WriteTodos,
+    WriteActivity,
+    WriteOutbox,
+    /// Sends everything in the outbox, see `outbox::replay`
+    ReplayOutbox,
+    /// Builds a new backend in the background, for when we started without reaching Shortcut
+    /// or the config was edited. Sends `Msg::Reconnected` once it's ready.
+    Reconnect {
+        source: BackendSource,
+        config: Config,
+    },
+    /// Everything fetched on startup, from scratch
+    FetchInfo,
+    /// Opens the config file in the editor and reconnects with the edited config
+    EditConfig,
+}
+
+/// Resolves the configured groups and loads their stories, or every story in the iterations
+/// when no groups are configured. Also returns configured groups that don't exist.
+async fn fetch_team_stories(
+    backend: &Backend,
+    iteration_ids: Vec<i32>,
+    groups: &[String],
+    known: Vec<Story>,
+) -> ApiResult<(Vec<Story>, Vec<String>)> {
+    if groups.is_empty() {
+        return Ok((
+            backend.get_iteration_stories(iteration_ids, known).await?,
+            Vec::new(),
+        ));
+    }
+
+    let (group_ids, unknown) = resolve_groups(groups, &backend.get_groups().await?);
+    let group_stories =
+        try_join_all(group_ids.into_iter().map(|id| backend.get_group_stories(id, known.clone())))
+            .await?;
+
+    // a story can only be in one group, but configs can list the same group twice
+    let mut stories: Vec<Story> = Vec::new();
+    for story in group_stories.into_iter().flatten() {
+        if !stories.iter().any(|s| s.id == story.id) {
+            stories.push(story);
+        }
+    }
+
+    Ok((stories, unknown))
+}
+
+/// Sends a mutation and reports back how it went. It goes to the outbox instead when Shortcut
+/// can't be reached, or straight away with `queue_only` so it can't overtake queued ones.
+/// Returns false when Shortcut rejected it.
+pub(crate) async fn submit_mutation(
+    backend: &Backend,
+    sender: &UnboundedSender<Msg>,
+    pending: PendingMutation,
+    queue_only: bool,
+) -> bool {
+    if queue_only {
+        sender.send(Msg::MutationQueued(pending)).ok();
+        return true;
+    }
+
+    let story_id = pending.story_id;
+    let msg = match (pending.mutation.send(backend, story_id).await, &pending.mutation) {
+        (Err(ApiError::Network(_)), _) => Msg::MutationQueued(pending),
+        (Err(error), mutation) => {
+            let context = match mutation {
+                Mutation::UpdateDescription { .. } => "Failed to update description".to_string(),
+                Mutation::UpdateState { .. } => format!("Failed to update state of sc-{}", story_id),
+                Mutation::UpdateStory { .. } => format!("Failed to update sc-{}", story_id),
+                Mutation::CreateComment { .. } | Mutation::UpdateComment { .. } => {
+                    "Failed to save comment".to_string()
+                }
+                Mutation::CreateTask { .. }
+                | Mutation::SetTaskComplete { .. }
+                | Mutation::RenameTask { .. }
+                | Mutation::DeleteTask { .. } => {
+                    format!("Failed to update tasks of sc-{}", story_id)
+                }
+            };
+            // a task that's gone is also a 404, which mustn't drop the whole story
+            let is_task = matches!(
+                mutation,
+                Mutation::CreateTask { .. }
+                    | Mutation::SetTaskComplete { .. }
+                    | Mutation::RenameTask { .. }
+                    | Mutation::DeleteTask { .. }
+            );
+
+            sender
+                .send(Msg::ApiError {
+                    context,
+                    error,
+                    story_id: (!is_task).then_some(story_id),
+                })
+                .ok();
+            return false;
+        }
+        (Ok(Saved::Comment(comment)), _) => Msg::CommentSaved { story_id, comment },
+        (Ok(Saved::Task(task)), _) => Msg::TaskSaved { story_id, task },
+        (Ok(Saved::Nothing), Mutation::DeleteTask { task_id }) => Msg::TaskDeleted {
+            story_id,
+            task_id: *task_id,
+        },
+        (Ok(Saved::Story(story)), _) => Msg::StoryUpdated(*story),
+        (Ok(Saved::Nothing), Mutation::UpdateState {
+            workflow_state_id, ..
+        }) => Msg::StoryStateUpdated {
+            story_id,
+            workflow_state_id: *workflow_state_id,
+        },
+        (Ok(Saved::Nothing), Mutation::UpdateDescription { description, .. }) => {
+            Msg::DescriptionUpdated {
+                story_id,
+                description: description.clone(),
+            }
+        }
+        (Ok(Saved::Nothing), _) => return true,
+    };
+
+    sender.send(msg).ok();
+    true
+}
+
+/// Sends the changes made in a note's task block. Its base is only moved on once Shortcut has
+/// all of them, so whatever it rejected is sent again on the next sync instead of being taken
+/// as synced.
+pub(crate) async fn push_note_tasks(
+    backend: &Backend,
+    sender: &UnboundedSender<Msg>,
+    cache_dir: &Path,
+    story_id: i32,
+    sync: &TaskSync,
+    queue_only: bool,
+) -> Result<()> {
+    let mut all_sent = true;
+    for mutation in &sync.mutations {
+        let pending = PendingMutation::new(story_id, mutation.clone());
+        all_sent &= submit_mutation(backend, sender, pending, queue_only).await;
+    }
+
+    if all_sent {
+        note::tasks::save_base(cache_dir, story_id, sync.base()).await?;
+    }
+    Ok(())
 }
 
 pub async fn execute(
     cmd: Cmd,
     sender: UnboundedSender<Msg>,
     model: &mut Model,
-    api_client: &ApiClient,
+    backend: &Backend,
 ) -> Result<()> {
     match cmd {
         Cmd::None => Ok(()),
 
         Cmd::WriteCache => {
-            model.cache.write().await?;
-            sender.send(Msg::CacheWritten).ok();
+            let changed_elsewhere = model.cache.write().await?;
+            sender
+                .send(if changed_elsewhere {
+                    Msg::CacheMerged
+                } else {
+                    Msg::CacheWritten
+                })
+                .ok();
             Ok(())
         }
 
         Cmd::FetchStories { iteration_ids } => {
             let sender = sender.clone();
-            let api_client = api_client.clone();
+            let backend = backend.clone();
+            let known = model.data.stories.clone();
 
             let handle = tokio::spawn(async move {
-                match api_client.get_owned_iteration_stories(iteration_ids).await {
+                match backend
+                    .get_owned_iteration_stories(iteration_ids, known)
+                    .await
+                {
                     Ok(stories) => {
                         sender
                             .send(Msg::StoriesLoaded {
@@ -100,13 +311,14 @@ pub async fn execute(
                             })
                             .ok();
                     }
-                    Err(e) => {
-                        let info = ErrorInfo::new(
-                            "Failed to get stories for current iteration".to_string(),
-                            e.to_string(),
-                        );
-
-                        sender.send(Msg::Error(info)).ok();
+                    Err(error) => {
+                        sender
+                            .send(Msg::ApiError {
+                                context: "Failed to get stories for current iteration".to_string(),
+                                error,
+                                story_id: None,
+                            })
+                            .ok();
                     }
                 }
             });
@@ -116,19 +328,70 @@ pub async fn execute(
             Ok(())
         }
 
+        Cmd::FetchIterations => {
+            let known = model.data.iterations.clone();
+
+            let current_sender = sender.clone();
+            let current_backend = backend.clone();
+            let current_known = known.clone();
+            let current_handle = tokio::spawn(async move {
+                match current_backend.get_current_iterations(current_known).await {
+                    Ok(iterations) => {
+                        current_sender.send(Msg::IterationsLoaded(iterations)).ok();
+                    }
+                    Err(error) => {
+                        current_sender
+                            .send(Msg::ApiError {
+                                context: "Failed to fetch current iteration info".to_string(),
+                                error,
+                                story_id: None,
+                            })
+                            .ok();
+                    }
+                }
+            });
+
+            let sender = sender.clone();
+            let backend = backend.clone();
+            let all_handle = tokio::spawn(async move {
+                match backend.get_all_iterations(known).await {
+                    Ok(iterations) => {
+                        sender.send(Msg::AllIterationsLoaded(iterations)).ok();
+                    }
+                    Err(error) => {
+                        sender
+                            .send(Msg::ApiError {
+                                context: "Failed to fetch all iterations".to_string(),
+                                error,
+                                story_id: None,
+                            })
+                            .ok();
+                    }
+                }
+            });
+
+            model.data.async_handles.push(current_handle);
+            model.data.async_handles.push(all_handle);
+            Ok(())
+        }
+
         Cmd::FetchEpics => {
             let sender = sender.clone();
-            let api_client = api_client.clone();
+            let backend = backend.clone();
 
             let handle = tokio::spawn(async move {
-                match api_client.get_all_epics_slim(false).await {
+                match backend.get_all_epics_slim(false).await {
                     Ok(epics) => {
                         sender.send(Msg::EpicsLoaded(epics)).ok();
                     }
-                    Err(e) => {
-                        let info =
-                            ErrorInfo::new("Failed to fetch epics".to_string(), e.to_string());
-                        sender.send(Msg::Error(info)).ok();
+                    Err(error) => {
+                        sender
+                            .send(Msg::ApiError {
+                                context: "Failed to fetch epics".to_string(),
+                                error,
+                                story_id: None,
+                            })
+                            .ok();
                     }
                 }
             });
@@ -137,6 +400,203 @@ pub async fn execute(
             Ok(())
         }
 
+        Cmd::FetchTeamStories {
+            iteration_ids,
+            groups,
+        } => {
+            let sender = sender.clone();
+            let backend = backend.clone();
+            let known = model.data.team_stories.clone();
+
+            let handle = tokio::spawn(async move {
+                match fetch_team_stories(&backend, iteration_ids, &groups, known).await {
+                    Ok((stories, unknown_groups)) => {
+                        if !unknown_groups.is_empty() {
+                            sender
+                                .send(Msg::Error(ErrorInfo::new(
+                                    "Unknown groups in config",
+                                    unknown_groups.join(", "),
+                                )))
+                                .ok();
+                        }
+                        sender.send(Msg::TeamStoriesLoaded(stories)).ok();
+                    }
+                    Err(error) => {
+                        sender
+                            .send(Msg::ApiError {
+                                context: "Failed to get team stories".to_string(),
+                                error,
+                                story_id: None,
+                            })
+                            .ok();
+                    }
+                }
+            });
+
+            model.data.async_handles.push(handle);
+            Ok(())
+        }
+
+        Cmd::SearchStories { query } => {
+            let sender = sender.clone();
+            let backend = backend.clone();
+
+            let handle = tokio::spawn(async move {
+                match backend.search_stories(&query).await {
+                    Ok(stories) => {
+                        sender.send(Msg::StorySearchLoaded { query, stories }).ok();
+                    }
+                    Err(error) => {
+                        sender.send(Msg::StorySearchFailed { query, error }).ok();
+                    }
+                }
+            });
+
+            model.data.async_handles.push(handle);
+            Ok(())
+        }
+
+        Cmd::UpdateStoryState {
+            story_id,
+            workflow_state_id,
+        } => {
+            let sender = sender.clone();
+            let backend = backend.clone();
+            let original = model
+                .data
+                .find_story(story_id)
+                .map_or(workflow_state_id, |s| s.workflow_state_id);
+            let pending = PendingMutation::new(
+                story_id,
+                Mutation::UpdateState {
+                    workflow_state_id,
+                    original,
+                },
+            );
+            let queue_only = !model.data.outbox.is_empty();
+
+            let handle = tokio::spawn(async move {
+                submit_mutation(&backend, &sender, pending, queue_only).await;
+            });
+
+            model.data.async_handles.push(handle);
+            Ok(())
+        }
+
+        Cmd::SubmitMutation { story_id, mutation } => {
+            let sender = sender.clone();
+            let backend = backend.clone();
+            let pending = PendingMutation::new(story_id, mutation);
+            let queue_only = !model.data.outbox.is_empty();
+
+            let handle = tokio::spawn(async move {
+                submit_mutation(&backend, &sender, pending, queue_only).await;
+            });
+
+            model.data.async_handles.push(handle);
+            Ok(())
+        }
+
+        Cmd::OpenLinkedStory { story_id, as_note } => {
+            let loaded = model
+                .data
+                .find_story(story_id)
+                .or(model.data.active_story.as_ref())
+                .filter(|s| s.id == story_id);
+            if let Some(story) = loaded {
+                let story = story.clone();
+                sender.send(Msg::LinkedStoryLoaded { story, as_note }).ok();
+                return Ok(());
+            }
+
+            let sender = sender.clone();
+            let backend = backend.clone();
+            let handle = tokio::spawn(async move {
+                let msg = match backend.get_story(story_id).await {
+                    Ok(story) => Msg::LinkedStoryLoaded { story, as_note },
+                    Err(error) => Msg::ApiError {
+                        context: format!("Failed to load sc-{}", story_id),
+                        error,
+                        // a deleted link target isn't one of our stories to drop
+                        story_id: None,
+                    },
+                };
+                sender.send(msg).ok();
+            });
+
+            model.data.async_handles.push(handle);
+            Ok(())
+        }
+
+        Cmd::SyncNoteTasks(stories) => {
+            let sender = sender.clone();
+            let backend = backend.clone();
+            let notes_dir = model.config.notes_dir.clone();
+            let cache_dir = model.config.cache_dir.clone();
+            let queue_only = !model.data.outbox.is_empty();
+
+            let handle = tokio::spawn(async move {
+                for story in stories {
+                    let sync =
+                        note::tasks::sync_story_note(&backend, &notes_dir, &cache_dir, &story);
+                    let sync = match sync.await {
+                        Ok(Some(sync)) => sync,
+                        Ok(None) => continue,
+                        Err(e) => {
+                            sender
+                                .send(Msg::Error(ErrorInfo::new(
+                                    format!("Failed to sync the tasks in the sc-{} note", story.id),
+                                    e,
+                                )))
+                                .ok();
+                            continue;
+                        }
+                    };
+
+                    let pushed =
+                        push_note_tasks(&backend, &sender, &cache_dir, story.id, &sync, queue_only);
+                    if let Err(e) = pushed.await {
+                        sender
+                            .send(Msg::Error(ErrorInfo::new(
+                                format!("Failed to sync the tasks in the sc-{} note", story.id),
+                                e,
+                            )))
+                            .ok();
+                    }
+
+                    if !sync.conflicts.is_empty() {
+                        let short = format!("Tasks in the sc-{} note changed in Shortcut", story.id);
+                        sender
+                            .send(Msg::Error(ErrorInfo::new(short, sync.conflicts.join("\n"))))
+                            .ok();
+                    }
+                }
+            });
+
+            model.data.async_handles.push(handle);
+            Ok(())
+        }
+
+        Cmd::WriteOutbox => {
+            crate::outbox::save_outbox(&model.config.cache_dir, &model.data.outbox).await?;
+            Ok(())
+        }
+
+        Cmd::ReplayOutbox => {
+            let sender = sender.clone();
+            let backend = backend.clone();
+            let pending = model.data.outbox.clone();
+            let cache_dir = model.config.cache_dir.clone();
+
+            let handle = tokio::spawn(async move {
+                let outcomes = crate::outbox::replay(&backend, pending, &cache_dir).await;
+                sender.send(Msg::OutboxReplayed(outcomes)).ok();
+            });
+
+            model.data.async_handles.push(handle);
+            Ok(())
+        }
+
         Cmd::SelectStory(story) => {
             if let Some(active_story) = &model.data.active_story
                 && let Some(story) = &story
@@ -154,7 +614,7 @@ pub async fn execute(
 
         Cmd::Batch(commands) => {
             for cmd in commands {
-                Box::pin(execute(cmd, sender.clone(), model, api_client)).await?;
+                Box::pin(execute(cmd, sender.clone(), model, backend)).await?;
             }
 
             Ok(())
@@ -174,7 +634,55 @@ pub async fn execute(
         }
 
         Cmd::WriteTodos => {
-            crate::todos::save_todos(&model.config.cache_dir, &model.data.todos).await?;
+            let saved = crate::todos::save_todos(
+                &model.config.cache_dir,
+                &model.data.synced_todos,
+                &model.data.todos,
+            )
+            .await?;
+            model.data.todos = saved.clone();
+            model.data.synced_todos = saved;
+            Ok(())
+        }
+
+        Cmd::WriteActivity => {
+            crate::activity::save_activity(&model.config.cache_dir, &model.data.activity).await?;
+            Ok(())
+        }
+
+        Cmd::Reconnect { source, config } => {
+            let handle = tokio::spawn(async move {
+                match crate::connect(&source, &config, None).await {
+                    Ok(backend) => {
+                        sender.send(Msg::Reconnected(Reconnection { backend, config })).ok();
+                    }
+                    Err(e) => match e.downcast::<ApiError>() {
+                        Ok(error) => {
+                            sender
+                                .send(Msg::ApiError {
+                                    context: "Failed to connect to Shortcut".to_string(),
+                                    error,
+                                    story_id: None,
+                                })
+                                .ok();
+                        }
+                        Err(e) => {
+                            sender
+                                .send(Msg::Error(ErrorInfo::new("Failed to reconnect", e)))
+                                .ok();
+                        }
+                    },
+                }
+            });
+
+            model.data.async_handles.push(handle);
+            Ok(())
+        }
+
+        Cmd::FetchInfo => {
+            // the new token may belong to another workspace, so nothing cached is reused
+            let handles = fetch_info_from_api(backend.clone(), sender, Vec::new()).await;
+            model.data.async_handles.extend(handles);
             Ok(())
         }
 
@@ -186,11 +694,16 @@ pub async fn execute(
         Cmd::OpenNote { .. }
         | Cmd::OpenIterationNote { .. }
         | Cmd::OpenEpicNote { .. }
+        | Cmd::EditStory { .. }
+        | Cmd::CreateStory { .. }
+        | Cmd::AddTasks { .. }
         | Cmd::EditStoryContent { .. }
+        | Cmd::EditComment { .. }
         | Cmd::CreateGitWorktree { .. }
         | Cmd::OpenDailyNote { .. }
         | Cmd::OpenScratchNote { .. }
-        | Cmd::OpenTmuxSession { .. } => {
+        | Cmd::OpenTmuxSession { .. }
+        | Cmd::EditConfig => {
             unreachable!("TUI-suspending commands should be handled in main_loop")
         }
     }
@@ -220,6 +733,18 @@ pub fn open_in_editor(config: &Config, path: &Path) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Opens `contents` in the editor via a temp file and returns the edited text
+pub fn edit_in_tempfile(editor: &str, contents: &str) -> anyhow::Result<String> {
+    let mut tempfile = NamedTempFile::new()?;
+    tempfile.write_all(contents.as_bytes())?;
+    let tmp_path = tempfile.path().to_path_buf();
+
+    Command::new(editor).arg(&tmp_path).status()?;
+
+    let edited = read_to_string(&tmp_path)?;
+    Ok(edited)
+}
+
 pub fn open_note_in_editor(
     story_id: i32,
     story_name: String,
@@ -227,6 +752,20 @@ pub fn open_note_in_editor(
     iteration_app_url: Option<String>,
     config: &Config,
 ) -> anyhow::Result<()> {
+    let path = create_story_note(story_id, story_name, story_app_url, iteration_app_url, config)?;
+    open_in_editor(config, &path)?;
+
+    Ok(())
+}
+
+/// Creates the story's note with its frontmatter unless it already exists, returning its path
+pub fn create_story_note(
+    story_id: i32,
+    story_name: String,
+    story_app_url: String,
+    iteration_app_url: Option<String>,
+    config: &Config,
+) -> anyhow::Result<PathBuf> {
     let note = Note::new(
         &config.notes_dir,
         story_id,
@@ -250,9 +789,7 @@ pub fn open_note_in_editor(
         std::fs::write(&note.path, frontmatter_string)?;
     }
 
-    open_in_editor(config, &note.path)?;
-
-    Ok(())
+    Ok(note.path)
 }
 
 pub fn open_iteration_note_in_editor(
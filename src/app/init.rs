@@ -3,154 +3,174 @@ use tokio::{sync::mpsc::{self, UnboundedSender}, task::JoinHandle};
 use uuid::Uuid;
 
 use crate::{
-    api::ApiClient,
+    api::{backend::{Backend, BackendSource}, error::ApiError, iteration::Iteration},
     app::{
         App,
-        model::{DataState, Model, UiState},
+        model::{LoadingState, Model},
         msg::Msg,
     },
     cache::Cache,
     config::Config,
-    dummy,
-    error::ErrorInfo,
-    get_user_id,
+    connect,
 };
 
 impl App {
-    pub async fn init() -> Result<Self> {
+    pub async fn init(backend_source: BackendSource) -> Result<Self> {
         let config = Config::read()?;
-        let mut cache = Cache::read(config.cache_dir.clone()).await;
-
-        if dummy::is_enabled() {
-            return Self::init_with_dummy_data(config, cache).await;
+        // only the model sees the scratch cache dir of a fake backend, `config` is written back
+        let mut model_config = config.clone();
+        model_config.cache_dir = backend_source.cache_dir(&config.cache_dir);
+        let mut cache = Cache::read(model_config.cache_dir.clone()).await;
+
+        // a cached user id means we never need the network to start, without one we can
+        // still start offline from the cache and look it up once Shortcut is reachable
+        let (backend, needs_reconnect) =
+            match connect(&backend_source, &config, cache.user_id).await {
+                Ok(backend) => (backend, false),
+                Err(e) if matches!(e.downcast_ref(), Some(ApiError::Network(_))) => {
+                    let backend = connect(&backend_source, &config, Some(Uuid::nil())).await?;
+                    (backend, true)
+                }
+                Err(e) => return Err(e),
+            };
+
+        if !needs_reconnect {
+            cache.user_id = Some(backend.user_id());
+            cache.write().await?;
         }
 
-        let api_client = {
-            let user_id = get_user_id(cache.user_id, &config.api_token).await?;
-            ApiClient::new(config.api_token.to_owned(), user_id)
-        };
-
-        cache.user_id = Some(api_client.user_id);
-        cache.write().await?;
-
-        let todos = crate::todos::load_todos(&config.cache_dir).await;
-
-        let (sender, receiver) = mpsc::unbounded_channel();
-        let sender_clone = sender.clone();
-
-        let mut model = Model::from_cache_and_config(cache, config.clone(), todos);
-
-        let handles = fetch_info_from_api(api_client.clone(), sender).await;
-        model.data.async_handles.extend(handles);
-
-        Ok(App {
-            model,
-            exit: false,
-            receiver,
-            sender: sender_clone,
-            api_client,
-            config,
-        })
-    }
-
-    async fn init_with_dummy_data(config: Config, mut cache: Cache) -> Result<Self> {
-        let dummy_user_id = Uuid::nil();
-        let api_client = ApiClient::new(config.api_token.to_owned(), dummy_user_id);
-
-        cache.user_id = Some(dummy_user_id);
+        let todos = crate::todos::load_todos(&model_config.cache_dir).await;
+        let activity = crate::activity::load_activity(&model_config.cache_dir).await;
+        let outbox = crate::outbox::load_outbox(&model_config.cache_dir).await;
 
         let (sender, receiver) = mpsc::unbounded_channel();
         let sender_clone = sender.clone();
 
-        let iteration = dummy::iteration();
-        let stories = dummy::stories();
-
-        let model = Model {
-            data: DataState {
-                stories: stories.clone(),
-                epics: Vec::new(),
-                current_iterations: Some(vec![iteration.clone()]),
-                active_story: None,
-                async_handles: Vec::new(),
-                iterations: vec![iteration.clone()],
-                todos: Vec::new(),
-            },
-            ui: UiState::default(),
-            config: config.clone(),
-            cache,
-        };
-
-        // Send messages so UI updates as if data loaded normally
-        let _ = sender.send(Msg::IterationsLoaded(vec![iteration.clone()]));
-        let _ = sender.send(Msg::AllIterationsLoaded(vec![iteration]));
-        let _ = sender.send(Msg::StoriesLoaded {
-            stories,
-            from_cache: false,
-        });
+        let mut model = Model::from_cache_and_config(cache, model_config, todos, activity);
+        model.data.outbox = outbox;
+
+        model.ui.refresh_started_at = Some(crate::time::now_naive());
+        if needs_reconnect {
+            model.ui.offline = true;
+            model.ui.loading = LoadingState::Loaded;
+        } else {
+            let known_iterations = model.data.iterations.clone();
+            let handles = fetch_info_from_api(backend.clone(), sender, known_iterations).await;
+            model.data.async_handles.extend(handles);
+        }
 
         Ok(App {
             model,
             exit: false,
             receiver,
             sender: sender_clone,
-            api_client,
+            backend,
+            backend_source,
             config,
+            needs_reconnect,
         })
     }
 }
 
-async fn fetch_info_from_api(api_client: ApiClient, sender: UnboundedSender<Msg>) -> Vec<JoinHandle<()>> {
-    let iteration_client = api_client.clone();
+/// `known_iterations` are reused for iterations that haven't changed since they were cached
+pub(crate) async fn fetch_info_from_api(
+    backend: Backend,
+    sender: UnboundedSender<Msg>,
+    known_iterations: Vec<Iteration>,
+) -> Vec<JoinHandle<()>> {
+    let current_known = known_iterations.clone();
+    let iteration_client = backend.clone();
     let iteration_sender = sender.clone();
     let current_iteration_handle = tokio::spawn(async move {
-        match iteration_client.get_current_iterations().await {
+        match iteration_client
+            .get_current_iterations(current_known)
+            .await {
             Ok(iterations) => {
                 let _ = iteration_sender.send(Msg::IterationsLoaded(iterations));
             }
-            Err(e) => {
-                let info = ErrorInfo::new(
-                    "Failed to fetch current iteration info".to_string(),
-                    e.to_string(),
-                );
-
-                let _ = iteration_sender.send(Msg::Error(info));
+            Err(error) => {
+                let _ = iteration_sender.send(Msg::ApiError {
+                    context: "Failed to fetch current iteration info".to_string(),
+                    error,
+                    story_id: None,
+                });
             }
         };
     });
 
-    let all_iter_client = api_client.clone();
+    let workflows_client = backend.clone();
+    let workflows_sender = sender.clone();
+    let workflows_handle = tokio::spawn(async move {
+        match workflows_client.get_workflows().await {
+            Ok(workflows) => {
+                let _ = workflows_sender.send(Msg::WorkflowsLoaded(workflows));
+            }
+            Err(error) => {
+                let _ = workflows_sender.send(Msg::ApiError {
+                    context: "Failed to fetch workflows".to_string(),
+                    error,
+                    story_id: None,
+                });
+            }
+        }
+    });
+
+    let members_client = backend.clone();
+    let members_sender = sender.clone();
+    let members_handle = tokio::spawn(async move {
+        match members_client.get_members().await {
+            Ok(members) => {
+                let _ = members_sender.send(Msg::MembersLoaded(members));
+            }
+            Err(error) => {
+                let _ = members_sender.send(Msg::ApiError {
+                    context: "Failed to fetch members".to_string(),
+                    error,
+                    story_id: None,
+                });
+            }
+        }
+    });
+
+    let all_iter_client = backend.clone();
     let all_iter_sender = sender.clone();
 
     let epics_handle = tokio::spawn(async move {
-        match api_client.get_all_epics_slim(false).await {
+        match backend.get_all_epics_slim(false).await {
             Ok(epics) => {
                 let _ = sender.send(Msg::EpicsLoaded(epics));
             }
-            Err(e) => {
-                let info = ErrorInfo::new(
-                    "Failed to fetch epics".to_string(),
-                    e.to_string(),
-                );
-                let _ = sender.send(Msg::Error(info));
+            Err(error) => {
+                let _ = sender.send(Msg::ApiError {
+                    context: "Failed to fetch epics".to_string(),
+                    error,
+                    story_id: None,
+                });
             }
         }
     });
 
     let all_iterations_handle = tokio::spawn(async move {
-        match all_iter_client.get_all_iterations().await {
+        match all_iter_client.get_all_iterations(known_iterations).await {
             Ok(iterations) => {
                 let _ = all_iter_sender.send(Msg::AllIterationsLoaded(iterations));
             }
-            Err(e) => {
-                let info = ErrorInfo::new(
-                    "Failed to fetch all iterations".to_string(),
-                    e.to_string(),
-                );
-                let _ = all_iter_sender.send(Msg::Error(info));
+            Err(error) => {
+                let _ = all_iter_sender.send(Msg::ApiError {
+                    context: "Failed to fetch all iterations".to_string(),
+                    error,
+                    story_id: None,
+                });
             }
         }
     });
 
-    vec![current_iteration_handle, epics_handle, all_iterations_handle]
+    vec![
+        current_iteration_handle,
+        workflows_handle,
+        members_handle,
+        epics_handle,
+        all_iterations_handle,
+    ]
 }
 
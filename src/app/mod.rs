@@ -1,30 +1,40 @@
-use std::fs::read_to_string;
-use std::io::Write;
-use std::time::Instant;
-
 use anyhow::Result;
 use crossterm::ExecutableCommand;
 use crossterm::terminal::{
     EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
 };
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
-use ratatui::widgets::{Block, Clear, Paragraph, StatefulWidget, WidgetRef, Widget};
+use ratatui::widgets::{Clear, StatefulWidget, WidgetRef, Widget};
 use ratatui::{DefaultTerminal, Frame};
-use tempfile::NamedTempFile;
 use tokio::sync::mpsc;
 
 use crate::app::pane::action_menu::ActionMenu;
-use crate::dbg_file;
+use crate::app::pane::state_picker::StatePicker;
 use crate::error::{ERROR_NOTIFICATION_MAX_HEIGHT, ErrorInfo};
+use crate::merge;
+use crate::note;
+use crate::story_edit::{self, NewStoryFields, StoryFields};
+use crate::outbox::{Mutation, PendingMutation};
+use crate::view::activity_list::ActivityListView;
 use crate::view::add_todo_modal::AddTodoModal;
 use crate::view::create_note_modal::CreateNoteModal;
 use crate::view::description_modal::{DescriptionModal, centered_rect};
 use crate::view::keybinds_panel::KeybindsPanel;
+use crate::view::search::SearchView;
 use crate::view::todos_list::TodosListView;
+use crate::view::token_prompt::TokenPrompt;
 use crate::view::{EpicListView, IterationListView};
 use crate::view::{navbar::NavBar, notes_list::NotesListView, story_list::StoryListView};
 use crate::worktree::{create_worktree, get_repo_list, select_repo_with_fzf};
-use crate::{api::{ApiClient, story::Story}, app::model::ViewType, config::Config};
+use crate::{
+    api::{
+        backend::{Backend, BackendSource},
+        error::ApiError,
+        story::Story,
+    },
+    app::model::ViewType,
+    config::Config,
+};
 
 pub mod cmd;
 pub mod init;
@@ -33,13 +43,25 @@ pub mod msg;
 pub mod pane;
 pub mod update;
 
+enum FetchedStory {
+    Story(Box<Story>),
+    /// Shortcut can't be reached
+    Offline,
+    /// Any other error, already reported
+    Failed,
+}
+
 pub struct App {
     pub model: model::Model,
     pub exit: bool,
     pub receiver: mpsc::UnboundedReceiver<msg::Msg>,
     pub sender: mpsc::UnboundedSender<msg::Msg>,
-    pub api_client: ApiClient,
+    pub backend: Backend,
+    /// Kept so the backend can be rebuilt after the config changes
+    pub backend_source: BackendSource,
     pub config: Config,
+    /// Set when we started without reaching Shortcut, the next refresh connects properly
+    pub needs_reconnect: bool,
 }
 
 impl App {
@@ -55,11 +77,16 @@ impl App {
                         cmd::Cmd::OpenNote { .. }
                         | cmd::Cmd::OpenIterationNote { .. }
                         | cmd::Cmd::OpenEpicNote { .. }
+                        | cmd::Cmd::EditStory { .. }
+                        | cmd::Cmd::CreateStory { .. }
+                        | cmd::Cmd::AddTasks { .. }
                         | cmd::Cmd::EditStoryContent { .. }
+                        | cmd::Cmd::EditComment { .. }
                         | cmd::Cmd::CreateGitWorktree { .. }
                         | cmd::Cmd::OpenDailyNote { .. }
                         | cmd::Cmd::OpenScratchNote { .. }
-                        | cmd::Cmd::OpenTmuxSession { .. } => {
+                        | cmd::Cmd::OpenTmuxSession { .. }
+                        | cmd::Cmd::EditConfig => {
                             self.handle_suspended_cmd(cmd, terminal).await?;
                         }
                         _ => {
@@ -67,7 +94,7 @@ impl App {
                                 cmd,
                                 self.sender.clone(),
                                 &mut self.model,
-                                &self.api_client,
+                                &self.backend,
                             )
                             .await?;
                         }
@@ -91,15 +118,18 @@ impl App {
                 story_app_url,
                 iteration_app_url,
             } => {
-                with_suspended_tui(terminal, || {
-                    cmd::open_note_in_editor(
-                        story_id,
-                        story_name,
-                        story_app_url,
-                        iteration_app_url,
-                        &self.model.config,
-                    )
-                })?;
+                let config = &self.model.config;
+                let path = cmd::create_story_note(
+                    story_id,
+                    story_name,
+                    story_app_url,
+                    iteration_app_url,
+                    config,
+                )?;
+                // so a new note already has the task block, edits are pushed on NoteOpened
+                self.pull_note_tasks(story_id).await;
+
+                with_suspended_tui(terminal, || cmd::open_in_editor(&self.model.config, &path))?;
                 self.sender.send(msg::Msg::NoteOpened).ok();
             }
 
@@ -119,29 +149,60 @@ impl App {
                 self.sender.send(msg::Msg::NoteOpened).ok();
             }
 
+            cmd::Cmd::EditStory { story_id } => self.edit_story(terminal, story_id).await?,
+
+            cmd::Cmd::AddTasks { story_id } => {
+                let editor = self.model.config.editor.clone();
+                let written =
+                    with_suspended_tui(terminal, || cmd::edit_in_tempfile(&editor, ""))?;
+
+                // sent one after another so they keep the order they were written in
+                for line in written.lines().map(str::trim).filter(|l| !l.is_empty()) {
+                    let mutation = Mutation::CreateTask {
+                        description: line.to_string(),
+                    };
+                    self.submit_mutation(PendingMutation::new(story_id, mutation)).await;
+                }
+            }
+
+            cmd::Cmd::CreateStory {
+                iteration_id,
+                epic_id,
+            } => self.create_story(terminal, iteration_id, epic_id).await?,
+
             cmd::Cmd::EditStoryContent {
                 story_id,
                 description,
+            } => self.edit_description(terminal, story_id, description).await?,
+
+            cmd::Cmd::EditComment {
+                story_id,
+                comment_id,
+                parent_id,
+                text,
             } => {
                 let config_editor = self.model.config.editor.clone();
                 let edited = with_suspended_tui(terminal, || {
-                    let mut tempfile = NamedTempFile::new()?;
-                    tempfile.write_all(description.as_bytes())?;
-                    let tmp_path = tempfile.path().to_path_buf();
-
-                    std::process::Command::new(&config_editor)
-                        .arg(&tmp_path)
-                        .status()?;
-
-                    let contents = read_to_string(&tmp_path)?;
-                    Ok(contents)
+                    cmd::edit_in_tempfile(&config_editor, &text)
                 })?;
 
-                if edited != description {
-                    self.api_client
-                        .update_story_description(story_id, edited)
-                        .await?;
+                let edited = edited.trim().to_string();
+                if edited.is_empty() || edited == text.trim() {
+                    return Ok(());
                 }
+
+                let mutation = match comment_id {
+                    Some(comment_id) => Mutation::UpdateComment {
+                        comment_id,
+                        text: edited,
+                        original: text,
+                    },
+                    None => Mutation::CreateComment {
+                        text: edited,
+                        parent_id,
+                    },
+                };
+                self.submit_mutation(PendingMutation::new(story_id, mutation)).await;
             }
 
             cmd::Cmd::CreateGitWorktree { branch_name } => {
@@ -199,12 +260,266 @@ impl App {
                 })?;
             }
 
+            cmd::Cmd::EditConfig => {
+                self.model.ui.show_token_prompt = false;
+                let path = Config::file_path()?;
+                let config = self.model.config.clone();
+                with_suspended_tui(terminal, || cmd::open_in_editor(&config, &path))?;
+
+                match Config::read() {
+                    Ok(config) => {
+                        let reconnect = cmd::Cmd::Reconnect {
+                            source: self.backend_source.clone(),
+                            config,
+                        };
+                        cmd::execute(reconnect, self.sender.clone(), &mut self.model, &self.backend)
+                            .await?;
+                    }
+                    Err(e) => {
+                        self.model
+                            .ui
+                            .errors
+                            .push(ErrorInfo::new("Failed to read config", e));
+                    }
+                }
+            }
+
             _ => unreachable!("Non-suspending command passed to handle_suspended_cmd"),
         }
 
         Ok(())
     }
 
+    /// Edits the description as it is in Shortcut now rather than as it was when the list
+    /// loaded. If a teammate changes it while the editor is open their changes are merged in,
+    /// and the editor is opened again with conflict markers where we both changed the same lines.
+    async fn edit_description(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        story_id: i32,
+        listed_description: String,
+    ) -> Result<()> {
+        let (mut base, mut base_updated_at) = match self.fetch_for_edit(story_id).await {
+            FetchedStory::Story(story) => (story.description, story.updated_at),
+            // offline, the outbox checks for conflicts when it replays instead
+            FetchedStory::Offline => (listed_description, None),
+            FetchedStory::Failed => return Ok(()),
+        };
+        let mut text = base.clone();
+        let editor = self.model.config.editor.clone();
+
+        loop {
+            let edited = with_suspended_tui(terminal, || cmd::edit_in_tempfile(&editor, &text))?;
+            if edited == base {
+                return Ok(());
+            }
+
+            if merge::has_conflict_markers(&edited) {
+                self.save_unsent_edit(
+                    story_id,
+                    &edited,
+                    format!("sc-{} description still has conflict markers, not saved", story_id),
+                    "Resolve the conflicts before saving",
+                )
+                .await;
+                return Ok(());
+            }
+
+            let latest = match self.fetch_for_edit(story_id).await {
+                FetchedStory::Story(story) => *story,
+                FetchedStory::Offline => {
+                    self.submit_description(story_id, edited, base).await;
+                    return Ok(());
+                }
+                FetchedStory::Failed => {
+                    self.save_unsent_edit(
+                        story_id,
+                        &edited,
+                        format!("sc-{} description not saved", story_id),
+                        "Your edit was dropped",
+                    )
+                    .await;
+                    return Ok(());
+                }
+            };
+
+            if latest.updated_at == base_updated_at || latest.description == base {
+                self.submit_description(story_id, edited, base).await;
+                return Ok(());
+            }
+
+            let merged = merge::merge(&base, &edited, &latest.description);
+            (base, base_updated_at) = (latest.description, latest.updated_at);
+
+            if merged.conflicts == 0 {
+                self.model.ui.errors.push(ErrorInfo::new(
+                    format!("sc-{} description changed while you were editing", story_id),
+                    "Their changes were merged with yours",
+                ));
+                self.submit_description(story_id, merged.text, base).await;
+                return Ok(());
+            }
+
+            text = merged.text;
+        }
+    }
+
+    /// Edits several fields at once, see `story_edit`. Only the fields that were changed are
+    /// sent, so changes teammates made to the others in the meantime are kept.
+    async fn edit_story(&mut self, terminal: &mut DefaultTerminal, story_id: i32) -> Result<()> {
+        let story = match self.fetch_for_edit(story_id).await {
+            FetchedStory::Story(story) => *story,
+            FetchedStory::Offline => match self.model.data.find_story(story_id) {
+                Some(story) => story.clone(),
+                None => return Ok(()),
+            },
+            FetchedStory::Failed => return Ok(()),
+        };
+        let editor = self.model.config.editor.clone();
+        let fields = story_edit::edit_until_valid(
+            StoryFields::from_story(&story).to_file()?,
+            |text| with_suspended_tui(terminal, || cmd::edit_in_tempfile(&editor, text)),
+            StoryFields::parse,
+        )?;
+
+        let update = match fields {
+            Some(fields) => fields.changes_from(&story),
+            None => return Ok(()),
+        };
+        if !update.is_empty() {
+            let original = update.original_values(&story);
+            let mutation = Mutation::UpdateStory { update, original };
+            self.submit_mutation(PendingMutation::new(story_id, mutation)).await;
+        }
+        Ok(())
+    }
+
+    /// Creates a story from a template with `iteration_id` and `epic_id` prefilled, and adds it
+    /// to the list
+    async fn create_story(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        iteration_id: Option<i32>,
+        epic_id: Option<i32>,
+    ) -> Result<()> {
+        let editor = self.model.config.editor.clone();
+        let new = story_edit::edit_until_valid(
+            NewStoryFields::new(iteration_id, epic_id).to_file()?,
+            |text| with_suspended_tui(terminal, || cmd::edit_in_tempfile(&editor, text)),
+            NewStoryFields::parse,
+        )?;
+        let Some(new) = new else {
+            return Ok(());
+        };
+
+        match self.backend.create_story(new.to_new_story(self.backend.user_id())).await {
+            Ok(story) => {
+                self.sender
+                    .send(msg::Msg::StoryCreated {
+                        story,
+                        set_active: new.set_active,
+                    })
+                    .ok();
+            }
+            Err(error) => {
+                self.sender
+                    .send(msg::Msg::ApiError {
+                        context: "Failed to create story".to_string(),
+                        error,
+                        story_id: None,
+                    })
+                    .ok();
+            }
+        }
+        Ok(())
+    }
+
+    /// Keeps an edit that can't be sent in the cache dir, so it isn't lost
+    async fn save_unsent_edit(
+        &mut self,
+        story_id: i32,
+        edited: &str,
+        short: String,
+        unsaved: &str,
+    ) {
+        let saved_to =
+            crate::outbox::save_conflict(&self.model.config.cache_dir, story_id, edited).await;
+        let long = match saved_to {
+            Some(path) => format!("Your edit was saved to {}", path.display()),
+            None => unsaved.to_string(),
+        };
+        self.model.ui.errors.push(ErrorInfo::new(short, long));
+    }
+
+    async fn fetch_for_edit(&self, story_id: i32) -> FetchedStory {
+        match self.backend.get_story(story_id).await {
+            Ok(story) => FetchedStory::Story(Box::new(story)),
+            Err(ApiError::Network(_)) => FetchedStory::Offline,
+            Err(error) => {
+                self.sender
+                    .send(msg::Msg::ApiError {
+                        context: "Failed to fetch story".to_string(),
+                        error,
+                        story_id: Some(story_id),
+                    })
+                    .ok();
+                FetchedStory::Failed
+            }
+        }
+    }
+
+    async fn submit_description(&self, story_id: i32, description: String, original: String) {
+        let mutation = Mutation::UpdateDescription {
+            description,
+            original,
+        };
+        self.submit_mutation(PendingMutation::new(story_id, mutation)).await;
+    }
+
+    /// Pulls the story's tasks into its note before it's opened
+    async fn pull_note_tasks(&self, story_id: i32) {
+        let Some(story) = self
+            .model
+            .data
+            .find_story(story_id)
+            .or(self.model.data.active_story.as_ref())
+            .filter(|s| s.id == story_id)
+        else {
+            return;
+        };
+
+        let config = &self.model.config;
+        let synced =
+            note::tasks::sync_story_note(&self.backend, &config.notes_dir, &config.cache_dir, story)
+                .await;
+        let pushed = match synced {
+            Ok(Some(sync)) => {
+                let queue_only = !self.model.data.outbox.is_empty();
+                cmd::push_note_tasks(
+                    &self.backend,
+                    &self.sender,
+                    &config.cache_dir,
+                    story_id,
+                    &sync,
+                    queue_only,
+                )
+                .await
+            }
+            Ok(None) => Ok(()),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = pushed {
+            let short = format!("Failed to sync the tasks in the sc-{} note", story_id);
+            self.sender.send(msg::Msg::Error(ErrorInfo::new(short, e))).ok();
+        }
+    }
+
+    /// See `cmd::submit_mutation`, queued mutations go first so this one can't overtake them
+    async fn submit_mutation(&self, pending: PendingMutation) {
+        let queue_only = !self.model.data.outbox.is_empty();
+        cmd::submit_mutation(&self.backend, &self.sender, pending, queue_only).await;
+    }
+
     async fn poll_for_message(&mut self) -> Result<Option<msg::Msg>> {
         use crossterm::event::{self, Event, KeyEventKind};
         use std::time::Duration;
@@ -221,6 +536,7 @@ impl App {
                     Event::Key(key) if key.kind == KeyEventKind::Press => {
                         Ok(Some(msg::Msg::KeyPressed(key)))
                     }
+                    _ if self.refresh_due() => Ok(Some(msg::Msg::Refresh)),
                     _ => Ok(None)
                 }
             }
@@ -246,13 +562,20 @@ impl App {
             .split(frame.area());
 
         // Render navbar
-        let has_stories = !self.model.data.stories.is_empty();
+        let has_stories = !self
+            .model
+            .data
+            .listed_stories(self.model.ui.story_list.show_team)
+            .is_empty();
         let navbar = NavBar::new(
             self.model.ui.active_view,
             self.model.ui.loading,
             has_stories,
+            self.model.ui.rate_limited_until,
+            self.model.ui.last_refreshed,
             tick,
-        );
+        )
+        .with_sync_status(self.model.ui.offline, self.model.data.outbox.len());
 
         navbar.render_ref(chunks[0], frame.buffer_mut());
 
@@ -260,11 +583,8 @@ impl App {
         match self.model.ui.active_view {
             ViewType::Stories => {
                 let story_list_view = StoryListView::new(
-                    &self.model.data.stories,
-                    self.model.data.current_iterations.as_deref(),
+                    &self.model.data,
                     &self.model.ui.story_list,
-                    self.model.data.active_story.as_ref(),
-                    true, // Always focused (single view)
                     self.model.ui.loading,
                     tick,
                 );
@@ -283,7 +603,8 @@ impl App {
                     active,
                     &self.model.data.iterations,
                     &self.model.ui.iteration_list,
-                );
+                )
+                .with_stories(&self.model.data.stories);
                 iteration_list.render_ref(chunks[1], frame.buffer_mut());
             }
 
@@ -301,10 +622,15 @@ impl App {
                 todos_view.render_ref(chunks[1], frame.buffer_mut());
             }
 
+            ViewType::Activity => {
+                let activity_view =
+                    ActivityListView::new(&self.model.data, &self.model.ui.activity_list);
+                activity_view.render_ref(chunks[1], frame.buffer_mut());
+            }
+
             ViewType::Search => {
-                // Placeholder for future views
-                let placeholder = Paragraph::new("Coming soon...").block(Block::bordered());
-                placeholder.render(chunks[1], frame.buffer_mut());
+                let search_view = SearchView::new(&self.model.data, &self.model.ui.search);
+                search_view.render_ref(chunks[1], frame.buffer_mut());
             }
         }
 
@@ -322,6 +648,30 @@ impl App {
             );
         }
 
+        if self.model.ui.state_picker.is_showing
+            && let Some(story) = self
+                .model
+                .ui
+                .state_picker
+                .target_story_id
+                .and_then(|id| self.model.data.find_story(id))
+        {
+            let picker = StatePicker::new(story, &self.model.data.workflows);
+            let (width, height) = picker.window_dimensions();
+            let (width, height) = (
+                (width as u16).min(frame.area().width),
+                (height as u16).min(frame.area().height),
+            );
+            let x = (frame.area().width - width) / 2;
+            let y = (frame.area().height - height) / 2;
+
+            picker.render(
+                Rect::new(x, y, width, height),
+                frame.buffer_mut(),
+                &mut self.model.ui.state_picker.list_state,
+            );
+        }
+
         // Render description modal (highest priority overlay before errors)
         if self.model.ui.description_modal.is_showing
             && let Some(story) = &self.model.ui.description_modal.story
@@ -329,7 +679,22 @@ impl App {
             let area = centered_rect(80, 80, frame.area());
             Clear.render(area, frame.buffer_mut());
 
-            let modal = DescriptionModal::new(story);
+            let modal = DescriptionModal::new(
+                story,
+                &self.model.data.members,
+                self.model.ui.description_modal.selected_comment_id,
+            )
+            .with_selected_task(self.model.ui.description_modal.selected_task_id)
+            .with_links(
+                self.model.ui.description_modal.selected_link_id,
+                self.model
+                    .data
+                    .stories
+                    .iter()
+                    .chain(&self.model.data.team_stories)
+                    .chain(self.model.data.active_story.iter())
+                    .collect(),
+            );
             modal.render(
                 area,
                 frame.buffer_mut(),
@@ -358,6 +723,13 @@ impl App {
             KeybindsPanel.render(frame.area(), frame.buffer_mut());
         }
 
+        if self.model.ui.show_token_prompt {
+            let config_path = Config::file_path()
+                .map(|p| p.display().to_string())
+                .unwrap_or_else(|_| "your config".to_string());
+            TokenPrompt::new(&config_path).render(frame.area(), frame.buffer_mut());
+        }
+
         self.draw_error(frame);
     }
 
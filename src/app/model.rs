@@ -1,3 +1,4 @@
+use chrono::NaiveDateTime;
 use throbber_widgets_tui::ThrobberState;
 use tokio::task::JoinHandle;
 use tui_scrollview::ScrollViewState;
@@ -7,12 +8,17 @@ use std::{collections::HashMap, path::PathBuf};
 use uuid::Uuid;
 
 use crate::{
-    api::{epic::EpicSlim, iteration::Iteration, story::Story},
-    app::pane::action_menu::ActionMenuState,
+    api::{
+        epic::EpicSlim, iteration::Iteration, story::Story, user::WorkspaceMember,
+        workflow::Workflow,
+    },
+    app::pane::{action_menu::ActionMenuState, search::SearchHit, state_picker::StatePickerState},
     cache::Cache,
     config::Config,
     error::ErrorInfo,
     todos::Todo,
+    activity::Activity,
+    outbox::PendingMutation,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -20,6 +26,7 @@ pub enum LoadingState {
     #[default]
     FetchingIteration,
     FetchingStories,
+    FetchingTeamStories,
     Loaded,
 }
 
@@ -28,6 +35,7 @@ impl LoadingState {
         match self {
             LoadingState::FetchingIteration => "Fetching iterations...",
             LoadingState::FetchingStories => "Loading stories...",
+            LoadingState::FetchingTeamStories => "Loading team stories...",
             LoadingState::Loaded => "",
         }
     }
@@ -44,6 +52,7 @@ pub enum ViewType {
     Epics,
     Notes,
     Todos,
+    Activity,
     Search,
     Iterations,
 }
@@ -55,6 +64,7 @@ impl ViewType {
         ViewType::Iterations,
         ViewType::Notes,
         ViewType::Todos,
+        ViewType::Activity,
         ViewType::Epics,
         ViewType::Search,
     ];
@@ -64,7 +74,8 @@ impl ViewType {
             ViewType::Stories => ViewType::Iterations,
             ViewType::Iterations => ViewType::Notes,
             ViewType::Notes => ViewType::Todos,
-            ViewType::Todos => ViewType::Epics,
+            ViewType::Todos => ViewType::Activity,
+            ViewType::Activity => ViewType::Epics,
             ViewType::Epics => ViewType::Search,
             ViewType::Search => ViewType::Stories,
         }
@@ -74,7 +85,8 @@ impl ViewType {
         match self {
             ViewType::Stories => ViewType::Search,
             ViewType::Search => ViewType::Epics,
-            ViewType::Epics => ViewType::Todos,
+            ViewType::Epics => ViewType::Activity,
+            ViewType::Activity => ViewType::Todos,
             ViewType::Todos => ViewType::Notes,
             ViewType::Notes => ViewType::Iterations,
             ViewType::Iterations => ViewType::Stories,
@@ -87,6 +99,7 @@ impl ViewType {
             ViewType::Epics => "Epics",
             ViewType::Notes => "Notes",
             ViewType::Todos => "Todos",
+            ViewType::Activity => "Activity",
             ViewType::Search => "Search",
             ViewType::Iterations => "Iterations",
         }
@@ -104,12 +117,22 @@ pub struct Model {
 #[derive(Default, Debug)]
 pub struct DataState {
     pub stories: Vec<Story>,
+    /// Everyone's stories for the team view, loaded when it is first shown
+    pub team_stories: Vec<Story>,
     pub iterations: Vec<Iteration>,
     pub epics: Vec<EpicSlim>,
+    pub workflows: Vec<Workflow>,
+    pub members: Vec<WorkspaceMember>,
     pub current_iterations: Option<Vec<Iteration>>,
     pub active_story: Option<Story>,
     pub async_handles: Vec<JoinHandle<()>>,
     pub todos: Vec<Todo>,
+    /// Todos as last saved, to tell our edits apart from another process's when saving
+    pub synced_todos: Vec<Todo>,
+    /// Changes spotted on our stories between refreshes, newest first
+    pub activity: Vec<Activity>,
+    /// Mutations made while offline, oldest first, sent once Shortcut is reachable again
+    pub outbox: Vec<PendingMutation>,
 }
 
 #[derive(Default, Debug)]
@@ -117,6 +140,9 @@ pub struct DescriptionModalState {
     pub is_showing: bool,
     pub scroll_view_state: ScrollViewState,
     pub story: Option<Story>,
+    pub selected_comment_id: Option<i32>,
+    pub selected_task_id: Option<i32>,
+    pub selected_link_id: Option<i32>,
 }
 
 #[derive(Default, Debug)]
@@ -127,11 +153,25 @@ pub struct UiState {
     pub iteration_list: IterationListState,
     pub epic_list: EpicListState,
     pub todos_list: TodosListState,
+    pub activity_list: ActivityListState,
+    pub search: SearchState,
     pub action_menu: ActionMenuState,
+    pub state_picker: StatePickerState,
     pub description_modal: DescriptionModalState,
     pub create_note_modal: CreateNoteModalState,
     pub add_todo_modal: AddTodoModalState,
     pub show_keybinds_panel: bool,
+    /// Shown when Shortcut rejects the API token, offers to edit the config
+    pub show_token_prompt: bool,
+    /// Set when Shortcut rate limited us after all retries, shown as a countdown in the navbar
+    pub rate_limited_until: Option<NaiveDateTime>,
+    /// When the last refresh was started, background refreshes are timed from this
+    pub refresh_started_at: Option<NaiveDateTime>,
+    /// When stories were last loaded from Shortcut, shown in the navbar
+    pub last_refreshed: Option<NaiveDateTime>,
+    /// Set when a request couldn't reach Shortcut, cleared by the next successful load
+    pub offline: bool,
+    pub replaying_outbox: bool,
     pub errors: Vec<ErrorInfo>,
     pub loading: LoadingState,
     pub throbber_state: ThrobberState,
@@ -142,6 +182,8 @@ pub struct IterationListState {
     pub selected_id: Option<i32>,
     pub search_query: String,
     pub search_active: bool,
+    /// Chart points done instead of points left
+    pub burnup: bool,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -151,10 +193,27 @@ pub struct EpicListState {
     pub search_active: bool,
 }
 
+#[derive(Clone, Debug, Default)]
+pub struct SearchState {
+    pub query: String,
+    pub search_active: bool,
+    pub selected: Option<SearchHit>,
+    /// Note files, rescanned whenever the view is opened
+    pub notes: Vec<PathBuf>,
+    /// Search every story in the workspace through the Shortcut API instead of the cache
+    pub remote: bool,
+    /// Query the current `remote_results` were fetched for
+    pub remote_query: Option<String>,
+    pub remote_results: Vec<Story>,
+    pub remote_loading: bool,
+}
+
 #[derive(Clone, Debug)]
 pub struct StoryListState {
     pub selected_story_id: Option<i32>,
     pub show_finished: bool,
+    /// Show the whole iteration or the configured groups' backlog instead of only our stories
+    pub show_team: bool,
 }
 
 impl Default for StoryListState {
@@ -162,6 +221,7 @@ impl Default for StoryListState {
         Self {
             selected_story_id: Default::default(),
             show_finished: true,
+            show_team: false,
         }
     }
 }
@@ -195,6 +255,11 @@ pub struct TodosListState {
     pub selected_id: Option<Uuid>,
 }
 
+#[derive(Clone, Debug, Default)]
+pub struct ActivityListState {
+    pub selected_id: Option<Uuid>,
+}
+
 impl StoryListState {
     /// Returns the index of the selected story in the given slice, if it exists.
     pub fn selected_index(&self, stories: &[Story]) -> Option<usize> {
@@ -207,19 +272,47 @@ impl DataState {
     pub fn current_iterations_ref(&self) -> Option<Vec<&Iteration>> {
         self.current_iterations.as_ref().map(|v| v.iter().collect())
     }
+
+    /// The stories the story list is currently showing, see `StoryListState::show_team`
+    pub fn listed_stories(&self, show_team: bool) -> &[Story] {
+        if show_team {
+            &self.team_stories
+        } else {
+            &self.stories
+        }
+    }
+
+    /// Looks a story up in our own and the team's stories
+    pub fn find_story(&self, id: i32) -> Option<&Story> {
+        self.stories
+            .iter()
+            .chain(&self.team_stories)
+            .find(|s| s.id == id)
+    }
 }
 
 impl Model {
-    pub fn from_cache_and_config(cache: Cache, config: Config, todos: Vec<Todo>) -> Model {
+    pub fn from_cache_and_config(
+        cache: Cache,
+        config: Config,
+        todos: Vec<Todo>,
+        activity: Vec<Activity>,
+    ) -> Model {
         let mut model = Model {
             data: DataState {
                 stories: cache.iteration_stories.clone().unwrap_or_default(),
+                team_stories: Vec::new(),
                 epics: cache.epics.clone(),
+                workflows: cache.workflows.clone(),
+                members: cache.members.clone(),
                 current_iterations: cache.current_iterations.clone(),
                 active_story: cache.active_story.clone(),
                 async_handles: Vec::new(),
                 iterations: cache.iterations.clone(),
+                synced_todos: todos.clone(),
                 todos,
+                activity,
+                outbox: Vec::new(),
             },
             ui: UiState::default(),
             config,
@@ -227,6 +320,7 @@ impl Model {
         };
         model.ui.story_list.selected_story_id = model.data.stories.first().map(|s| s.id);
         model.ui.epic_list.selected_id = model.data.epics.first().map(|e| e.id);
+        model.ui.activity_list.selected_id = model.data.activity.first().map(|a| a.id);
         model
     }
 }
@@ -1,7 +1,20 @@
+use std::fmt;
+
 use crossterm::event::KeyEvent;
+use uuid::Uuid;
 
-use crate::api::{epic::EpicSlim, iteration::Iteration, story::Story};
+use crate::api::{
+    backend::Backend,
+    epic::EpicSlim,
+    error::ApiError,
+    iteration::Iteration,
+    story::{Story, comment::StoryComment, task::StoryTask},
+    user::WorkspaceMember,
+    workflow::Workflow,
+};
 use crate::app::model::ViewType;
+use crate::config::Config;
+use crate::outbox::{PendingMutation, ReplayOutcome};
 use crate::error::ErrorInfo;
 
 #[derive(Debug, Clone)]
@@ -12,7 +25,10 @@ pub enum Msg {
     IterationList(IterationListMsg),
     EpicList(EpicListMsg),
     TodosList(TodosListMsg),
+    ActivityList(ActivityListMsg),
+    Search(SearchMsg),
     ActionMenu(ActionMenuMsg),
+    StatePicker(StatePickerMsg),
     DescriptionModal(DescriptionModalMsg),
     CreateNoteModal(CreateNoteModalMsg),
     AddTodoModal(AddTodoModalMsg),
@@ -23,15 +39,87 @@ pub enum Msg {
     EpicsLoaded(Vec<EpicSlim>),
     IterationsLoaded(Vec<Iteration>),
     AllIterationsLoaded(Vec<Iteration>),
+    WorkflowsLoaded(Vec<Workflow>),
+    MembersLoaded(Vec<WorkspaceMember>),
+    StoryStateUpdated {
+        story_id: i32,
+        workflow_state_id: i32,
+    },
+    TaskSaved {
+        story_id: i32,
+        task: StoryTask,
+    },
+    TaskDeleted {
+        story_id: i32,
+        task_id: i32,
+    },
+    StoryCreated {
+        story: Story,
+        set_active: bool,
+    },
+    /// Shortcut's copy of a story after we changed it
+    StoryUpdated(Story),
+    DescriptionUpdated {
+        story_id: i32,
+        description: String,
+    },
+    /// Shortcut couldn't be reached, the mutation waits in the outbox
+    MutationQueued(PendingMutation),
+    OutboxReplayed(Vec<(Uuid, ReplayOutcome)>),
+    CommentSaved {
+        story_id: i32,
+        comment: StoryComment,
+    },
+    StorySearchLoaded {
+        query: String,
+        stories: Vec<Story>,
+    },
+    StorySearchFailed {
+        query: String,
+        error: ApiError,
+    },
+    TeamStoriesLoaded(Vec<Story>),
     SwitchToView(ViewType),
     NoteOpened,
+    /// A story linked from the description modal, ready to show or open as a note
+    LinkedStoryLoaded {
+        story: Story,
+        as_note: bool,
+    },
     ToggleActionMenu,
     ToggleKeybindsPanel,
     CacheWritten,
+    /// The cache was written, keeping changes another process (e.g. `sc state`) made to it
+    CacheMerged,
+    /// Refetch iterations, stories and epics, from the `r` key or the refresh interval
+    Refresh,
+    /// Shortcut was reached again, see `Cmd::Reconnect`
+    Reconnected(Reconnection),
     Error(ErrorInfo),
+    /// A request failed, `story_id` is set when the request was about a specific story
+    ApiError {
+        context: String,
+        error: ApiError,
+        story_id: Option<i32>,
+    },
     Quit,
 }
 
+/// A backend built by `Cmd::Reconnect` and the config it was built from
+#[derive(Clone)]
+pub struct Reconnection {
+    pub backend: Backend,
+    pub config: Config,
+}
+
+impl fmt::Debug for Reconnection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Reconnection")
+            .field("user_id", &self.backend.user_id())
+            .finish_non_exhaustive()
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum StoryListMsg {
     FocusNext,
@@ -42,6 +130,7 @@ pub enum StoryListMsg {
     EditStoryContents,
     OpenInBrowser,
     ToggleFinished,
+    ToggleTeam,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -52,6 +141,14 @@ pub enum ActionMenuMsg {
     Close,
 }
 
+#[derive(Debug, Clone, Copy)]
+pub enum StatePickerMsg {
+    FocusNext,
+    FocusPrev,
+    Accept,
+    Close,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum NotesListMsg {
     FocusNext,
@@ -66,6 +163,7 @@ pub enum IterationListMsg {
     FocusNext,
     FocusPrev,
     OpenNote,
+    ToggleBurnup,
     ActivateSearch,
     DeactivateSearch,
     SearchInput(char),
@@ -85,6 +183,21 @@ pub enum EpicListMsg {
     ClearSearch,
 }
 
+#[derive(Debug, Clone, Copy)]
+pub enum SearchMsg {
+    FocusNext,
+    FocusPrev,
+    Open,
+    OpenInBrowser,
+    SetActive,
+    ToggleRemote,
+    ActivateSearch,
+    DeactivateSearch,
+    SearchInput(char),
+    SearchBackspace,
+    ClearSearch,
+}
+
 #[derive(Debug, Clone)]
 pub enum DescriptionModalMsg {
     Open,
@@ -95,6 +208,20 @@ pub enum DescriptionModalMsg {
     ScrollPageDown,
     ScrollToTop,
     ScrollToBottom,
+    NextComment,
+    PrevComment,
+    NewComment,
+    ReplyToComment,
+    EditComment,
+    NextTask,
+    PrevTask,
+    ToggleTask,
+    AddTasks,
+    DeleteTask,
+    NextLink,
+    PrevLink,
+    OpenLink,
+    OpenLinkNote,
 }
 
 #[derive(Debug, Clone)]
@@ -116,6 +243,14 @@ pub enum TodosListMsg {
     DeleteSelected,
 }
 
+#[derive(Debug, Clone, Copy)]
+pub enum ActivityListMsg {
+    FocusNext,
+    FocusPrev,
+    Open,
+    MarkAllRead,
+}
+
 #[derive(Debug, Clone)]
 pub enum AddTodoModalMsg {
     Open,
@@ -7,13 +7,18 @@ use ratatui::{
     widgets::{Block, BorderType, Clear, Padding, StatefulWidget, Widget},
 };
 use tui_widget_list::{ListBuilder, ListState, ListView};
+use uuid::Uuid;
 
 use crate::{
-    api::story::{get_story_associated_iteration, Story},
+    api::{
+        story::{get_story_associated_iteration, Story},
+        user::get_member,
+    },
     app::{
         cmd::Cmd,
         model::{DataState, UiState},
         msg::ActionMenuMsg,
+        pane::{description_modal::latest_own_comment, state_picker},
     },
     error::ErrorInfo,
     navkey,
@@ -103,19 +108,29 @@ pub enum ActionMenuItem {
     OpenNote,
     OpenIterationNote,
     EditDescription,
+    EditStory,
+    CreateStory,
     OpenTmux,
     SetActive,
     CreateGitWorktree,
     OpenInBrowser,
+    ChangeState,
+    NewComment,
+    EditMyComment,
 }
 
 impl ActionMenuItem {
     pub const ALL: &[Self] = &[
         Self::OpenNote,
         Self::OpenIterationNote,
+        Self::ChangeState,
+        Self::NewComment,
+        Self::EditMyComment,
         Self::CreateGitWorktree,
         Self::OpenTmux,
         Self::EditDescription,
+        Self::EditStory,
+        Self::CreateStory,
         Self::SetActive,
         Self::OpenInBrowser,
     ];
@@ -129,10 +144,15 @@ impl ActionMenuItem {
             Self::OpenNote => "Open Note",
             Self::OpenIterationNote => "Open Iteration Note",
             Self::EditDescription => "Edit Description",
+            Self::EditStory => "Edit Story",
+            Self::CreateStory => "New Story",
             Self::OpenTmux => "Open Mux Session",
             Self::SetActive => "Toggle Active Story",
             Self::CreateGitWorktree => "Create git worktree",
             Self::OpenInBrowser => "Open ticket in browser",
+            Self::ChangeState => "Change State",
+            Self::NewComment => "New Comment",
+            Self::EditMyComment => "Edit My Comment",
         }
     }
 }
@@ -141,7 +161,9 @@ pub fn update(
     ui_state: &mut UiState,
     data_state: &DataState,
     msg: ActionMenuMsg,
-    story: &Story,
+    story: Option<&Story>,
+    user_id: Uuid,
+    branch_name_template: &str,
 ) -> Vec<Cmd> {
     let item_count = ActionMenuItem::ALL.len();
     let state = &mut ui_state.action_menu.list_state;
@@ -176,81 +198,152 @@ pub fn update(
         }
 
         ActionMenuMsg::Accept => {
-            let mut actions = match ActionMenuItem::from_idx(state.selected.unwrap_or(0)) {
-                ActionMenuItem::OpenIterationNote => {
-                    let iteration = data_state.current_iterations_ref().and_then(|iterations| {
-                        get_story_associated_iteration(story.iteration_id, iterations)
-                    });
-
-                    match iteration {
-                        Some(it) => vec![Cmd::OpenIterationNote {
-                            iteration_id: it.id,
-                            iteration_name: it.name.clone(),
-                            iteration_app_url: it.app_url.clone(),
-                        }],
-                        None => {
-                            ui_state.errors.push(ErrorInfo::new(
-                                "No iteration",
-                                "This story has no associated iteration",
-                            ));
-                            vec![Cmd::None]
-                        }
-                    }
+            let item = ActionMenuItem::from_idx(state.selected.unwrap_or(0));
+            let mut actions = match (item, story) {
+                (item, Some(story)) => {
+                    story_action(ui_state, data_state, item, story, user_id, branch_name_template)
                 }
+                (ActionMenuItem::CreateStory, None) => vec![new_story(ui_state, data_state, None)],
+                // everything else acts on the story the menu was opened on
+                (_, None) => vec![Cmd::None],
+            };
 
-                ActionMenuItem::OpenNote => {
-                    let iteration_app_url = data_state
-                        .current_iterations_ref()
-                        .and_then(|iterations| {
-                            get_story_associated_iteration(story.iteration_id, iterations)
-                        })
-                        .map(|it| it.app_url.clone());
-
-                    vec![Cmd::OpenNote {
-                        story_id: story.id,
-                        story_name: story.name.clone(),
-                        story_app_url: story.app_url.clone(),
-                        iteration_app_url,
-                    }]
-                }
+            actions.push(Cmd::ActionMenuVisibility(false));
+            actions
+        }
 
-                ActionMenuItem::EditDescription => {
-                    vec![Cmd::EditStoryContent {
-                        story_id: story.id,
-                        description: story.description.clone(),
-                    }]
-                }
+        ActionMenuMsg::Close => {
+            vec![Cmd::ActionMenuVisibility(false)]
+        }
+    }
+}
 
-                ActionMenuItem::OpenTmux => {
-                    vec![Cmd::OpenTmuxSession {
-                        story_name: story.name.clone(),
-                    }]
-                }
+/// Lands in the iteration of the story the menu was opened on and the selected epic
+fn new_story(ui_state: &UiState, data_state: &DataState, story: Option<&Story>) -> Cmd {
+    let iteration_id = story.and_then(|s| s.iteration_id).or_else(|| {
+        data_state
+            .current_iterations
+            .as_ref()
+            .and_then(|iterations| iterations.first())
+            .map(|it| it.id)
+    });
+
+    Cmd::CreateStory {
+        iteration_id,
+        epic_id: ui_state.epic_list.selected_id,
+    }
+}
 
-                ActionMenuItem::SetActive => {
-                    vec![Cmd::SelectStory(Some(story.clone())), Cmd::WriteCache]
+fn story_action(
+    ui_state: &mut UiState,
+    data_state: &DataState,
+    item: ActionMenuItem,
+    story: &Story,
+    user_id: Uuid,
+    branch_name_template: &str,
+) -> Vec<Cmd> {
+    match item {
+        ActionMenuItem::OpenIterationNote => {
+            let iteration = data_state.current_iterations_ref().and_then(|iterations| {
+                get_story_associated_iteration(story.iteration_id, iterations)
+            });
+
+            match iteration {
+                Some(it) => vec![Cmd::OpenIterationNote {
+                    iteration_id: it.id,
+                    iteration_name: it.name.clone(),
+                    iteration_app_url: it.app_url.clone(),
+                }],
+                None => {
+                    ui_state.errors.push(ErrorInfo::new(
+                        "No iteration",
+                        "This story has no associated iteration",
+                    ));
+                    vec![Cmd::None]
                 }
+            }
+        }
 
-                ActionMenuItem::CreateGitWorktree => {
-                    // TODO: figure out how to get actual branch name
-                    vec![Cmd::CreateGitWorktree {
-                        branch_name: "feat/branchname".to_string(),
-                    }]
-                }
+        ActionMenuItem::OpenNote => {
+            let iteration_app_url = data_state
+                .current_iterations_ref()
+                .and_then(|iterations| {
+                    get_story_associated_iteration(story.iteration_id, iterations)
+                })
+                .map(|it| it.app_url.clone());
+
+            vec![Cmd::OpenNote {
+                story_id: story.id,
+                story_name: story.name.clone(),
+                story_app_url: story.app_url.clone(),
+                iteration_app_url,
+            }]
+        }
 
-                ActionMenuItem::OpenInBrowser => {
-                    vec![Cmd::OpenInBrowser {
-                        app_url: story.app_url.clone(),
-                    }]
-                }
-            };
+        ActionMenuItem::EditDescription => {
+            vec![Cmd::EditStoryContent {
+                story_id: story.id,
+                description: story.description.clone(),
+            }]
+        }
 
-            actions.push(Cmd::ActionMenuVisibility(false));
-            actions
+        ActionMenuItem::EditStory => vec![Cmd::EditStory { story_id: story.id }],
+
+        ActionMenuItem::CreateStory => vec![new_story(ui_state, data_state, Some(story))],
+
+        ActionMenuItem::OpenTmux => {
+            vec![Cmd::OpenTmuxSession {
+                story_name: story.name.clone(),
+            }]
         }
 
-        ActionMenuMsg::Close => {
-            vec![Cmd::ActionMenuVisibility(false)]
+        ActionMenuItem::SetActive => {
+            vec![Cmd::SelectStory(Some(story.clone())), Cmd::WriteCache]
+        }
+
+        ActionMenuItem::CreateGitWorktree => {
+            let mention_name = get_member(user_id, &data_state.members)
+                .map(|m| m.profile.mention_name.as_str());
+
+            vec![Cmd::CreateGitWorktree {
+                branch_name: story.branch_name(branch_name_template, mention_name),
+            }]
+        }
+
+        ActionMenuItem::ChangeState => {
+            state_picker::open(&mut ui_state.state_picker, story, &data_state.workflows);
+            vec![Cmd::None]
+        }
+
+        ActionMenuItem::NewComment => {
+            vec![Cmd::EditComment {
+                story_id: story.id,
+                comment_id: None,
+                parent_id: None,
+                text: String::new(),
+            }]
+        }
+
+        ActionMenuItem::EditMyComment => match latest_own_comment(story, user_id) {
+            Some(comment) => vec![Cmd::EditComment {
+                story_id: story.id,
+                comment_id: Some(comment.id),
+                parent_id: comment.parent_id,
+                text: comment.text.clone().unwrap_or_default(),
+            }],
+            None => {
+                ui_state.errors.push(ErrorInfo::new(
+                    "No comment to edit",
+                    "You haven't commented on this story",
+                ));
+                vec![Cmd::None]
+            }
+        },
+
+        ActionMenuItem::OpenInBrowser => {
+            vec![Cmd::OpenInBrowser {
+                app_url: story.app_url.clone(),
+            }]
         }
     }
 }
@@ -0,0 +1,62 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::{
+    activity::Activity,
+    app::{cmd::Cmd, msg::ActivityListMsg},
+};
+
+pub use crate::app::model::ActivityListState;
+
+pub fn update(
+    state: &mut ActivityListState,
+    activity: &mut [Activity],
+    msg: ActivityListMsg,
+) -> Vec<Cmd> {
+    let position = state
+        .selected_id
+        .and_then(|id| activity.iter().position(|a| a.id == id));
+
+    match msg {
+        ActivityListMsg::FocusNext => {
+            let next = match position {
+                Some(pos) => (pos + 1) % activity.len(),
+                None => 0,
+            };
+            state.selected_id = activity.get(next).map(|a| a.id);
+            vec![Cmd::None]
+        }
+
+        ActivityListMsg::FocusPrev => {
+            let prev = match position {
+                Some(pos) => (pos + activity.len() - 1) % activity.len(),
+                None => activity.len().saturating_sub(1),
+            };
+            state.selected_id = activity.get(prev).map(|a| a.id);
+            vec![Cmd::None]
+        }
+
+        // the description modal is opened by the caller, which has the stories
+        ActivityListMsg::Open => match position {
+            Some(pos) if !activity[pos].read => {
+                activity[pos].read = true;
+                vec![Cmd::WriteActivity]
+            }
+            _ => vec![Cmd::None],
+        },
+
+        ActivityListMsg::MarkAllRead => {
+            activity.iter_mut().for_each(|a| a.read = true);
+            vec![Cmd::WriteActivity]
+        }
+    }
+}
+
+pub fn key_to_msg(key: KeyEvent) -> Option<ActivityListMsg> {
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down => Some(ActivityListMsg::FocusNext),
+        KeyCode::Char('k') | KeyCode::Up => Some(ActivityListMsg::FocusPrev),
+        KeyCode::Char(' ') | KeyCode::Enter => Some(ActivityListMsg::Open),
+        KeyCode::Char('m') => Some(ActivityListMsg::MarkAllRead),
+        _ => None,
+    }
+}
@@ -1,13 +1,44 @@
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use tui_scrollview::ScrollViewState;
+use uuid::Uuid;
 
 use crate::{
-    api::story::Story,
+    api::story::{
+        Story,
+        comment::{StoryComment, comment_threads},
+        link::ordered_links,
+        task::ordered_tasks,
+    },
     app::{cmd::Cmd, model::DescriptionModalState, msg::DescriptionModalMsg},
+    error::ErrorInfo,
     navkey,
+    outbox::Mutation,
 };
 
-pub fn update(state: &mut DescriptionModalState, msg: DescriptionModalMsg) -> Vec<Cmd> {
+/// Comments that can be selected in the modal, in display order
+fn selectable_comment_ids(story: &Story) -> Vec<i32> {
+    comment_threads(&story.comments)
+        .into_iter()
+        .filter(|(_, c)| !c.deleted)
+        .map(|(_, c)| c.id)
+        .collect()
+}
+
+/// The most recent non-deleted comment written by `user_id`
+pub fn latest_own_comment(story: &Story, user_id: Uuid) -> Option<&StoryComment> {
+    story
+        .comments
+        .iter()
+        .filter(|c| c.author_id == user_id && !c.deleted)
+        .max_by_key(|c| c.position)
+}
+
+pub fn update(
+    state: &mut DescriptionModalState,
+    user_id: Uuid,
+    errors: &mut Vec<ErrorInfo>,
+    msg: DescriptionModalMsg,
+) -> Vec<Cmd> {
     match msg {
         DescriptionModalMsg::Open => {
             vec![Cmd::None]
@@ -17,6 +48,9 @@ pub fn update(state: &mut DescriptionModalState, msg: DescriptionModalMsg) -> Ve
             state.is_showing = false;
             state.scroll_view_state = ScrollViewState::default();
             state.story = None;
+            state.selected_comment_id = None;
+            state.selected_task_id = None;
+            state.selected_link_id = None;
             vec![Cmd::None]
         }
 
@@ -49,6 +83,205 @@ pub fn update(state: &mut DescriptionModalState, msg: DescriptionModalMsg) -> Ve
             state.scroll_view_state.scroll_to_bottom();
             vec![Cmd::None]
         }
+
+        DescriptionModalMsg::NextComment | DescriptionModalMsg::PrevComment => {
+            let Some(story) = &state.story else {
+                return vec![Cmd::None];
+            };
+
+            let ids = selectable_comment_ids(story);
+            if ids.is_empty() {
+                return vec![Cmd::None];
+            }
+
+            let current_idx = state
+                .selected_comment_id
+                .and_then(|id| ids.iter().position(|c| *c == id));
+
+            let idx = match (msg, current_idx) {
+                (DescriptionModalMsg::NextComment, Some(idx)) => (idx + 1) % ids.len(),
+                (DescriptionModalMsg::NextComment, None) => 0,
+                (_, Some(0)) | (_, None) => ids.len() - 1,
+                (_, Some(idx)) => idx - 1,
+            };
+
+            state.selected_comment_id = Some(ids[idx]);
+            vec![Cmd::None]
+        }
+
+        DescriptionModalMsg::NextTask | DescriptionModalMsg::PrevTask => {
+            let Some(story) = &state.story else {
+                return vec![Cmd::None];
+            };
+
+            let ids: Vec<_> = ordered_tasks(&story.tasks).iter().map(|t| t.id).collect();
+            if ids.is_empty() {
+                return vec![Cmd::None];
+            }
+
+            let current_idx = state
+                .selected_task_id
+                .and_then(|id| ids.iter().position(|t| *t == id));
+
+            let idx = match (msg, current_idx) {
+                (DescriptionModalMsg::NextTask, Some(idx)) => (idx + 1) % ids.len(),
+                (DescriptionModalMsg::NextTask, None) => 0,
+                (_, Some(0)) | (_, None) => ids.len() - 1,
+                (_, Some(idx)) => idx - 1,
+            };
+
+            state.selected_task_id = Some(ids[idx]);
+            vec![Cmd::None]
+        }
+
+        DescriptionModalMsg::ToggleTask | DescriptionModalMsg::DeleteTask => {
+            let Some(story) = &state.story else {
+                return vec![Cmd::None];
+            };
+
+            let Some(task) = state
+                .selected_task_id
+                .and_then(|id| story.tasks.iter().find(|t| t.id == id))
+            else {
+                errors.push(ErrorInfo::new(
+                    "No task selected",
+                    "Use { and } to select a task",
+                ));
+                return vec![Cmd::None];
+            };
+
+            let mutation = match msg {
+                DescriptionModalMsg::ToggleTask => Mutation::SetTaskComplete {
+                    task_id: task.id,
+                    complete: !task.complete,
+                },
+                _ => Mutation::DeleteTask { task_id: task.id },
+            };
+
+            vec![Cmd::SubmitMutation {
+                story_id: story.id,
+                mutation,
+            }]
+        }
+
+        DescriptionModalMsg::NextLink | DescriptionModalMsg::PrevLink => {
+            let Some(story) = &state.story else {
+                return vec![Cmd::None];
+            };
+
+            let ids: Vec<_> = ordered_links(&story.story_links).iter().map(|l| l.id).collect();
+            if ids.is_empty() {
+                return vec![Cmd::None];
+            }
+
+            let current_idx = state
+                .selected_link_id
+                .and_then(|id| ids.iter().position(|l| *l == id));
+
+            let idx = match (msg, current_idx) {
+                (DescriptionModalMsg::NextLink, Some(idx)) => (idx + 1) % ids.len(),
+                (DescriptionModalMsg::NextLink, None) => 0,
+                (_, Some(0)) | (_, None) => ids.len() - 1,
+                (_, Some(idx)) => idx - 1,
+            };
+
+            state.selected_link_id = Some(ids[idx]);
+            vec![Cmd::None]
+        }
+
+        DescriptionModalMsg::OpenLink | DescriptionModalMsg::OpenLinkNote => {
+            let Some(story) = &state.story else {
+                return vec![Cmd::None];
+            };
+
+            let Some(link) = state
+                .selected_link_id
+                .and_then(|id| story.story_links.iter().find(|l| l.id == id))
+            else {
+                errors.push(ErrorInfo::new(
+                    "No linked story selected",
+                    "Use < and > to select a linked story",
+                ));
+                return vec![Cmd::None];
+            };
+
+            vec![Cmd::OpenLinkedStory {
+                story_id: link.other_story_id(),
+                as_note: matches!(msg, DescriptionModalMsg::OpenLinkNote),
+            }]
+        }
+
+        DescriptionModalMsg::AddTasks => match &state.story {
+            Some(story) => vec![Cmd::AddTasks { story_id: story.id }],
+            None => vec![Cmd::None],
+        },
+
+        DescriptionModalMsg::NewComment => match &state.story {
+            Some(story) => vec![Cmd::EditComment {
+                story_id: story.id,
+                comment_id: None,
+                parent_id: None,
+                text: String::new(),
+            }],
+            None => vec![Cmd::None],
+        },
+
+        DescriptionModalMsg::ReplyToComment => {
+            let Some(story) = &state.story else {
+                return vec![Cmd::None];
+            };
+
+            match state.selected_comment_id {
+                Some(parent_id) => vec![Cmd::EditComment {
+                    story_id: story.id,
+                    comment_id: None,
+                    parent_id: Some(parent_id),
+                    text: String::new(),
+                }],
+                None => {
+                    errors.push(ErrorInfo::new(
+                        "No comment selected",
+                        "Use [ and ] to select the comment to reply to",
+                    ));
+                    vec![Cmd::None]
+                }
+            }
+        }
+
+        DescriptionModalMsg::EditComment => {
+            let Some(story) = &state.story else {
+                return vec![Cmd::None];
+            };
+
+            // Edit the selected comment, or our latest one if nothing is selected
+            let comment = match state.selected_comment_id {
+                Some(id) => story.comments.iter().find(|c| c.id == id),
+                None => latest_own_comment(story, user_id),
+            };
+
+            match comment {
+                Some(comment) if comment.author_id == user_id => vec![Cmd::EditComment {
+                    story_id: story.id,
+                    comment_id: Some(comment.id),
+                    parent_id: comment.parent_id,
+                    text: comment.text.clone().unwrap_or_default(),
+                }],
+                Some(_) => {
+                    errors.push(ErrorInfo::new(
+                        "Can't edit comment",
+                        "You can only edit your own comments",
+                    ));
+                    vec![Cmd::None]
+                }
+                None => {
+                    errors.push(ErrorInfo::new(
+                        "No comment to edit",
+                        "You haven't commented on this story",
+                    ));
+                    vec![Cmd::None]
+                }
+            }
+        }
     }
 }
 
@@ -56,6 +289,9 @@ pub fn open(state: &mut DescriptionModalState, story: Story) {
     state.is_showing = true;
     state.scroll_view_state = ScrollViewState::default();
     state.story = Some(story);
+    state.selected_comment_id = None;
+    state.selected_task_id = None;
+            state.selected_link_id = None;
 }
 
 pub fn key_to_msg(key: KeyEvent) -> Option<DescriptionModalMsg> {
@@ -73,6 +309,20 @@ pub fn key_to_msg(key: KeyEvent) -> Option<DescriptionModalMsg> {
         KeyCode::PageUp => Some(DescriptionModalMsg::ScrollPageUp),
         KeyCode::Char('g') => Some(DescriptionModalMsg::ScrollToTop),
         KeyCode::Char('G') => Some(DescriptionModalMsg::ScrollToBottom),
+        KeyCode::Char(']') => Some(DescriptionModalMsg::NextComment),
+        KeyCode::Char('[') => Some(DescriptionModalMsg::PrevComment),
+        KeyCode::Char('c') => Some(DescriptionModalMsg::NewComment),
+        KeyCode::Char('r') => Some(DescriptionModalMsg::ReplyToComment),
+        KeyCode::Char('e') => Some(DescriptionModalMsg::EditComment),
+        KeyCode::Char('}') => Some(DescriptionModalMsg::NextTask),
+        KeyCode::Char('{') => Some(DescriptionModalMsg::PrevTask),
+        KeyCode::Char('x') => Some(DescriptionModalMsg::ToggleTask),
+        KeyCode::Char('a') => Some(DescriptionModalMsg::AddTasks),
+        KeyCode::Char('D') => Some(DescriptionModalMsg::DeleteTask),
+        KeyCode::Char('>') => Some(DescriptionModalMsg::NextLink),
+        KeyCode::Char('<') => Some(DescriptionModalMsg::PrevLink),
+        KeyCode::Enter => Some(DescriptionModalMsg::OpenLink),
+        KeyCode::Char('n') => Some(DescriptionModalMsg::OpenLinkNote),
         _ => None,
     }
 }
@@ -80,19 +330,34 @@ pub fn key_to_msg(key: KeyEvent) -> Option<DescriptionModalMsg> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::api::story::{StoryType, task::StoryTask};
     use crossterm::event::{KeyEventKind, KeyEventState};
 
     fn create_test_story() -> Story {
         Story {
             id: 1,
             name: "Test Story".to_string(),
+            owner_ids: vec![],
+            group_id: None,
+            updated_at: None,
+            estimate: None,
+            labels: vec![],
+            deadline: None,
             description: "Test description".to_string(),
             completed: false,
             branches: vec![],
             comments: vec![],
+            tasks: vec![],
+            story_links: vec![],
+            blocked: false,
+            blocker: false,
+            completed_at: None,
             epic_id: None,
             iteration_id: None,
             app_url: "https://example.com".to_string(),
+            workflow_id: 1,
+            workflow_state_id: 1,
+            story_type: StoryType::Feature,
         }
     }
 
@@ -113,12 +378,118 @@ mod tests {
             is_showing: true,
             scroll_view_state: ScrollViewState::default(),
             story: Some(create_test_story()),
+            selected_comment_id: Some(1),
+            selected_task_id: Some(1),
+            selected_link_id: Some(1),
         };
 
-        update(&mut state, DescriptionModalMsg::Close);
+        update(&mut state, Uuid::nil(), &mut Vec::new(), DescriptionModalMsg::Close);
 
         assert!(!state.is_showing);
         assert!(state.story.is_none());
+        assert!(state.selected_comment_id.is_none());
+        assert!(state.selected_task_id.is_none());
+        assert!(state.selected_link_id.is_none());
+    }
+
+    #[test]
+    fn test_toggle_task_flips_selected_task() {
+        let mut story = create_test_story();
+        story.tasks = vec![
+            StoryTask {
+                id: 1,
+                description: "Tests pass".to_string(),
+                complete: true,
+                position: 1,
+            },
+            StoryTask {
+                id: 2,
+                description: "Docs updated".to_string(),
+                complete: false,
+                position: 2,
+            },
+        ];
+        let mut state = DescriptionModalState::default();
+        open(&mut state, story);
+
+        update(&mut state, Uuid::nil(), &mut Vec::new(), DescriptionModalMsg::PrevTask);
+        let cmds = update(&mut state, Uuid::nil(), &mut Vec::new(), DescriptionModalMsg::ToggleTask);
+
+        assert!(matches!(
+            cmds.as_slice(),
+            [Cmd::SubmitMutation {
+                story_id: 1,
+                mutation: Mutation::SetTaskComplete {
+                    task_id: 2,
+                    complete: true
+                },
+            }]
+        ));
+    }
+
+    fn create_comment(id: i32, author_id: Uuid) -> StoryComment {
+        StoryComment {
+            id,
+            author_id,
+            deleted: false,
+            position: id,
+            text: Some(format!("comment {}", id)),
+            parent_id: None,
+            created_at: chrono::DateTime::default(),
+        }
+    }
+
+    #[test]
+    fn test_comment_selection_wraps() {
+        let mut story = create_test_story();
+        story.comments = vec![create_comment(1, Uuid::nil()), create_comment(2, Uuid::nil())];
+        let mut state = DescriptionModalState::default();
+        open(&mut state, story);
+
+        update(&mut state, Uuid::nil(), &mut Vec::new(), DescriptionModalMsg::NextComment);
+        assert_eq!(state.selected_comment_id, Some(1));
+
+        update(&mut state, Uuid::nil(), &mut Vec::new(), DescriptionModalMsg::PrevComment);
+        assert_eq!(state.selected_comment_id, Some(2));
+    }
+
+    #[test]
+    fn test_edit_comment_rejects_other_authors() {
+        let other = Uuid::new_v4();
+        let mut story = create_test_story();
+        story.comments = vec![create_comment(1, other)];
+        let mut state = DescriptionModalState::default();
+        open(&mut state, story);
+        state.selected_comment_id = Some(1);
+
+        let mut errors = Vec::new();
+        let cmds = update(&mut state, Uuid::nil(), &mut errors, DescriptionModalMsg::EditComment);
+
+        assert!(matches!(cmds.as_slice(), [Cmd::None]));
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn test_edit_comment_defaults_to_latest_own() {
+        let me = Uuid::nil();
+        let mut story = create_test_story();
+        story.comments = vec![
+            create_comment(1, me),
+            create_comment(2, me),
+            create_comment(3, Uuid::new_v4()),
+        ];
+        let mut state = DescriptionModalState::default();
+        open(&mut state, story);
+
+        let cmds = update(&mut state, me, &mut Vec::new(), DescriptionModalMsg::EditComment);
+
+        assert!(matches!(
+            cmds.as_slice(),
+            [Cmd::EditComment {
+                comment_id: Some(2),
+                ..
+            }]
+        ));
     }
 
     #[test]
@@ -110,6 +110,11 @@ pub fn update(
             }
         }
 
+        IterationListMsg::ToggleBurnup => {
+            state.burnup = !state.burnup;
+            vec![Cmd::None]
+        }
+
         IterationListMsg::ActivateSearch => {
             state.search_active = true;
             vec![Cmd::None]
@@ -158,6 +163,7 @@ pub fn key_to_msg(key: KeyEvent) -> Option<IterationListMsg> {
         navkey!(down) => Some(IterationListMsg::FocusNext),
         navkey!(up) => Some(IterationListMsg::FocusPrev),
         KeyCode::Enter => Some(IterationListMsg::OpenNote),
+        KeyCode::Char('b') => Some(IterationListMsg::ToggleBurnup),
         _ => None,
     }
 }
@@ -1,9 +1,12 @@
 pub mod action_menu;
+pub mod activity_list;
 pub mod add_todo_modal;
 pub mod create_note_modal;
 pub mod description_modal;
 pub mod epic_list;
 pub mod iteration_list;
 pub mod notes_list;
+pub mod search;
+pub mod state_picker;
 pub mod story_list;
 pub mod todos_list;
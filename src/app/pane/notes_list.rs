@@ -35,6 +35,7 @@ fn scan_subdir(notes_dir: &Path, subdir: &str) -> Vec<PathBuf> {
 }
 
 /// Scans all note subdirectories and returns per-category vecs.
+#[allow(clippy::type_complexity)]
 pub fn scan_notes(
     notes_dir: &Path,
 ) -> (
@@ -135,10 +136,10 @@ pub fn update(state: &mut NotesListState, msg: NotesListMsg) -> Vec<Cmd> {
             }
 
             // Save current selection for current section
-            if let Some(ref sel) = state.selected_path.clone() {
-                if let Some(cur_idx) = section_of(state, sel) {
-                    state.section_selections.insert(cur_idx, sel.clone());
-                }
+            if let Some(ref sel) = state.selected_path.clone()
+                && let Some(cur_idx) = section_of(state, sel)
+            {
+                state.section_selections.insert(cur_idx, sel.clone());
             }
 
             // Find current section index within non_empty list
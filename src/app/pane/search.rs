@@ -0,0 +1,472 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
+
+use crate::{
+    api::{
+        iteration::Iteration,
+        story::{Story, get_story_associated_iteration},
+    },
+    app::{
+        cmd::Cmd,
+        model::{DataState, SearchState},
+        msg::SearchMsg,
+        pane::notes_list::scan_notes,
+    },
+    keybindings::Key,
+};
+
+/// Identifies a single search result across all searchable kinds
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SearchHit {
+    Story(i32),
+    Epic(i32),
+    Iteration(i32),
+    Note(PathBuf),
+}
+
+impl SearchHit {
+    /// Heading of the group the hit is listed under
+    pub fn group_label(&self) -> &'static str {
+        match self {
+            SearchHit::Story(_) => "Stories",
+            SearchHit::Epic(_) => "Epics",
+            SearchHit::Iteration(_) => "Iterations",
+            SearchHit::Note(_) => "Notes",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SearchResult {
+    pub hit: SearchHit,
+    pub label: String,
+}
+
+/// Returns the items whose label fuzzy-matches `query`, best match first.
+fn rank<T>(items: impl IntoIterator<Item = T>, query: &str, label: impl Fn(&T) -> String) -> Vec<(T, String)> {
+    let matcher = SkimMatcherV2::default();
+    let mut scored: Vec<_> = items
+        .into_iter()
+        .filter_map(|item| {
+            let label = label(&item);
+            matcher
+                .fuzzy_match(&label, query)
+                .map(|score| (score, item, label))
+        })
+        .collect();
+
+    scored.sort_by_key(|(score, _, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, item, label)| (item, label)).collect()
+}
+
+/// Label for a note file, e.g. `stories/1234-fix-login`
+fn note_label(path: &Path) -> String {
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default();
+
+    match path
+        .parent()
+        .and_then(|p| p.file_name())
+        .and_then(|s| s.to_str())
+    {
+        Some(dir) => format!("{}/{}", dir, stem),
+        None => stem.to_string(),
+    }
+}
+
+/// Every note file under `notes_dir`, in the same order as the notes view
+pub fn scan_note_files(notes_dir: &Path) -> Vec<PathBuf> {
+    let (daily, stories, iterations, epics, scratch) = scan_notes(notes_dir);
+    [daily, stories, iterations, epics, scratch].concat()
+}
+
+/// Current iterations first, then every other known iteration
+fn all_iterations(data: &DataState) -> Vec<&Iteration> {
+    let current = data.current_iterations.as_deref().unwrap_or(&[]);
+    let current_ids: HashSet<i32> = current.iter().map(|it| it.id).collect();
+
+    current
+        .iter()
+        .chain(data.iterations.iter().filter(|it| !current_ids.contains(&it.id)))
+        .collect()
+}
+
+/// Fuzzy-matches `query` against cached stories, epics, iterations and note files.
+/// Results are grouped by kind (in that order) and ranked by score within each group.
+/// An empty query matches nothing.
+pub fn results(query: &str, data: &DataState, notes: &[PathBuf]) -> Vec<SearchResult> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let stories = rank(&data.stories, query, |s| story_label(s))
+        .into_iter()
+        .map(|(s, label)| (SearchHit::Story(s.id), label));
+    let epics = rank(&data.epics, query, |e| e.name.clone())
+        .into_iter()
+        .map(|(e, label)| (SearchHit::Epic(e.id), label));
+    let iterations = rank(all_iterations(data), query, |it| it.name.clone())
+        .into_iter()
+        .map(|(it, label)| (SearchHit::Iteration(it.id), label));
+    let notes = rank(notes, query, |p| note_label(p))
+        .into_iter()
+        .map(|(p, label)| (SearchHit::Note(p.clone()), label));
+
+    stories
+        .chain(epics)
+        .chain(iterations)
+        .chain(notes)
+        .map(|(hit, label)| SearchResult { hit, label })
+        .collect()
+}
+
+fn story_label(story: &Story) -> String {
+    format!("sc-{} {}", story.id, story.name)
+}
+
+/// Results for the current mode: remote results are listed in the order Shortcut returned them
+pub fn visible_results(state: &SearchState, data: &DataState) -> Vec<SearchResult> {
+    if !state.remote {
+        return results(&state.query, data, &state.notes);
+    }
+
+    state
+        .remote_results
+        .iter()
+        .map(|s| SearchResult {
+            hit: SearchHit::Story(s.id),
+            label: story_label(s),
+        })
+        .collect()
+}
+
+/// Looks a story up in the cache first, falling back to remote search results
+pub fn find_story<'a>(state: &'a SearchState, data: &'a DataState, story_id: i32) -> Option<&'a Story> {
+    data.stories
+        .iter()
+        .chain(data.active_story.iter())
+        .chain(state.remote_results.iter())
+        .find(|s| s.id == story_id)
+}
+
+fn open_hit(hit: &SearchHit, state: &SearchState, data: &DataState) -> Option<Cmd> {
+    match hit {
+        SearchHit::Story(id) => {
+            let story = find_story(state, data, *id)?;
+            let iteration_app_url =
+                get_story_associated_iteration(story.iteration_id, all_iterations(data))
+                    .map(|it| it.app_url.clone());
+
+            Some(Cmd::OpenNote {
+                story_id: story.id,
+                story_name: story.name.clone(),
+                story_app_url: story.app_url.clone(),
+                iteration_app_url,
+            })
+        }
+
+        SearchHit::Epic(id) => {
+            let epic = data.epics.iter().find(|e| e.id == *id)?;
+            Some(Cmd::OpenEpicNote {
+                epic_id: epic.id,
+                epic_name: epic.name.clone(),
+                epic_app_url: epic.app_url.clone(),
+            })
+        }
+
+        SearchHit::Iteration(id) => {
+            let iteration = all_iterations(data).into_iter().find(|it| it.id == *id)?;
+            Some(Cmd::OpenIterationNote {
+                iteration_id: iteration.id,
+                iteration_name: iteration.name.clone(),
+                iteration_app_url: iteration.app_url.clone(),
+            })
+        }
+
+        SearchHit::Note(path) => Some(Cmd::OpenScratchNote {
+            path: path.clone(),
+            name: path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or_default()
+                .to_string(),
+        }),
+    }
+}
+
+fn app_url(hit: &SearchHit, state: &SearchState, data: &DataState) -> Option<String> {
+    match hit {
+        SearchHit::Story(id) => find_story(state, data, *id).map(|s| s.app_url.clone()),
+        SearchHit::Epic(id) => data
+            .epics
+            .iter()
+            .find(|e| e.id == *id)
+            .map(|e| e.app_url.clone()),
+        SearchHit::Iteration(id) => all_iterations(data)
+            .into_iter()
+            .find(|it| it.id == *id)
+            .map(|it| it.app_url.clone()),
+        SearchHit::Note(_) => None,
+    }
+}
+
+fn select_first(state: &mut SearchState, data: &DataState) {
+    state.selected = visible_results(state, data).first().map(|r| r.hit.clone());
+}
+
+pub fn update(state: &mut SearchState, data: &DataState, msg: SearchMsg) -> Vec<Cmd> {
+    match msg {
+        SearchMsg::FocusNext | SearchMsg::FocusPrev => {
+            let visible = visible_results(state, data);
+            if visible.is_empty() {
+                return vec![Cmd::None];
+            }
+
+            let current_idx = state
+                .selected
+                .as_ref()
+                .and_then(|sel| visible.iter().position(|r| r.hit == *sel));
+
+            let idx = match (msg, current_idx) {
+                (SearchMsg::FocusNext, Some(idx)) => (idx + 1) % visible.len(),
+                (SearchMsg::FocusNext, None) => 0,
+                (_, Some(0)) | (_, None) => visible.len() - 1,
+                (_, Some(idx)) => idx - 1,
+            };
+
+            state.selected = Some(visible[idx].hit.clone());
+            vec![Cmd::None]
+        }
+
+        SearchMsg::Open => {
+            // Remote queries are only sent on Enter, not on every keystroke
+            if state.remote
+                && !state.query.is_empty()
+                && state.remote_query.as_deref() != Some(state.query.as_str())
+            {
+                state.remote_query = Some(state.query.clone());
+                state.remote_loading = true;
+                return vec![Cmd::SearchStories {
+                    query: state.query.clone(),
+                }];
+            }
+
+            match state
+                .selected
+                .as_ref()
+                .and_then(|hit| open_hit(hit, state, data))
+            {
+                Some(cmd) => vec![cmd],
+                None => vec![Cmd::None],
+            }
+        }
+
+        SearchMsg::OpenInBrowser => {
+            match state
+                .selected
+                .as_ref()
+                .and_then(|hit| app_url(hit, state, data))
+            {
+                Some(app_url) => vec![Cmd::OpenInBrowser { app_url }],
+                None => vec![Cmd::None],
+            }
+        }
+
+        SearchMsg::SetActive => {
+            let story = match &state.selected {
+                Some(SearchHit::Story(id)) => find_story(state, data, *id),
+                _ => None,
+            };
+
+            match story {
+                Some(story) => vec![Cmd::SelectStory(Some(story.clone())), Cmd::WriteCache],
+                None => vec![Cmd::None],
+            }
+        }
+
+        SearchMsg::ToggleRemote => {
+            state.remote = !state.remote;
+            state.search_active = true;
+            select_first(state, data);
+            vec![Cmd::None]
+        }
+
+        SearchMsg::ActivateSearch => {
+            state.search_active = true;
+            vec![Cmd::None]
+        }
+
+        SearchMsg::DeactivateSearch => {
+            state.search_active = false;
+            vec![Cmd::None]
+        }
+
+        SearchMsg::SearchInput(c) => {
+            state.query.push(c);
+            select_first(state, data);
+            vec![Cmd::None]
+        }
+
+        SearchMsg::SearchBackspace => {
+            state.query.pop();
+            select_first(state, data);
+            vec![Cmd::None]
+        }
+
+        SearchMsg::ClearSearch => {
+            state.query.clear();
+            state.remote_query = None;
+            state.remote_results.clear();
+            state.selected = None;
+            state.search_active = true;
+            vec![Cmd::None]
+        }
+    }
+}
+
+/// Applies the results of a remote search, ignoring responses for outdated queries
+pub fn apply_remote_results(state: &mut SearchState, data: &DataState, query: String, stories: Vec<Story>) {
+    if state.remote_query.as_deref() != Some(query.as_str()) {
+        return;
+    }
+
+    state.remote_loading = false;
+    state.remote_results = stories;
+    select_first(state, data);
+    // Leave typing mode so the results can be navigated and acted on straight away
+    state.search_active = false;
+}
+
+/// Forgets the failed query so Enter sends it again
+pub fn remote_search_failed(state: &mut SearchState, query: &str) {
+    if state.remote_query.as_deref() == Some(query) {
+        state.remote_query = None;
+    }
+}
+
+pub fn key_to_msg(key: KeyEvent) -> Option<SearchMsg> {
+    if key.code == KeyCode::Char('r') && key.modifiers.contains(KeyModifiers::CONTROL) {
+        return Some(SearchMsg::ToggleRemote);
+    }
+
+    if key.code == KeyCode::Enter {
+        return Some(SearchMsg::Open);
+    }
+
+    match Key::from_key_event(key)? {
+        Key::FocusNext => Some(SearchMsg::FocusNext),
+        Key::FocusPrev => Some(SearchMsg::FocusPrev),
+        Key::OpenNote => Some(SearchMsg::Open),
+        Key::OpenBrowser => Some(SearchMsg::OpenInBrowser),
+        Key::SelectStory => Some(SearchMsg::SetActive),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::epic::EpicSlim;
+    use crate::api::story::{Story, create_test_story};
+
+    fn story(id: i32, name: &str) -> Story {
+        Story { id, ..create_test_story(name) }
+    }
+
+    fn data() -> DataState {
+        let epic = EpicSlim {
+            id: 7,
+            name: "Login revamp".to_string(),
+            app_url: String::new(),
+            owner_ids: vec![],
+            created_at: chrono::DateTime::default(),
+        };
+
+        DataState {
+            stories: vec![story(1, "Fix flaky tests"), story(2, "Fix login redirect")],
+            epics: vec![epic],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn empty_query_matches_nothing() {
+        assert!(results("", &data(), &[]).is_empty());
+    }
+
+    #[test]
+    fn results_are_grouped_by_kind() {
+        let notes = vec![PathBuf::from("/notes/scratch/login-ideas.md")];
+        let hits: Vec<_> = results("login", &data(), &notes)
+            .into_iter()
+            .map(|r| r.hit)
+            .collect();
+
+        assert_eq!(
+            hits,
+            [
+                SearchHit::Story(2),
+                SearchHit::Epic(7),
+                SearchHit::Note(PathBuf::from("/notes/scratch/login-ideas.md")),
+            ]
+        );
+    }
+
+    #[test]
+    fn note_label_includes_directory() {
+        assert_eq!(
+            note_label(Path::new("/notes/daily/2026-02-18.md")),
+            "daily/2026-02-18"
+        );
+    }
+
+    #[test]
+    fn open_note_hit_routes_to_scratch_note() {
+        let path = PathBuf::from("/notes/scratch/ideas.md");
+        let cmd = open_hit(&SearchHit::Note(path.clone()), &SearchState::default(), &data());
+
+        assert!(matches!(cmd, Some(Cmd::OpenScratchNote { path: p, name }) if p == path && name == "ideas"));
+    }
+
+    #[test]
+    fn stale_remote_results_are_ignored() {
+        let mut state = SearchState {
+            remote: true,
+            remote_query: Some("login".to_string()),
+            remote_loading: true,
+            ..Default::default()
+        };
+
+        apply_remote_results(&mut state, &data(), "logi".to_string(), vec![story(3, "Old")]);
+        assert!(state.remote_results.is_empty());
+        assert!(state.remote_loading);
+
+        apply_remote_results(&mut state, &data(), "login".to_string(), vec![story(4, "New")]);
+        assert_eq!(state.selected, Some(SearchHit::Story(4)));
+        assert!(!state.remote_loading);
+    }
+
+    #[test]
+    fn failed_remote_search_is_sent_again() {
+        let mut state = SearchState {
+            remote: true,
+            query: "login".to_string(),
+            ..Default::default()
+        };
+
+        let sent = |state: &mut SearchState| {
+            matches!(update(state, &data(), SearchMsg::Open)[..], [Cmd::SearchStories { .. }])
+        };
+        assert!(sent(&mut state));
+        assert!(!sent(&mut state));
+        remote_search_failed(&mut state, "login");
+        assert!(sent(&mut state));
+    }
+}
@@ -0,0 +1,198 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Style,
+    widgets::{Block, BorderType, Clear, Padding, StatefulWidget, Widget},
+};
+use tui_widget_list::{ListBuilder, ListState, ListView};
+
+use crate::{
+    api::{
+        story::Story,
+        workflow::{Workflow, WorkflowState, get_workflow},
+    },
+    app::{cmd::Cmd, msg::StatePickerMsg},
+    navkey,
+    view::ActionItemWidget,
+};
+
+#[derive(Debug)]
+pub struct StatePickerState {
+    pub list_state: ListState,
+    pub is_showing: bool,
+    pub target_story_id: Option<i32>,
+}
+
+impl Default for StatePickerState {
+    fn default() -> Self {
+        let mut list_state = ListState::default();
+        list_state.select(Some(0));
+
+        Self {
+            list_state,
+            is_showing: false,
+            target_story_id: None,
+        }
+    }
+}
+
+/// Popup listing the workflow states a story can be moved to
+pub struct StatePicker<'a> {
+    states: Vec<&'a WorkflowState>,
+    current_state_id: i32,
+}
+
+impl<'a> StatePicker<'a> {
+    pub fn new(story: &Story, workflows: &'a [Workflow]) -> Self {
+        Self {
+            states: states_for_story(story, workflows),
+            current_state_id: story.workflow_state_id,
+        }
+    }
+
+    pub fn window_dimensions(&self) -> (usize, usize) {
+        let longest_label_len = self
+            .states
+            .iter()
+            .map(|s| s.name.len())
+            .max()
+            .unwrap_or_default();
+
+        // same padding as the action menu, +2 for the current state marker
+        (longest_label_len + 6 + 2, self.states.len() + 4)
+    }
+}
+
+impl StatefulWidget for StatePicker<'_> {
+    type State = ListState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(" Move to ")
+            .padding(Padding::vertical(1));
+
+        let inner = block.inner(area);
+
+        let highlight_style = Style::default().reversed();
+        let labels: Vec<String> = self
+            .states
+            .iter()
+            .map(|s| {
+                if s.id == self.current_state_id {
+                    format!("● {}", s.name)
+                } else {
+                    s.name.clone()
+                }
+            })
+            .collect();
+        let n_states = labels.len();
+
+        let builder = ListBuilder::new(move |context| {
+            let widget = ActionItemWidget::new(
+                labels[context.index].clone(),
+                context.is_selected,
+                highlight_style,
+            );
+            let height = widget.height();
+
+            (widget, height)
+        });
+
+        let list = ListView::new(builder, n_states);
+
+        Clear.render(area, buf);
+        block.render(area, buf);
+        StatefulWidget::render(list, inner, buf, state);
+    }
+}
+
+/// Returns the states of the workflow the story belongs to, in board order
+pub fn states_for_story<'a>(story: &Story, workflows: &'a [Workflow]) -> Vec<&'a WorkflowState> {
+    get_workflow(story.workflow_id, workflows)
+        .map(|wf| wf.ordered_states())
+        .unwrap_or_default()
+}
+
+pub fn open(state: &mut StatePickerState, story: &Story, workflows: &[Workflow]) {
+    let current_idx = states_for_story(story, workflows)
+        .iter()
+        .position(|s| s.id == story.workflow_state_id);
+
+    state.is_showing = true;
+    state.target_story_id = Some(story.id);
+    state.list_state.select(Some(current_idx.unwrap_or(0)));
+}
+
+fn close(state: &mut StatePickerState) {
+    state.is_showing = false;
+    state.target_story_id = None;
+    state.list_state.select(Some(0));
+}
+
+pub fn update(
+    state: &mut StatePickerState,
+    story: &Story,
+    workflows: &[Workflow],
+    msg: StatePickerMsg,
+) -> Vec<Cmd> {
+    let states = states_for_story(story, workflows);
+    let item_count = states.len();
+
+    match msg {
+        StatePickerMsg::FocusNext => {
+            if item_count == 0 {
+                return vec![Cmd::None];
+            }
+            let current = state.list_state.selected.unwrap_or(0);
+            let next = if current >= item_count - 1 { 0 } else { current + 1 };
+            state.list_state.select(Some(next));
+            vec![Cmd::None]
+        }
+
+        StatePickerMsg::FocusPrev => {
+            if item_count == 0 {
+                return vec![Cmd::None];
+            }
+            let current = state.list_state.selected.unwrap_or(0);
+            let prev = if current == 0 { item_count - 1 } else { current - 1 };
+            state.list_state.select(Some(prev));
+            vec![Cmd::None]
+        }
+
+        StatePickerMsg::Accept => {
+            let chosen = state
+                .list_state
+                .selected
+                .and_then(|idx| states.get(idx))
+                .map(|s| s.id);
+            close(state);
+
+            match chosen {
+                Some(workflow_state_id) if workflow_state_id != story.workflow_state_id => {
+                    vec![Cmd::UpdateStoryState {
+                        story_id: story.id,
+                        workflow_state_id,
+                    }]
+                }
+                _ => vec![Cmd::None],
+            }
+        }
+
+        StatePickerMsg::Close => {
+            close(state);
+            vec![Cmd::None]
+        }
+    }
+}
+
+pub fn key_to_msg(key: KeyEvent) -> Option<StatePickerMsg> {
+    match key.code {
+        KeyCode::Enter => Some(StatePickerMsg::Accept),
+        navkey!(down) => Some(StatePickerMsg::FocusNext),
+        navkey!(up) => Some(StatePickerMsg::FocusPrev),
+        KeyCode::Esc | KeyCode::Char('q') => Some(StatePickerMsg::Close),
+        _ => None,
+    }
+}
@@ -227,6 +227,9 @@ pub fn update(
             }
         }
 
+        // needs the config and other data, handled by `App::toggle_team_view`
+        StoryListMsg::ToggleTeam => vec![Cmd::None],
+
         StoryListMsg::ToggleFinished => {
             state.show_finished = !state.show_finished;
 
@@ -257,6 +260,7 @@ pub fn key_to_msg(key: KeyEvent) -> Option<StoryListMsg> {
         Key::FocusNext => Some(StoryListMsg::FocusNext),
         Key::FocusPrev => Some(StoryListMsg::FocusPrev),
         Key::ToggleFinished => Some(StoryListMsg::ToggleFinished),
+        Key::ToggleTeam => Some(StoryListMsg::ToggleTeam),
         Key::OpenBrowser => Some(StoryListMsg::OpenInBrowser),
         Key::OpenNote => Some(StoryListMsg::OpenNote),
         Key::SelectStory => Some(StoryListMsg::SelectStory),
@@ -99,10 +99,10 @@ pub fn update(
         }
 
         TodosListMsg::ToggleComplete => {
-            if let Some(id) = state.selected_id {
-                if let Some(todo) = todos.iter_mut().find(|t| t.id == id) {
-                    todo.completed = !todo.completed;
-                }
+            if let Some(id) = state.selected_id
+                && let Some(todo) = todos.iter_mut().find(|t| t.id == id)
+            {
+                todo.completed = !todo.completed;
             }
             vec![Cmd::WriteTodos]
         }
@@ -1,19 +1,31 @@
-use crossterm::event::{KeyCode, KeyEvent};
+use std::time::Duration;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
 use crate::{
-    api::story::get_story_associated_iteration,
+    api::{
+        backend::Backend,
+        error::ApiError,
+        story::{Story, get_story_associated_iteration},
+        workflow::{WorkflowStateType, get_workflow_state},
+    },
     app::{
         App,
         cmd::Cmd,
         model::{LoadingState, ViewType},
-        msg::{AddTodoModalMsg, CreateNoteModalMsg, EpicListMsg, IterationListMsg, Msg},
-        pane::{action_menu, add_todo_modal, create_note_modal, description_modal, epic_list, iteration_list, notes_list, story_list, todos_list},
+        msg::{ActivityListMsg, AddTodoModalMsg, CreateNoteModalMsg, DescriptionModalMsg, EpicListMsg, IterationListMsg, Msg, Reconnection, SearchMsg, StoryListMsg},
+        pane::{action_menu, activity_list, add_todo_modal, create_note_modal, description_modal, epic_list, iteration_list, notes_list, search, state_picker, story_list, todos_list},
     },
+    config::Config,
     dbg_file,
     error::ErrorInfo,
     keybindings::Key,
+    outbox::{Mutation, ReplayOutcome},
 };
 
+/// Shortcut's rate limit window is a minute, used when no `Retry-After` header was sent
+const DEFAULT_RATE_LIMIT_WAIT: Duration = Duration::from_secs(60);
+
 impl App {
     pub fn update(
         &mut self,
@@ -36,12 +48,17 @@ impl App {
 
             Msg::KeyPressed(key_event) => self.handle_key_input(key_event),
 
-            Msg::StoryList(story_msg) => story_list::update(
-                &mut self.model.ui.story_list,
-                &self.model.data.stories,
-                self.model.data.current_iterations_ref(),
-                story_msg,
-            ),
+            Msg::StoryList(StoryListMsg::ToggleTeam) => self.toggle_team_view(),
+
+            Msg::StoryList(story_msg) => {
+                let show_team = self.model.ui.story_list.show_team;
+                story_list::update(
+                    &mut self.model.ui.story_list,
+                    self.model.data.listed_stories(show_team),
+                    self.model.data.current_iterations_ref(),
+                    story_msg,
+                )
+            }
 
             Msg::NotesList(notes_msg) => notes_list::update(
                 &mut self.model.ui.notes_list,
@@ -58,6 +75,41 @@ impl App {
                 epic_list::update(&mut self.model.ui.epic_list, &self.model.data.epics, msg)
             }
 
+            Msg::ActivityList(msg) => {
+                let cmds = activity_list::update(
+                    &mut self.model.ui.activity_list,
+                    &mut self.model.data.activity,
+                    msg,
+                );
+
+                if matches!(msg, ActivityListMsg::Open)
+                    && let Some(story_id) = self
+                        .model
+                        .ui
+                        .activity_list
+                        .selected_id
+                        .and_then(|id| self.model.data.activity.iter().find(|a| a.id == id))
+                        .map(|a| a.story_id)
+                {
+                    match self.model.data.find_story(story_id) {
+                        Some(story) => {
+                            description_modal::open(
+                                &mut self.model.ui.description_modal,
+                                story.clone(),
+                            );
+                        }
+                        None => self.model.ui.errors.push(ErrorInfo::new(
+                            format!("sc-{} isn't loaded", story_id),
+                            "It is no longer in your iterations",
+                        )),
+                    }
+                }
+
+                cmds
+            }
+
+            Msg::Search(msg) => search::update(&mut self.model.ui.search, &self.model.data, msg),
+
             Msg::StoriesLoaded {
                 stories,
                 from_cache,
@@ -66,10 +118,19 @@ impl App {
                 // This keeps spinner showing during background refresh
                 if !from_cache {
                     self.model.ui.loading = LoadingState::Loaded;
+                    self.model.ui.last_refreshed = Some(crate::time::now_naive());
+                    self.model.ui.offline = false;
                 }
+                // back online, so anything queued can go out now
+                let replay = if from_cache { None } else { self.replay_outbox() };
+
+                let show_team = self.model.ui.story_list.show_team;
 
                 // Select first story if none selected and list is non-empty
-                if self.model.ui.story_list.selected_story_id.is_none() && !stories.is_empty() {
+                if !show_team
+                    && self.model.ui.story_list.selected_story_id.is_none()
+                    && !stories.is_empty()
+                {
                     self.model.ui.story_list.selected_story_id = stories.first().map(|s| s.id);
                 }
 
@@ -81,15 +142,52 @@ impl App {
                         .stories
                         .iter()
                         .zip(stories.iter())
-                        .all(|(a, b)| a.id == b.id)
+                        .all(|(a, b)| a.id == b.id && a.updated_at == b.updated_at)
                 {
-                    return vec![Cmd::None];
+                    // notes may still have been edited outside the TUI
+                    return replay.into_iter().chain(self.sync_note_tasks()).collect();
+                }
+
+                // Stories picked from a workspace search were never in the list, so only
+                // clear the active story when it drops out of the list
+                let active_was_listed = self.model.data.active_story.as_ref().is_some_and(|active| {
+                    self.model.data.stories.iter().any(|s| s.id == active.id)
+                });
+
+                let mut cmds = vec![Cmd::WriteCache];
+                cmds.extend(replay);
+                if !from_cache {
+                    let changes = crate::activity::diff_stories(
+                        &self.model.data.stories,
+                        &stories,
+                        self.backend.user_id(),
+                        crate::time::now_naive(),
+                    );
+
+                    if !changes.is_empty() {
+                        crate::activity::record(&mut self.model.data.activity, changes);
+                        if self.model.ui.activity_list.selected_id.is_none() {
+                            self.model.ui.activity_list.selected_id =
+                                self.model.data.activity.first().map(|a| a.id);
+                        }
+                        cmds.push(Cmd::WriteActivity);
+                    }
                 }
 
+                // linked stories can be opened without ever being in the list
+                let modal_was_listed =
+                    self.model.ui.description_modal.story.as_ref().is_some_and(|modal_story| {
+                        self.model.data.stories.iter().any(|s| s.id == modal_story.id)
+                    });
+
                 self.model.data.stories = stories.clone();
+                if !from_cache {
+                    cmds.extend(self.sync_note_tasks());
+                }
 
                 // Reconcile selection: if selected story no longer exists, select first
-                if let Some(selected_id) = self.model.ui.story_list.selected_story_id
+                if !show_team
+                    && let Some(selected_id) = self.model.ui.story_list.selected_story_id
                     && !stories.iter().any(|s| s.id == selected_id)
                 {
                     self.model.ui.story_list.selected_story_id = stories.first().map(|s| s.id);
@@ -99,12 +197,22 @@ impl App {
                 if self.model.ui.description_modal.is_showing
                     && let Some(ref modal_story) = self.model.ui.description_modal.story
                 {
-                    match stories.iter().find(|s| s.id == modal_story.id) {
-                        Some(fresh_story) => {
+                    let is_team_story = self
+                        .model
+                        .data
+                        .team_stories
+                        .iter()
+                        .any(|s| s.id == modal_story.id)
+                        || !modal_was_listed;
+
+                    match (stories.iter().find(|s| s.id == modal_story.id), is_team_story) {
+                        (Some(fresh_story), _) => {
                             // Update modal with fresh data
                             self.model.ui.description_modal.story = Some(fresh_story.clone());
                         }
-                        None => {
+                        // opened from the team view or a link, it was never one of ours
+                        (None, true) => {}
+                        (None, false) => {
                             // Story gone — close modal, show error
                             self.model.ui.description_modal.is_showing = false;
                             self.model.ui.description_modal.scroll_view_state = Default::default();
@@ -133,6 +241,7 @@ impl App {
 
                 // Reconcile active story
                 if let Some(ref active) = self.model.data.active_story
+                    && active_was_listed
                     && !stories.iter().any(|s| s.id == active.id)
                 {
                     // Active story no longer in iteration — clear it
@@ -146,11 +255,11 @@ impl App {
 
                 self.model.cache.iteration_stories = Some(stories);
 
-                vec![Cmd::WriteCache]
+                cmds
             }
 
             Msg::EpicsLoaded(mut epics) => {
-                epics.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+                epics.sort_by_key(|e| std::cmp::Reverse(e.created_at));
 
                 // Skip re-render if the ID set hasn't changed (same as StoriesLoaded)
                 if self.model.data.epics.len() == epics.len()
@@ -174,7 +283,7 @@ impl App {
             }
 
             Msg::IterationsLoaded(mut iterations) => {
-                iterations.sort_by(|a, b| b.start_date.cmp(&a.start_date));
+                iterations.sort_by_key(|it| std::cmp::Reverse(it.start_date));
                 if self.model.ui.iteration_list.selected_id.is_none() {
                     self.model.ui.iteration_list.selected_id = iterations.first().map(|it| it.id);
                 }
@@ -195,12 +304,343 @@ impl App {
             }
 
             Msg::AllIterationsLoaded(mut iterations) => {
-                iterations.sort_by(|a, b| b.start_date.cmp(&a.start_date));
+                iterations.sort_by_key(|it| std::cmp::Reverse(it.start_date));
                 self.model.data.iterations = iterations.clone();
                 self.model.cache.iterations = iterations;
                 vec![Cmd::WriteCache]
             }
 
+            Msg::WorkflowsLoaded(workflows) => {
+                self.model.data.workflows = workflows.clone();
+                self.model.cache.workflows = workflows;
+                vec![Cmd::WriteCache]
+            }
+
+            Msg::MembersLoaded(members) => {
+                self.model.data.members = members.clone();
+                self.model.cache.members = members;
+                vec![Cmd::WriteCache]
+            }
+
+            Msg::CommentSaved { story_id, comment } => {
+                let apply = |story: &mut Story| {
+                    if story.id != story_id {
+                        return;
+                    }
+                    match story.comments.iter_mut().find(|c| c.id == comment.id) {
+                        Some(existing) => *existing = comment.clone(),
+                        None => story.comments.push(comment.clone()),
+                    }
+                };
+
+                self.model.data.stories.iter_mut().for_each(apply);
+                self.model.data.team_stories.iter_mut().for_each(apply);
+                self.model.data.active_story.iter_mut().for_each(apply);
+                self.model.ui.description_modal.story.iter_mut().for_each(apply);
+
+                self.model.cache.iteration_stories = Some(self.model.data.stories.clone());
+                self.model.cache.active_story = self.model.data.active_story.clone();
+                vec![Cmd::WriteCache]
+            }
+
+            Msg::TaskSaved { story_id, task } => {
+                let apply = |story: &mut Story| {
+                    if story.id != story_id {
+                        return;
+                    }
+                    match story.tasks.iter_mut().find(|t| t.id == task.id) {
+                        Some(existing) => *existing = task.clone(),
+                        None => story.tasks.push(task.clone()),
+                    }
+                };
+
+                self.model.data.stories.iter_mut().for_each(apply);
+                self.model.data.team_stories.iter_mut().for_each(apply);
+                self.model.data.active_story.iter_mut().for_each(apply);
+                self.model.ui.description_modal.story.iter_mut().for_each(apply);
+
+                self.model.cache.iteration_stories = Some(self.model.data.stories.clone());
+                self.model.cache.active_story = self.model.data.active_story.clone();
+                vec![Cmd::WriteCache]
+            }
+
+            Msg::TaskDeleted { story_id, task_id } => {
+                let apply = |story: &mut Story| {
+                    if story.id == story_id {
+                        story.tasks.retain(|t| t.id != task_id);
+                    }
+                };
+
+                self.model.data.stories.iter_mut().for_each(apply);
+                self.model.data.team_stories.iter_mut().for_each(apply);
+                self.model.data.active_story.iter_mut().for_each(apply);
+                self.model.ui.description_modal.story.iter_mut().for_each(apply);
+
+                let modal = &mut self.model.ui.description_modal;
+                if modal.selected_task_id == Some(task_id) {
+                    modal.selected_task_id = None;
+                }
+
+                self.model.cache.iteration_stories = Some(self.model.data.stories.clone());
+                self.model.cache.active_story = self.model.data.active_story.clone();
+                vec![Cmd::WriteCache]
+            }
+
+            Msg::TeamStoriesLoaded(stories) => {
+                if self.model.ui.loading == LoadingState::FetchingTeamStories {
+                    self.model.ui.loading = LoadingState::Loaded;
+                }
+
+                self.model.data.team_stories = stories;
+
+                let state = &mut self.model.ui.story_list;
+                let team_stories = &self.model.data.team_stories;
+                if state.show_team
+                    && !state
+                        .selected_story_id
+                        .is_some_and(|id| team_stories.iter().any(|s| s.id == id))
+                {
+                    state.selected_story_id = team_stories.first().map(|s| s.id);
+                }
+
+                vec![Cmd::None]
+            }
+
+            Msg::StorySearchLoaded { query, stories } => {
+                search::apply_remote_results(
+                    &mut self.model.ui.search,
+                    &self.model.data,
+                    query,
+                    stories,
+                );
+                vec![Cmd::None]
+            }
+
+            Msg::StorySearchFailed { query, error } => {
+                search::remote_search_failed(&mut self.model.ui.search, &query);
+                self.update(Msg::ApiError {
+                    context: format!("Failed to search Shortcut for \"{}\"", query),
+                    error,
+                    story_id: None,
+                })
+            }
+
+            Msg::StoryStateUpdated {
+                story_id,
+                workflow_state_id,
+            } => {
+                let completed = get_workflow_state(workflow_state_id, &self.model.data.workflows)
+                    .map(|state| state.state_type == WorkflowStateType::Done);
+                let apply = |story: &mut Story| {
+                    if story.id == story_id {
+                        story.workflow_state_id = workflow_state_id;
+                        if let Some(completed) = completed {
+                            // the burndown shouldn't wait for the next refresh
+                            if completed != story.completed {
+                                story.completed_at = completed.then(chrono::Utc::now);
+                            }
+                            story.completed = completed;
+                        }
+                    }
+                };
+
+                self.model.data.stories.iter_mut().for_each(apply);
+                self.model.data.team_stories.iter_mut().for_each(apply);
+                self.model.data.active_story.iter_mut().for_each(apply);
+                self.model.ui.description_modal.story.iter_mut().for_each(apply);
+
+                self.model.cache.iteration_stories = Some(self.model.data.stories.clone());
+                self.model.cache.active_story = self.model.data.active_story.clone();
+                vec![Cmd::WriteCache]
+            }
+
+            Msg::StoryCreated { story, set_active } => {
+                self.model.data.stories.push(story.clone());
+                self.model.cache.iteration_stories = Some(self.model.data.stories.clone());
+
+                if !self.model.ui.story_list.show_team {
+                    self.model.ui.story_list.selected_story_id = Some(story.id);
+                }
+
+                let mut cmds = vec![Cmd::WriteCache];
+                if set_active {
+                    cmds.insert(0, Cmd::SelectStory(Some(story)));
+                }
+                cmds
+            }
+
+            Msg::StoryUpdated(updated) => {
+                let apply = |story: &mut Story| {
+                    if story.id == updated.id {
+                        *story = updated.clone();
+                    }
+                };
+
+                self.model.data.stories.iter_mut().for_each(apply);
+                self.model.data.team_stories.iter_mut().for_each(apply);
+                self.model.data.active_story.iter_mut().for_each(apply);
+                self.model.ui.description_modal.story.iter_mut().for_each(apply);
+
+                self.model.cache.iteration_stories = Some(self.model.data.stories.clone());
+                self.model.cache.active_story = self.model.data.active_story.clone();
+                vec![Cmd::WriteCache]
+            }
+
+            Msg::DescriptionUpdated {
+                story_id,
+                description,
+            } => {
+                let apply = |story: &mut Story| {
+                    if story.id == story_id {
+                        story.description = description.clone();
+                    }
+                };
+
+                self.model.data.stories.iter_mut().for_each(apply);
+                self.model.data.team_stories.iter_mut().for_each(apply);
+                self.model.data.active_story.iter_mut().for_each(apply);
+                self.model.ui.description_modal.story.iter_mut().for_each(apply);
+
+                self.model.cache.iteration_stories = Some(self.model.data.stories.clone());
+                self.model.cache.active_story = self.model.data.active_story.clone();
+                vec![Cmd::WriteCache]
+            }
+
+            Msg::MutationQueued(pending) => {
+                self.model.ui.offline = true;
+
+                // show the change straight away, it is sent once we're back online
+                let mut cmds = match &pending.mutation {
+                    Mutation::UpdateDescription { description, .. } => {
+                        self.update(Msg::DescriptionUpdated {
+                            story_id: pending.story_id,
+                            description: description.clone(),
+                        })
+                    }
+                    Mutation::UpdateState {
+                        workflow_state_id, ..
+                    } => self.update(Msg::StoryStateUpdated {
+                        story_id: pending.story_id,
+                        workflow_state_id: *workflow_state_id,
+                    }),
+                    Mutation::UpdateStory { update, .. } => {
+                        let story = self
+                            .model
+                            .data
+                            .find_story(pending.story_id)
+                            .or(self.model.data.active_story.as_ref())
+                            .filter(|s| s.id == pending.story_id)
+                            .cloned();
+
+                        match story {
+                            Some(mut story) => {
+                                update.apply(&mut story);
+                                self.update(Msg::StoryUpdated(story))
+                            }
+                            None => vec![],
+                        }
+                    }
+                    Mutation::SetTaskComplete { task_id, .. }
+                    | Mutation::RenameTask { task_id, .. } => {
+                        let task = self
+                            .model
+                            .data
+                            .find_story(pending.story_id)
+                            .or(self.model.ui.description_modal.story.as_ref())
+                            .filter(|s| s.id == pending.story_id)
+                            .and_then(|s| s.tasks.iter().find(|t| t.id == *task_id))
+                            .cloned();
+
+                        match task {
+                            Some(mut task) => {
+                                match &pending.mutation {
+                                    Mutation::SetTaskComplete { complete, .. } => {
+                                        task.complete = *complete
+                                    }
+                                    Mutation::RenameTask { description, .. } => {
+                                        task.description = description.clone()
+                                    }
+                                    _ => {}
+                                }
+                                self.update(Msg::TaskSaved {
+                                    story_id: pending.story_id,
+                                    task,
+                                })
+                            }
+                            None => vec![],
+                        }
+                    }
+                    Mutation::DeleteTask { task_id } => self.update(Msg::TaskDeleted {
+                        story_id: pending.story_id,
+                        task_id: *task_id,
+                    }),
+                    // comments and tasks need an id from Shortcut, they show up after the replay
+                    Mutation::CreateComment { .. }
+                    | Mutation::UpdateComment { .. }
+                    | Mutation::CreateTask { .. } => vec![],
+                };
+
+                self.model.data.outbox.push(pending);
+                cmds.push(Cmd::WriteOutbox);
+                cmds
+            }
+
+            Msg::OutboxReplayed(outcomes) => {
+                self.model.ui.replaying_outbox = false;
+                let mut sent_any = false;
+                let mut cmds = vec![Cmd::WriteOutbox];
+
+                for (id, outcome) in outcomes {
+                    let Some(index) = self.model.data.outbox.iter().position(|m| m.id == id)
+                    else {
+                        continue;
+                    };
+                    let story_id = self.model.data.outbox[index].story_id;
+
+                    match outcome {
+                        ReplayOutcome::Sent => sent_any = true,
+                        ReplayOutcome::Deferred(error) => {
+                            // e.g. still offline, it's sent again with the next replay
+                            cmds.extend(self.update(Msg::ApiError {
+                                context: format!("Failed to send queued change to sc-{}", story_id),
+                                error,
+                                story_id: None,
+                            }));
+                            break;
+                        }
+                        ReplayOutcome::Conflict { saved_to } => {
+                            let long = match saved_to {
+                                Some(path) => format!("Your change was saved to {}", path.display()),
+                                None => "Your change was dropped".to_string(),
+                            };
+                            self.model.ui.errors.push(ErrorInfo::new(
+                                format!("sc-{} changed in Shortcut while you were offline", story_id),
+                                long,
+                            ));
+                        }
+                        ReplayOutcome::Failed { error, saved_to } => {
+                            let long = match saved_to {
+                                Some(path) => {
+                                    format!("{}, your change was saved to {}", error, path.display())
+                                }
+                                None => format!("{}, your change was dropped", error),
+                            };
+                            self.model.ui.errors.push(ErrorInfo::new(
+                                format!("Failed to send queued change to sc-{}", story_id),
+                                long,
+                            ));
+                        }
+                    }
+
+                    self.model.data.outbox.remove(index);
+                }
+
+                if sent_any {
+                    cmds.extend(self.refresh());
+                }
+                cmds
+            }
+
             Msg::SwitchToView(view_type) => {
                 self.model.ui.active_view = view_type;
                 if view_type == ViewType::Notes {
@@ -222,6 +662,11 @@ impl App {
                         self.model.ui.notes_list.selected_path = first;
                     }
                 }
+                if view_type == ViewType::Search {
+                    self.model.ui.search.notes = search::scan_note_files(&self.model.config.notes_dir);
+                    // Start typing straight away when the view is opened
+                    self.model.ui.search.search_active = true;
+                }
                 vec![Cmd::None]
             }
 
@@ -235,36 +680,137 @@ impl App {
                     self.model.ui.notes_list.epic_notes = epics;
                     self.model.ui.notes_list.scratch_notes = scratch;
                 }
+                if self.model.ui.active_view == ViewType::Search {
+                    self.model.ui.search.notes = search::scan_note_files(&self.model.config.notes_dir);
+                }
+                // push ticked off tasks straight away
+                vec![self.sync_note_tasks().unwrap_or(Cmd::None)]
+            }
+
+            Msg::LinkedStoryLoaded { story, as_note } => {
+                if as_note {
+                    let iteration_app_url = self
+                        .model
+                        .data
+                        .current_iterations_ref()
+                        .and_then(|iterations| {
+                            get_story_associated_iteration(story.iteration_id, iterations)
+                        })
+                        .map(|it| it.app_url.clone());
+
+                    return vec![Cmd::OpenNote {
+                        story_id: story.id,
+                        story_name: story.name,
+                        story_app_url: story.app_url,
+                        iteration_app_url,
+                    }];
+                }
+
+                description_modal::open(&mut self.model.ui.description_modal, story);
                 vec![Cmd::None]
             }
 
             Msg::CacheWritten => vec![Cmd::None],
 
+            Msg::CacheMerged => {
+                self.model.data.active_story = self.model.cache.active_story.clone();
+                vec![Cmd::None]
+            }
+
+            Msg::Refresh => self.refresh(),
+
+            Msg::Reconnected(Reconnection { backend, config }) => {
+                self.reconnected(backend, config)
+            }
+
             Msg::Error(e) => {
                 self.model.ui.errors.push(e);
                 // Stop loading spinner on error
                 self.model.ui.loading = LoadingState::Loaded;
+                self.model.ui.search.remote_loading = false;
                 vec![Cmd::None]
             }
 
+            Msg::ApiError {
+                context,
+                error,
+                story_id,
+            } => {
+                self.model.ui.loading = LoadingState::Loaded;
+                self.model.ui.search.remote_loading = false;
+
+                match (&error, story_id) {
+                    // the navbar shows we're offline, a popup per failed request would be noise
+                    (ApiError::Network(_), _) => {
+                        self.model.ui.offline = true;
+                        vec![Cmd::None]
+                    }
+                    (ApiError::Unauthorized, _) => {
+                        self.model.ui.show_token_prompt = true;
+                        vec![Cmd::None]
+                    }
+                    (ApiError::NotFound, Some(story_id)) => {
+                        self.drop_story(story_id);
+                        self.model.ui.errors.push(ErrorInfo::new(
+                            format!("sc-{} no longer exists", story_id),
+                            "It was deleted or you lost access, removed it from the list",
+                        ));
+                        vec![Cmd::WriteCache]
+                    }
+                    (ApiError::RateLimited { retry_after }, _) => {
+                        let wait = retry_after.unwrap_or(DEFAULT_RATE_LIMIT_WAIT);
+                        self.model.ui.rate_limited_until = chrono::Duration::from_std(wait)
+                            .ok()
+                            .map(|wait| crate::time::now_naive() + wait);
+                        self.model.ui.errors.push(ErrorInfo::new(context, error));
+                        vec![Cmd::None]
+                    }
+                    _ => {
+                        self.model.ui.errors.push(ErrorInfo::new(context, error));
+                        vec![Cmd::None]
+                    }
+                }
+            }
+
             Msg::ActionMenu(menu_msg) => {
-                let story = self
-                    .model
-                    .ui
-                    .action_menu
-                    .target_story_id
-                    .and_then(|id| self.model.data.stories.iter().find(|s| s.id == id));
+                let target_story_id = self.model.ui.action_menu.target_story_id;
+                let story = target_story_id.and_then(|id| self.model.data.find_story(id));
 
-                if let Some(hovered_story) = story {
+                if target_story_id.is_some() && story.is_none() {
+                    // Target story no longer exists, close menu
+                    self.model.ui.action_menu.is_showing = false;
+                    vec![Cmd::None]
+                } else {
+                    // without a story, e.g. an empty list, only New Story does anything
                     action_menu::update(
                         &mut self.model.ui,
                         &self.model.data,
                         menu_msg,
-                        hovered_story,
+                        story,
+                        self.backend.user_id(),
+                        &self.model.config.branch_name_template,
+                    )
+                }
+            }
+
+            Msg::StatePicker(picker_msg) => {
+                let story = self
+                    .model
+                    .ui
+                    .state_picker
+                    .target_story_id
+                    .and_then(|id| self.model.data.find_story(id));
+
+                if let Some(story) = story {
+                    state_picker::update(
+                        &mut self.model.ui.state_picker,
+                        story,
+                        &self.model.data.workflows,
+                        picker_msg,
                     )
                 } else {
-                    // Target story no longer exists, close menu
-                    self.model.ui.action_menu.is_showing = false;
+                    // Target story no longer exists, close picker
+                    self.model.ui.state_picker.is_showing = false;
                     vec![Cmd::None]
                 }
             }
@@ -280,9 +826,12 @@ impl App {
                 vec![Cmd::None]
             }
 
-            Msg::DescriptionModal(modal_msg) => {
-                description_modal::update(&mut self.model.ui.description_modal, modal_msg)
-            }
+            Msg::DescriptionModal(modal_msg) => description_modal::update(
+                &mut self.model.ui.description_modal,
+                self.backend.user_id(),
+                &mut self.model.ui.errors,
+                modal_msg,
+            ),
 
             Msg::CreateNoteModal(modal_msg) => create_note_modal::update(
                 &mut self.model.ui.create_note_modal,
@@ -304,7 +853,180 @@ impl App {
         }
     }
 
-    /// Intercepts keys for search state in Iteration/Epic views.
+    /// Switches the story list between our own stories and the team's, refetching the team's
+    /// every time they are shown
+    fn toggle_team_view(&mut self) -> Vec<Cmd> {
+        let state = &mut self.model.ui.story_list;
+        state.show_team = !state.show_team;
+
+        let stories = self.model.data.listed_stories(state.show_team);
+        if !state
+            .selected_story_id
+            .is_some_and(|id| stories.iter().any(|s| s.id == id))
+        {
+            state.selected_story_id = stories.first().map(|s| s.id);
+        }
+
+        if !state.show_team {
+            return vec![Cmd::None];
+        }
+
+        self.model.ui.loading = LoadingState::FetchingTeamStories;
+        let iteration_ids = self
+            .model
+            .data
+            .current_iterations
+            .iter()
+            .flatten()
+            .map(|it| it.id)
+            .collect();
+
+        vec![Cmd::FetchTeamStories {
+            iteration_ids,
+            groups: self.model.config.groups.clone(),
+        }]
+    }
+
+    /// Refetches iterations (and through them our stories), epics and the team's stories if
+    /// they were loaded. Selection and open modals are kept by the `*Loaded` reconciliation.
+    fn refresh(&mut self) -> Vec<Cmd> {
+        // a refresh is already in flight, or the initial load hasn't finished
+        if self.model.ui.loading.is_loading() {
+            return vec![Cmd::None];
+        }
+
+        // also while reconnecting, so the refresh interval doesn't ask again every tick
+        self.model.ui.refresh_started_at = Some(crate::time::now_naive());
+
+        // we started offline, so there is no session to refresh with yet
+        if self.needs_reconnect {
+            return vec![Cmd::Reconnect {
+                source: self.backend_source.clone(),
+                config: self.config.clone(),
+            }];
+        }
+
+        self.model.ui.loading = LoadingState::FetchingIteration;
+
+        let mut cmds = vec![Cmd::FetchIterations, Cmd::FetchEpics];
+        if self.model.ui.story_list.show_team {
+            cmds.push(Cmd::FetchTeamStories {
+                iteration_ids: self
+                    .model
+                    .data
+                    .current_iterations
+                    .iter()
+                    .flatten()
+                    .map(|it| it.id)
+                    .collect(),
+                groups: self.model.config.groups.clone(),
+            });
+        }
+
+        cmds
+    }
+
+    /// Swaps in the backend built by `Cmd::Reconnect` and refetches everything with it
+    fn reconnected(&mut self, backend: Backend, config: Config) -> Vec<Cmd> {
+        self.model.cache.user_id = Some(backend.user_id());
+        self.backend = backend;
+        self.needs_reconnect = false;
+        self.model.config = Config {
+            cache_dir: self.backend_source.cache_dir(&config.cache_dir),
+            ..config.clone()
+        };
+        self.config = config;
+        self.model.ui.loading = LoadingState::FetchingIteration;
+        self.model.ui.refresh_started_at = Some(crate::time::now_naive());
+
+        vec![Cmd::WriteCache, Cmd::FetchInfo]
+    }
+
+    /// Syncs the task blocks in the notes of our stories, once nothing is waiting in the outbox
+    /// that the sync would undo
+    fn sync_note_tasks(&self) -> Option<Cmd> {
+        if !self.model.data.outbox.is_empty() {
+            return None;
+        }
+
+        let mut stories = self.model.data.stories.clone();
+        if let Some(active) = &self.model.data.active_story
+            && !stories.iter().any(|s| s.id == active.id)
+        {
+            stories.push(active.clone());
+        }
+        Some(Cmd::SyncNoteTasks(stories))
+    }
+
+    /// Starts sending the outbox, unless it's empty or already being sent
+    fn replay_outbox(&mut self) -> Option<Cmd> {
+        if self.model.data.outbox.is_empty() || self.model.ui.replaying_outbox {
+            return None;
+        }
+
+        self.model.ui.replaying_outbox = true;
+        Some(Cmd::ReplayOutbox)
+    }
+
+    /// Whether the refresh interval has passed since the last refresh started
+    pub(crate) fn refresh_due(&self) -> bool {
+        let Some(interval) = self.model.config.refresh_interval() else {
+            return false;
+        };
+        let now = crate::time::now_naive();
+
+        if self.model.ui.rate_limited_until.is_some_and(|until| until > now) {
+            return false;
+        }
+
+        self.model
+            .ui
+            .refresh_started_at
+            .is_none_or(|started| now - started >= interval)
+    }
+
+    /// Removes a story Shortcut no longer knows about from the list, cache and any open overlays
+    fn drop_story(&mut self, story_id: i32) {
+        self.model.data.stories.retain(|s| s.id != story_id);
+        self.model.data.team_stories.retain(|s| s.id != story_id);
+        self.model.cache.iteration_stories = Some(self.model.data.stories.clone());
+        self.model.ui.search.remote_results.retain(|s| s.id != story_id);
+
+        if self.model.data.active_story.as_ref().is_some_and(|s| s.id == story_id) {
+            self.model.data.active_story = None;
+            self.model.cache.active_story = None;
+        }
+
+        if self.model.ui.story_list.selected_story_id == Some(story_id) {
+            self.model.ui.story_list.selected_story_id = self
+                .model
+                .data
+                .listed_stories(self.model.ui.story_list.show_team)
+                .first()
+                .map(|s| s.id);
+        }
+
+        if self.model.ui.description_modal.story.as_ref().is_some_and(|s| s.id == story_id) {
+            description_modal::update(
+                &mut self.model.ui.description_modal,
+                self.backend.user_id(),
+                &mut self.model.ui.errors,
+                DescriptionModalMsg::Close,
+            );
+        }
+
+        if self.model.ui.action_menu.target_story_id == Some(story_id) {
+            self.model.ui.action_menu.is_showing = false;
+            self.model.ui.action_menu.target_story_id = None;
+        }
+
+        if self.model.ui.state_picker.target_story_id == Some(story_id) {
+            self.model.ui.state_picker.is_showing = false;
+            self.model.ui.state_picker.target_story_id = None;
+        }
+    }
+
+    /// Intercepts keys for search state in Iteration/Epic/Search views.
     ///
     /// Two modes:
     /// - **Active** (`search_active = true`): typing mode. j/k are consumed (not
@@ -321,6 +1043,10 @@ impl App {
                 self.model.ui.epic_list.search_active,
                 !self.model.ui.epic_list.search_query.is_empty(),
             ),
+            ViewType::Search => (
+                self.model.ui.search.search_active,
+                !self.model.ui.search.query.is_empty(),
+            ),
             _ => return None,
         };
 
@@ -328,20 +1054,36 @@ impl App {
             let msg = match key.code {
                 // Enter still opens the selected item
                 KeyCode::Enter => return None,
+                // The search view is typing-first, so let arrows, view switching and the
+                // remote toggle through
+                KeyCode::Up | KeyCode::Down | KeyCode::Tab | KeyCode::BackTab
+                    if self.model.ui.active_view == ViewType::Search =>
+                {
+                    return None;
+                }
+                KeyCode::Char('r')
+                    if self.model.ui.active_view == ViewType::Search
+                        && key.modifiers.contains(KeyModifiers::CONTROL) =>
+                {
+                    return None;
+                }
                 // Esc: exit typing mode, keep query so the list stays filtered
                 KeyCode::Esc => match self.model.ui.active_view {
                     ViewType::Iterations => Msg::IterationList(IterationListMsg::DeactivateSearch),
                     ViewType::Epics => Msg::EpicList(EpicListMsg::DeactivateSearch),
+                    ViewType::Search => Msg::Search(SearchMsg::DeactivateSearch),
                     _ => unreachable!(),
                 },
                 KeyCode::Backspace => match self.model.ui.active_view {
                     ViewType::Iterations => Msg::IterationList(IterationListMsg::SearchBackspace),
                     ViewType::Epics => Msg::EpicList(EpicListMsg::SearchBackspace),
+                    ViewType::Search => Msg::Search(SearchMsg::SearchBackspace),
                     _ => unreachable!(),
                 },
                 KeyCode::Char(c) => match self.model.ui.active_view {
                     ViewType::Iterations => Msg::IterationList(IterationListMsg::SearchInput(c)),
                     ViewType::Epics => Msg::EpicList(EpicListMsg::SearchInput(c)),
+                    ViewType::Search => Msg::Search(SearchMsg::SearchInput(c)),
                     _ => unreachable!(),
                 },
                 _ => return Some(vec![Cmd::None]),
@@ -354,6 +1096,7 @@ impl App {
             let msg = match self.model.ui.active_view {
                 ViewType::Iterations => Msg::IterationList(IterationListMsg::ClearSearch),
                 ViewType::Epics => Msg::EpicList(EpicListMsg::ClearSearch),
+                ViewType::Search => Msg::Search(SearchMsg::ClearSearch),
                 _ => unreachable!(),
             };
             return Some(self.update(msg));
@@ -363,6 +1106,16 @@ impl App {
     }
 
     fn handle_key_input(&mut self, key: KeyEvent) -> Vec<Cmd> {
+        // Token prompt blocks everything else, nothing works until the token is fixed
+        if self.model.ui.show_token_prompt {
+            match key.code {
+                KeyCode::Char('e') => return vec![Cmd::EditConfig],
+                KeyCode::Esc | KeyCode::Char('q') => self.model.ui.show_token_prompt = false,
+                _ => {}
+            }
+            return vec![Cmd::None];
+        }
+
         // Keybinds panel takes highest priority
         if self.model.ui.show_keybinds_panel {
             if matches!(key.code, KeyCode::Char('?') | KeyCode::Char('q') | KeyCode::Esc) {
@@ -389,6 +1142,15 @@ impl App {
             };
         }
 
+        // State picker is opened from the action menu or story list and swallows all keys
+        if self.model.ui.state_picker.is_showing {
+            return if let Some(msg) = state_picker::key_to_msg(key) {
+                self.update(Msg::StatePicker(msg))
+            } else {
+                vec![Cmd::None]
+            };
+        }
+
         // Create note modal intercepts all keys when showing
         if self.model.ui.create_note_modal.is_showing {
             return if let Some(modal_msg) = create_note_modal::key_to_msg(key) {
@@ -407,7 +1169,7 @@ impl App {
             };
         }
 
-        // Search bar intercepts most keys when active in Iteration/Epic views
+        // Search bar intercepts most keys when active in Iteration/Epic/Search views
         if let Some(cmds) = self.try_handle_search_key(key) {
             return cmds;
         }
@@ -427,6 +1189,7 @@ impl App {
                     let prev = self.model.ui.active_view.prev();
                     return self.update(Msg::SwitchToView(prev));
                 }
+                Key::Refresh => return self.update(Msg::Refresh),
                 Key::DailyNote => {
                     let today = crate::time::today();
                     let path = self.config.notes_dir.join("daily").join(format!("{}.md", today));
@@ -454,6 +1217,26 @@ impl App {
                     return self.update(Msg::EpicList(msg));
                 }
             }
+            ViewType::Search => {
+                if key.code == KeyCode::Char('/') {
+                    return self.update(Msg::Search(SearchMsg::ActivateSearch));
+                }
+                if app_key == Some(Key::Description) {
+                    if let Some(search::SearchHit::Story(id)) = self.model.ui.search.selected
+                        && let Some(story) =
+                            search::find_story(&self.model.ui.search, &self.model.data, id)
+                    {
+                        description_modal::open(
+                            &mut self.model.ui.description_modal,
+                            story.clone(),
+                        );
+                    }
+                    return vec![Cmd::None];
+                }
+                if let Some(msg) = search::key_to_msg(key) {
+                    return self.update(Msg::Search(msg));
+                }
+            }
             ViewType::Stories => {
                 if key.code == KeyCode::Enter {
                     return self.update(Msg::ToggleActionMenu);
@@ -467,12 +1250,41 @@ impl App {
                                 .ui
                                 .story_list
                                 .selected_story_id
-                                .and_then(|id| self.model.data.stories.iter().find(|s| s.id == id));
+                                .and_then(|id| self.model.data.find_story(id));
+
+                            let Some(story) = story else {
+                                return vec![Cmd::None];
+                            };
+
+                            let story_id = story.id;
+                            description_modal::open(
+                                &mut self.model.ui.description_modal,
+                                story.clone(),
+                            );
+
+                            // seeing the story is as good as reading its activity
+                            return if crate::activity::mark_story_read(
+                                &mut self.model.data.activity,
+                                story_id,
+                            ) {
+                                vec![Cmd::WriteActivity]
+                            } else {
+                                vec![Cmd::None]
+                            };
+                        }
+                        Key::ChangeState => {
+                            let story = self
+                                .model
+                                .ui
+                                .story_list
+                                .selected_story_id
+                                .and_then(|id| self.model.data.find_story(id));
 
                             if let Some(story) = story {
-                                description_modal::open(
-                                    &mut self.model.ui.description_modal,
-                                    story.clone(),
+                                state_picker::open(
+                                    &mut self.model.ui.state_picker,
+                                    story,
+                                    &self.model.data.workflows,
                                 );
                             }
                             return vec![Cmd::None];
@@ -483,7 +1295,7 @@ impl App {
                                 .ui
                                 .story_list
                                 .selected_story_id
-                                .and_then(|id| self.model.data.stories.iter().find(|s| s.id == id));
+                                .and_then(|id| self.model.data.find_story(id));
 
                             if let Some(story) = story {
                                 let iteration = self
@@ -527,6 +1339,11 @@ impl App {
                     return self.update(Msg::NotesList(msg));
                 }
             }
+            ViewType::Activity => {
+                if let Some(msg) = activity_list::key_to_msg(key) {
+                    return self.update(Msg::ActivityList(msg));
+                }
+            }
             ViewType::Todos => {
                 if key.code == KeyCode::Char('n') {
                     return self.update(Msg::AddTodoModal(AddTodoModalMsg::Open));
@@ -535,9 +1352,76 @@ impl App {
                     return self.update(Msg::TodosList(msg));
                 }
             }
-            _ => {}
         }
 
         vec![Cmd::None]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, sync::Arc};
+
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::{
+        api::{backend::BackendSource, fake::FakeBackend},
+        app::model::Model,
+        cache::Cache,
+        config::{Config, Mux},
+        dummy,
+    };
+
+    fn app() -> App {
+        let config = Config {
+            notes_dir: PathBuf::from("/notes"),
+            cache_dir: PathBuf::from("/cache"),
+            api_token: String::new(),
+            editor: String::new(),
+            repositories_directory: PathBuf::from("/repos"),
+            mux: Mux::default(),
+            branch_name_template: String::new(),
+            api_base_url: String::new(),
+            groups: Vec::new(),
+            refresh_interval_secs: 300,
+        };
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        App {
+            model: Model::from_cache_and_config(Cache::default(), config.clone(), vec![], vec![]),
+            exit: false,
+            receiver,
+            sender,
+            backend: Arc::new(FakeBackend::new(dummy::fixture())),
+            backend_source: BackendSource::Fake { fixture: None },
+            config,
+            needs_reconnect: false,
+        }
+    }
+
+    #[test]
+    fn ctrl_r_in_search_toggles_remote_instead_of_refreshing() {
+        let mut app = app();
+        app.model.ui.active_view = ViewType::Search;
+        app.model.ui.search.search_active = true;
+
+        let ctrl_r = KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL);
+        app.update(Msg::KeyPressed(ctrl_r));
+        assert!(app.model.ui.search.remote);
+        assert!(app.model.ui.refresh_started_at.is_none());
+    }
+
+    #[test]
+    fn refresh_while_offline_reconnects_once_per_interval() {
+        let mut app = app();
+        // as `App::init` leaves it after starting offline
+        app.needs_reconnect = true;
+        app.model.ui.loading = LoadingState::Loaded;
+        assert!(app.refresh_due());
+
+        let cmds = app.update(Msg::Refresh);
+        assert!(matches!(cmds.as_slice(), [Cmd::Reconnect { .. }]));
+        assert!(!app.refresh_due());
+    }
+}
@@ -0,0 +1,116 @@
+use chrono::{FixedOffset, NaiveDate};
+
+use crate::api::{iteration::Iteration, story::Story};
+
+/// Points over the days of an iteration, day 0 being its start date. Built only from the
+/// stories' estimates and completion dates, so it works from the cache.
+#[derive(Debug, PartialEq)]
+pub struct Burndown {
+    pub total: f64,
+    /// Points still open at the end of each day so far
+    pub remaining: Vec<(f64, f64)>,
+    /// Points completed by the end of each day so far
+    pub done: Vec<(f64, f64)>,
+    /// Day of the iteration's end date
+    pub days: f64,
+}
+
+impl Burndown {
+    /// Stories count as done on the day they were completed at `offset`, the one `today` is in
+    pub fn new(
+        iteration: &Iteration,
+        stories: &[&Story],
+        today: NaiveDate,
+        offset: FixedOffset,
+    ) -> Self {
+        let days = (iteration.end_date - iteration.start_date).num_days().max(0);
+        let last_day = today.min(iteration.end_date);
+        let days_so_far = (last_day - iteration.start_date).num_days().clamp(0, days);
+
+        let completed: Vec<_> = stories
+            .iter()
+            .filter(|s| s.completed)
+            .map(|s| {
+                // the day it was finished on, anything finished before the start counts on day 0
+                let finished = s
+                    .completed_at
+                    .or(s.updated_at)
+                    .map(|at| at.with_timezone(&offset).date_naive())
+                    .unwrap_or(iteration.start_date);
+                let day = (finished - iteration.start_date).num_days().max(0);
+                (day, s.estimate.unwrap_or(0) as f64)
+            })
+            .collect();
+
+        let total = stories.iter().map(|s| s.estimate.unwrap_or(0) as f64).sum();
+        let done: Vec<_> = (0..=days_so_far)
+            .map(|day| {
+                let points = completed.iter().filter(|(d, _)| *d <= day).map(|(_, p)| p).sum();
+                (day as f64, points)
+            })
+            .collect();
+        let remaining = done.iter().map(|(day, points)| (*day, total - points)).collect();
+
+        Self {
+            total,
+            remaining,
+            done,
+            days: days as f64,
+        }
+    }
+
+    /// Straight line from everything open on the first day to nothing open on the last
+    pub fn ideal_remaining(&self) -> [(f64, f64); 2] {
+        [(0.0, self.total), (self.days, 0.0)]
+    }
+
+    /// Straight line from nothing done on the first day to everything done on the last
+    pub fn ideal_done(&self) -> [(f64, f64); 2] {
+        [(0.0, 0.0), (self.days, self.total)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+    use crate::api::story::create_test_story;
+
+    fn story(estimate: i32, completed_on: Option<(u32, u32)>) -> Story {
+        Story {
+            id: 1,
+            completed: completed_on.is_some(),
+            completed_at: completed_on
+                .map(|(month, day)| Utc.with_ymd_and_hms(2026, month, day, 12, 0, 0).unwrap()),
+            iteration_id: Some(1),
+            estimate: Some(estimate),
+            ..create_test_story("Story")
+        }
+    }
+
+    #[test]
+    fn points_burn_down_on_the_day_stories_are_completed() {
+        let iteration = Iteration {
+            id: 1,
+            name: "Sprint".to_string(),
+            description: String::new(),
+            start_date: NaiveDate::from_ymd_opt(2026, 3, 2).unwrap(),
+            end_date: NaiveDate::from_ymd_opt(2026, 3, 13).unwrap(),
+            app_url: String::new(),
+            updated_at: None,
+        };
+        let early = story(2, Some((2, 27)));
+        let second_day = story(3, Some((3, 3)));
+        let open = story(5, None);
+
+        let today = NaiveDate::from_ymd_opt(2026, 3, 4).unwrap();
+        let stories = [&early, &second_day, &open];
+        let burndown = Burndown::new(&iteration, &stories, today, FixedOffset::east_opt(0).unwrap());
+
+        assert_eq!(burndown.total, 10.0);
+        assert_eq!(burndown.days, 11.0);
+        assert_eq!(burndown.remaining, vec![(0.0, 8.0), (1.0, 5.0), (2.0, 5.0)]);
+        assert_eq!(burndown.done, vec![(0.0, 2.0), (1.0, 5.0), (2.0, 5.0)]);
+    }
+}
@@ -1,38 +1,91 @@
-use std::{
-    env,
-    path::{Path, PathBuf},
-};
+use std::{env, path::PathBuf};
 
-use serde::{Deserialize, Serialize};
-use tokio::{fs::{self, File, remove_file}, io::{AsyncReadExt, AsyncWriteExt}};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{Map, Value, json};
+use tokio::{fs::{self, File}, io::AsyncReadExt};
 use uuid::Uuid;
 
 use crate::{
-    api::{epic::EpicSlim, iteration::Iteration, story::Story},
-    dbg_file,
+    api::{
+        epic::EpicSlim, iteration::Iteration, story::Story, user::WorkspaceMember,
+        workflow::Workflow,
+    },
+    dbg_file, persist,
 };
 
+/// Bump when a change to the cached types can't be absorbed by serde defaults, and add the
+/// migration to `MIGRATIONS`
+pub const CACHE_VERSION: u32 = 1;
+
+/// `MIGRATIONS[n]` upgrades a version `n` cache to version `n + 1`
+const MIGRATIONS: &[fn(&mut Map<String, Value>)] = &[migrate_v0_to_v1];
+
+/// Version 0 is every cache written before versioning. Its stories have no workflow or story
+/// type, and its comments no id or creation time, the next refresh replaces the stand-ins.
+fn migrate_v0_to_v1(fields: &mut Map<String, Value>) {
+    fn migrate_story(story: &mut Value) {
+        let Some(story) = story.as_object_mut() else {
+            return;
+        };
+        story.entry("workflow_id").or_insert(json!(0));
+        story.entry("workflow_state_id").or_insert(json!(0));
+        story.entry("story_type").or_insert(json!("feature"));
+
+        let comments = story.get_mut("comments").and_then(Value::as_array_mut);
+        for comment in comments.into_iter().flatten().filter_map(Value::as_object_mut) {
+            // negative so they can't clash with real ids, see `activity::diff_stories`
+            let position = comment.get("position").and_then(Value::as_i64).unwrap_or(0);
+            comment.entry("id").or_insert(json!(-position));
+            comment
+                .entry("created_at")
+                .or_insert(json!(DateTime::<Utc>::UNIX_EPOCH));
+        }
+    }
+
+    if let Some(Value::Array(stories)) = fields.get_mut("iteration_stories") {
+        stories.iter_mut().for_each(migrate_story);
+    }
+    if let Some(story) = fields.get_mut("active_story") {
+        migrate_story(story);
+    }
+}
+
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct Cache {
+    #[serde(default)]
+    pub version: u32,
     pub current_iterations: Option<Vec<Iteration>>,
     pub iterations: Vec<Iteration>,
     pub iteration_stories: Option<Vec<Story>>,
     pub epics: Vec<EpicSlim>,
+    #[serde(default)]
+    pub workflows: Vec<Workflow>,
+    #[serde(default)]
+    pub members: Vec<WorkspaceMember>,
     pub active_story: Option<Story>,
     pub user_id: Option<Uuid>,
     pub cache_dir: PathBuf,
+    /// The cache as last read from or written to disk, the base for merging in changes made by
+    /// other processes
+    #[serde(skip)]
+    synced: Option<Map<String, Value>>,
 }
 
 impl Default for Cache {
     fn default() -> Self {
         Self {
+            version: CACHE_VERSION,
             current_iterations: None,
             iteration_stories: None,
             iterations: Vec::new(),
             epics: Vec::new(),
+            workflows: Vec::new(),
+            members: Vec::new(),
             user_id: None,
             active_story: None,
             cache_dir: Self::default_cache_dir(),
+            synced: None,
         }
     }
 }
@@ -79,25 +132,155 @@ impl Cache {
             }
         };
 
-        match serde_json::from_str::<Cache>(&contents) {
+        let cache_dir = cache_file
+            .parent()
+            .map(PathBuf::from)
+            .unwrap_or_else(Self::default_cache_dir);
+        Self::from_json(&contents, cache_dir)
+    }
+
+    /// Migrates a cache written by an older version up to `CACHE_VERSION`. Anything that still
+    /// doesn't parse is dropped field by field, and refetched, rather than losing the whole
+    /// cache.
+    fn from_json(contents: &str, cache_dir: PathBuf) -> Self {
+        let mut fields = match serde_json::from_str::<Value>(contents) {
+            Ok(Value::Object(fields)) => fields,
+            _ => {
+                dbg_file!("Cache isn't a JSON object, starting from scratch");
+                return Self {
+                    cache_dir,
+                    ..Self::default()
+                };
+            }
+        };
+
+        let version = Self::migrate(&mut fields);
+        let mut cache = match serde_json::from_value::<Cache>(Value::Object(fields.clone())) {
             Ok(cache) => cache,
-            Err(_) => {
-                let cache_file = Path::new(&cache_file);
-                if cache_file.is_file() {
-                    let _ = remove_file(cache_file).await;
-                }
-                Self::default()
+            Err(e) => {
+                dbg_file!("Cache v{} didn't migrate cleanly, salvaging: {}", version, e);
+                Self::salvage(&fields)
             }
+        };
+
+        cache.version = CACHE_VERSION;
+        cache.cache_dir = cache_dir;
+        cache.synced = Some(cache.fields());
+        cache
+    }
+
+    /// Runs the migrations `fields` is missing, returns the version it was at
+    fn migrate(fields: &mut Map<String, Value>) -> usize {
+        let version = fields.get("version").and_then(Value::as_u64).unwrap_or(0) as usize;
+        for migration in MIGRATIONS.iter().skip(version) {
+            migration(fields);
         }
+        version
     }
 
-    pub async fn write(&self) -> anyhow::Result<()> {
+    fn fields(&self) -> Map<String, Value> {
+        match serde_json::to_value(self) {
+            Ok(Value::Object(fields)) => fields,
+            _ => unreachable!("Cache always serializes to an object"),
+        }
+    }
+
+    /// Three-way merge of the cache on disk into ours, see `persist::merge_fields`
+    fn merge_with(self, contents: &str) -> anyhow::Result<Self> {
+        let Some(base) = &self.synced else {
+            // we never saw the file, so there is nothing to tell their changes apart by
+            return Ok(self);
+        };
+
+        // salvaged or defaulted fields would look like they removed everything, so a file that
+        // doesn't parse cleanly is overwritten with ours instead
+        let Ok(Value::Object(mut theirs)) = serde_json::from_str::<Value>(contents) else {
+            return Ok(self);
+        };
+        Self::migrate(&mut theirs);
+        let Ok(mut theirs) = serde_json::from_value::<Cache>(Value::Object(theirs)) else {
+            return Ok(self);
+        };
+        theirs.version = CACHE_VERSION;
+        theirs.cache_dir = self.cache_dir.clone();
+
+        let fields = persist::merge_fields(base, &self.fields(), &theirs.fields());
+
+        let mut merged: Cache = serde_json::from_value(Value::Object(fields))?;
+        merged.cache_dir = self.cache_dir;
+        Ok(merged)
+    }
+
+    /// Keeps every field, and every element of list fields, that still parses on its own
+    fn salvage(fields: &Map<String, Value>) -> Self {
+        fn field<T: DeserializeOwned + Default>(fields: &Map<String, Value>, name: &str) -> T {
+            fields
+                .get(name)
+                .and_then(|value| serde_json::from_value(value.clone()).ok())
+                .unwrap_or_default()
+        }
+
+        fn list<T: DeserializeOwned>(fields: &Map<String, Value>, name: &str) -> Option<Vec<T>> {
+            let Some(Value::Array(values)) = fields.get(name) else {
+                return None;
+            };
+
+            Some(
+                values
+                    .iter()
+                    .filter_map(|value| serde_json::from_value(value.clone()).ok())
+                    .collect(),
+            )
+        }
+
+        let iteration_stories: Option<Vec<Story>> = list(fields, "iteration_stories");
+
+        // the active story is worth recovering from the list if only its own copy is broken
+        let active_story = field::<Option<Story>>(fields, "active_story").or_else(|| {
+            let id = fields.get("active_story")?.get("id")?.as_i64()?;
+            iteration_stories
+                .iter()
+                .flatten()
+                .find(|s| i64::from(s.id) == id)
+                .cloned()
+        });
+
+        Self {
+            version: CACHE_VERSION,
+            current_iterations: list(fields, "current_iterations"),
+            iterations: list(fields, "iterations").unwrap_or_default(),
+            iteration_stories,
+            epics: list(fields, "epics").unwrap_or_default(),
+            workflows: list(fields, "workflows").unwrap_or_default(),
+            members: list(fields, "members").unwrap_or_default(),
+            active_story,
+            user_id: field(fields, "user_id"),
+            cache_dir: Self::default_cache_dir(),
+            synced: None,
+        }
+    }
+
+    /// Writes the cache, keeping anything another `sc` process wrote since we last read or
+    /// wrote it. Returns whether there was anything to keep, `self` then has their changes too.
+    pub async fn write(&mut self) -> anyhow::Result<bool> {
         let cache_file = Self::get_cache_file(self.cache_dir.clone());
-        let mut f = File::create(cache_file).await?;
-        f.write_all(&serde_json::to_string(self)?.into_bytes())
-            .await?;
+        let ours = self.clone();
+
+        let mut merged = persist::locked_update(cache_file, move |current| {
+            let merged = match current {
+                Some(contents) => ours.merge_with(contents)?,
+                None => ours,
+            };
+            Ok((serde_json::to_string(&merged)?, merged))
+        })
+        .await?;
 
-        Ok(())
+        let fields = merged.fields();
+        let changed_elsewhere = fields != self.fields();
+        merged.synced = Some(fields);
+        *self = merged;
+
+        Ok(changed_elsewhere)
     }
 }
 
@@ -107,3 +290,158 @@ async fn read_file(file: &PathBuf) -> anyhow::Result<String> {
     f.read_to_string(&mut buf).await?;
     Ok(buf)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::story::StoryType;
+
+    fn story_json(id: i32) -> Value {
+        json!({
+            "completed": false,
+            "description": "",
+            "epic_id": null,
+            "id": id,
+            "iteration_id": null,
+            "name": "Story",
+            "app_url": "",
+            "workflow_id": 1,
+            "workflow_state_id": 1,
+            "story_type": "feature"
+        })
+    }
+
+    #[test]
+    fn unversioned_cache_is_migrated() {
+        let user_id = Uuid::from_u128(7);
+        let contents = json!({
+            "current_iterations": null,
+            "iterations": [],
+            "iteration_stories": [story_json(1)],
+            "epics": [],
+            "active_story": story_json(1),
+            "user_id": user_id,
+            "cache_dir": "/old"
+        });
+
+        let cache = Cache::from_json(&contents.to_string(), PathBuf::from("/new"));
+        assert_eq!(cache.version, CACHE_VERSION);
+        assert_eq!(cache.user_id, Some(user_id));
+        assert_eq!(cache.active_story.map(|s| s.id), Some(1));
+        assert_eq!(cache.cache_dir, PathBuf::from("/new"));
+    }
+
+    #[test]
+    fn baseline_stories_are_migrated() {
+        let baseline_story = json!({
+            "branches": [],
+            "completed": false,
+            "comments": [
+                {"author_id": Uuid::nil(), "deleted": false, "position": 1, "text": "First"},
+                {"author_id": Uuid::nil(), "deleted": false, "position": 2, "text": "Second"}
+            ],
+            "description": "",
+            "epic_id": null,
+            "id": 1,
+            "iteration_id": null,
+            "name": "Story",
+            "app_url": ""
+        });
+        let contents = json!({
+            "current_iterations": null,
+            "iterations": [],
+            "iteration_stories": [baseline_story],
+            "epics": [],
+            "active_story": baseline_story,
+            "user_id": null,
+            "cache_dir": "/cache"
+        });
+
+        // parses as is, nothing is left for salvage to drop
+        let mut fields = contents.as_object().unwrap().clone();
+        Cache::migrate(&mut fields);
+        assert!(serde_json::from_value::<Cache>(Value::Object(fields)).is_ok());
+
+        let cache = Cache::from_json(&contents.to_string(), PathBuf::from("/cache"));
+        let stories = cache.iteration_stories.unwrap();
+        assert_eq!(stories.len(), 1);
+        assert_eq!(stories[0].story_type, StoryType::Feature);
+        assert_eq!(
+            stories[0].comments.iter().map(|c| c.id).collect::<Vec<_>>(),
+            vec![-1, -2]
+        );
+        assert_eq!(cache.active_story.as_ref(), Some(&stories[0]));
+    }
+
+    #[test]
+    fn unparseable_fields_are_dropped_without_losing_the_rest() {
+        let user_id = Uuid::from_u128(7);
+        let contents = json!({
+            "version": CACHE_VERSION,
+            "current_iterations": null,
+            "iterations": "not a list",
+            "iteration_stories": [story_json(1), {"id": "broken"}, story_json(2)],
+            "epics": [],
+            "workflows": [],
+            "active_story": {"id": 2, "name": "Missing most fields"},
+            "user_id": user_id,
+            "cache_dir": "/cache"
+        });
+
+        let cache = Cache::from_json(&contents.to_string(), PathBuf::from("/cache"));
+        assert!(cache.iterations.is_empty());
+        assert_eq!(
+            cache.iteration_stories.unwrap().iter().map(|s| s.id).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert_eq!(cache.active_story.map(|s| s.id), Some(2));
+        assert_eq!(cache.user_id, Some(user_id));
+    }
+
+    #[test]
+    fn active_story_set_elsewhere_survives_our_write() {
+        let on_disk = json!({
+            "version": CACHE_VERSION,
+            "current_iterations": null,
+            "iterations": [],
+            "iteration_stories": null,
+            "epics": [],
+            "workflows": [],
+            "active_story": null,
+            "user_id": null,
+            "cache_dir": "/cache"
+        });
+        let mut ours = Cache::from_json(&on_disk.to_string(), PathBuf::from("/cache"));
+        ours.user_id = Some(Uuid::from_u128(7));
+
+        let mut theirs = on_disk.clone();
+        theirs["active_story"] = story_json(3);
+
+        let merged = ours.merge_with(&theirs.to_string()).unwrap();
+        assert_eq!(merged.user_id, Some(Uuid::from_u128(7)));
+        assert_eq!(merged.active_story.map(|s| s.id), Some(3));
+    }
+
+    #[test]
+    fn unparseable_file_is_overwritten_with_ours() {
+        let mut on_disk = json!({
+            "version": CACHE_VERSION,
+            "current_iterations": null,
+            "iterations": [],
+            "iteration_stories": null,
+            "epics": [],
+            "workflows": [],
+            "active_story": story_json(3),
+            "user_id": Uuid::from_u128(7),
+            "cache_dir": "/cache"
+        });
+        let ours = Cache::from_json(&on_disk.to_string(), PathBuf::from("/cache"));
+
+        let merged = ours.clone().merge_with("{ truncated").unwrap();
+        assert_eq!(merged.fields(), ours.fields());
+
+        on_disk["iterations"] = json!("not a list");
+        let merged = ours.clone().merge_with(&on_disk.to_string()).unwrap();
+        assert_eq!(merged.fields(), ours.fields());
+    }
+}
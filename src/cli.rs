@@ -1,11 +1,52 @@
+use std::path::PathBuf;
+
 use clap::{Parser, Subcommand};
 
+use crate::{
+    api::{backend::BackendSource, recording::Recording},
+    dummy,
+};
+
 #[derive(Parser)]
 #[command(name = "note")]
 #[command(about = "Note manager with shortcut integration", long_about = None)]
 pub struct Cli {
     #[command(subcommand)]
     pub command: Option<Commands>,
+
+    /// Save every API response to this directory
+    #[arg(long, global = true, value_name = "DIR", conflicts_with = "replay")]
+    pub record: Option<PathBuf>,
+
+    /// Serve API responses saved with --record instead of talking to Shortcut
+    #[arg(long, global = true, value_name = "DIR", conflicts_with = "fake")]
+    pub replay: Option<PathBuf>,
+
+    /// Run against an in-memory workspace seeded from a JSON/YAML fixture, or the built in
+    /// dummy data when no fixture is given
+    #[arg(long, global = true, value_name = "FIXTURE", num_args = 0..=1, conflicts_with = "record")]
+    pub fake: Option<Option<PathBuf>>,
+}
+
+impl Cli {
+    pub fn backend_source(&self) -> BackendSource {
+        if let Some(fixture) = &self.fake {
+            return BackendSource::Fake {
+                fixture: fixture.clone(),
+            };
+        }
+        if dummy::is_enabled() {
+            return BackendSource::Fake { fixture: None };
+        }
+
+        let recording = match (&self.record, &self.replay) {
+            (Some(dir), _) => Some(Recording::Record(dir.clone())),
+            (_, Some(dir)) => Some(Recording::Replay(dir.clone())),
+            _ => None,
+        };
+
+        BackendSource::Shortcut { recording }
+    }
 }
 
 #[derive(Subcommand)]
@@ -24,4 +65,15 @@ pub enum Commands {
 
     #[command()]
     Cat,
+
+    /// Move the active story to another workflow state, e.g. `sc state "in review"`
+    #[command(alias = "s")]
+    State { name: String },
+
+    /// Create a story in $EDITOR, in the active story's iteration and epic
+    New {
+        /// Make the new story the active story
+        #[arg(long, short)]
+        active: bool,
+    },
 }
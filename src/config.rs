@@ -6,6 +6,8 @@ use std::{
 use anyhow::Context;
 use serde::{Deserialize, Serialize};
 
+use crate::api::{API_BASE_URL, story::DEFAULT_BRANCH_NAME_TEMPLATE};
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum Mux {
@@ -22,6 +24,10 @@ pub struct Config {
     pub editor: String,
     pub repositories_directory: PathBuf,
     pub mux: Mux,
+    pub branch_name_template: String,
+    pub api_base_url: String,
+    pub groups: Vec<String>,
+    pub refresh_interval_secs: u64,
 }
 
 #[derive(Deserialize, Serialize, Clone)]
@@ -36,6 +42,18 @@ struct ConfigFile {
     repositories_directory: String,
     #[serde(default)]
     mux: Mux,
+    /// Supports `{mention}`, `{id}`, `{slug}` and `{type}` placeholders
+    #[serde(default = "default_branch_name_template")]
+    branch_name_template: String,
+    /// Lets the TUI talk to a local stand-in for Shortcut
+    #[serde(default = "default_api_base_url")]
+    api_base_url: String,
+    /// Shortcut groups (teams) by name, mention name or id, whose backlog the team view shows
+    #[serde(default)]
+    groups: Vec<String>,
+    /// How often data is refetched in the background, 0 turns it off
+    #[serde(default = "default_refresh_interval_secs")]
+    refresh_interval_secs: u64,
 }
 
 impl Default for ConfigFile {
@@ -47,6 +65,10 @@ impl Default for ConfigFile {
             editor: default_editor(),
             repositories_directory: default_repositories_directory(),
             mux: Mux::default(),
+            branch_name_template: default_branch_name_template(),
+            api_base_url: default_api_base_url(),
+            groups: Vec::new(),
+            refresh_interval_secs: default_refresh_interval_secs(),
         }
     }
 }
@@ -63,7 +85,30 @@ fn default_repositories_directory() -> String {
     "~/Repositories".to_string()
 }
 
+fn default_branch_name_template() -> String {
+    DEFAULT_BRANCH_NAME_TEMPLATE.to_string()
+}
+
+fn default_api_base_url() -> String {
+    API_BASE_URL.to_string()
+}
+
+fn default_refresh_interval_secs() -> u64 {
+    300
+}
+
 impl Config {
+    /// `None` when background refresh is turned off
+    pub fn refresh_interval(&self) -> Option<chrono::Duration> {
+        (self.refresh_interval_secs > 0)
+            .then(|| chrono::Duration::seconds(self.refresh_interval_secs as i64))
+    }
+
+    pub fn file_path() -> anyhow::Result<PathBuf> {
+        confy::get_configuration_file_path("shortcut-notes", Some("config"))
+            .context("Failed to find config file")
+    }
+
     pub fn read() -> anyhow::Result<Config> {
         let config: ConfigFile = confy::load("shortcut-notes", Some("config"))?;
 
@@ -88,6 +133,10 @@ impl Config {
             editor: config.editor,
             repositories_directory,
             mux: config.mux,
+            branch_name_template: config.branch_name_template,
+            api_base_url: config.api_base_url,
+            groups: config.groups,
+            refresh_interval_secs: config.refresh_interval_secs,
         })
     }
 
@@ -99,6 +148,10 @@ impl Config {
             editor: self.editor.clone(),
             repositories_directory: self.repositories_directory.to_str().unwrap().to_string(),
             mux: self.mux.clone(),
+            branch_name_template: self.branch_name_template.clone(),
+            api_base_url: self.api_base_url.clone(),
+            groups: self.groups.clone(),
+            refresh_interval_secs: self.refresh_interval_secs,
         };
 
         confy::store("shortcut-notes", Some("config"), config).context("Failed to write config")
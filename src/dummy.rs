@@ -1,81 +1,311 @@
-//! Dummy data for development/testing. Enable with DUMMY_DATA=1 env var.
+//! Dummy data for development/testing. Enable with DUMMY_DATA=1 env var, which runs the app
+//! against a `FakeBackend` seeded with `fixture()`.
 
 use crate::time;
 
-use crate::api::{iteration::Iteration, story::Story};
+use uuid::Uuid;
+
+use crate::api::{
+    epic::EpicSlim,
+    fake::Fixture,
+    group::Group,
+    iteration::Iteration,
+    story::{
+        Story, StoryType,
+        comment::StoryComment,
+        link::{LinkSide, LinkVerb, StoryLink},
+        task::StoryTask,
+    },
+    user::{MemberProfile, WorkspaceMember},
+    workflow::{Workflow, WorkflowState, WorkflowStateType},
+};
+
+const ITERATION_ID: i32 = 1;
 
 pub fn is_enabled() -> bool {
     std::env::var("DUMMY_DATA").is_ok_and(|val| val == "1")
 }
 
+/// Everything below as one workspace, the dummy user is `Uuid::nil()`
+pub fn fixture() -> Fixture {
+    Fixture {
+        user_id: Uuid::nil(),
+        members: members(),
+        workflows: workflows(),
+        groups: vec![group()],
+        iterations: vec![iteration()],
+        epics: epics(),
+        stories: stories(),
+    }
+}
+
 pub fn iteration() -> Iteration {
     let today = time::today();
     Iteration {
-        id: -1,
+        id: ITERATION_ID,
         name: "Sprint 42".to_string(),
         description: "The sprint where we answer everything".to_string(),
         start_date: today - chrono::Duration::days(7),
         end_date: today + chrono::Duration::days(7),
         app_url: "https://app.shortcut.com/example/iteration/1".to_string(),
+        updated_at: None,
+    }
+}
+
+pub fn workflows() -> Vec<Workflow> {
+    let state = |id: i32, name: &str, state_type: WorkflowStateType, position: i32| WorkflowState {
+        id,
+        name: name.to_string(),
+        state_type,
+        position,
+    };
+
+    vec![Workflow {
+        id: 1,
+        name: "Engineering".to_string(),
+        states: vec![
+            state(10, "Ready for Development", WorkflowStateType::Unstarted, 0),
+            state(11, "In Development", WorkflowStateType::Started, 1),
+            state(12, "Ready for Review", WorkflowStateType::Started, 2),
+            state(13, "Completed", WorkflowStateType::Done, 3),
+        ],
+    }]
+}
+
+const GROUP_ID: Uuid = Uuid::from_u128(100);
+
+pub fn group() -> Group {
+    Group {
+        id: GROUP_ID,
+        name: "Platform".to_string(),
+        mention_name: "platform".to_string(),
+        archived: false,
     }
 }
 
+const TEAMMATE_ID: Uuid = Uuid::from_u128(1);
+
+pub fn members() -> Vec<WorkspaceMember> {
+    let member = |id: Uuid, name: &str, mention_name: &str| WorkspaceMember {
+        id,
+        profile: MemberProfile {
+            name: name.to_string(),
+            mention_name: mention_name.to_string(),
+        },
+    };
+
+    vec![
+        member(Uuid::nil(), "Dummy User", "dummy"),
+        member(TEAMMATE_ID, "Alex Teammate", "alex"),
+    ]
+}
+
+pub fn epics() -> Vec<EpicSlim> {
+    let epic = |id: i32, name: &str| EpicSlim {
+        id,
+        name: name.to_string(),
+        app_url: format!("https://app.shortcut.com/example/epic/{}", id),
+        owner_ids: vec![Uuid::nil()],
+        created_at: chrono::Utc::now() - chrono::Duration::days(30),
+    };
+
+    vec![epic(10, "Accounts"), epic(20, "Performance")]
+}
+
+/// sc-104 blocks sc-101, as listed on either story
+fn blocked_by_pooling(side: LinkSide) -> StoryLink {
+    StoryLink {
+        id: 950,
+        subject_id: 104,
+        object_id: 101,
+        verb: LinkVerb::Blocks,
+        side,
+    }
+}
+
+fn tasks() -> Vec<StoryTask> {
+    ["Google OAuth2 client", "Session cookie", "Logout endpoint"]
+        .into_iter()
+        .enumerate()
+        .map(|(i, description)| StoryTask {
+            id: 900 + i as i32,
+            description: description.to_string(),
+            complete: i == 0,
+            position: i as i32,
+        })
+        .collect()
+}
+
+fn comments() -> Vec<StoryComment> {
+    let now = chrono::Utc::now();
+    let comment = |id: i32, author_id: Uuid, text: &str, parent_id: Option<i32>| StoryComment {
+        id,
+        author_id,
+        deleted: false,
+        position: id,
+        text: Some(text.to_string()),
+        parent_id,
+        created_at: now - chrono::Duration::hours(10 - id as i64),
+    };
+
+    vec![
+        comment(1, TEAMMATE_ID, "Should we support GitHub login as well?", None),
+        comment(2, Uuid::nil(), "Not for this story, I'll make a follow-up.", Some(1)),
+        comment(3, TEAMMATE_ID, "PR is up for review.", None),
+    ]
+}
+
 pub fn stories() -> Vec<Story> {
     vec![
         Story {
             id: 101,
             name: "Implement user authentication".to_string(),
+            owner_ids: vec![Uuid::nil()],
+            group_id: Some(GROUP_ID),
+            updated_at: None,
+            estimate: Some(5),
+            labels: vec![],
+            deadline: None,
             description: "Add login/logout functionality with OAuth2.\n\nAcceptance criteria:\n- Users can log in with Google\n- Session persists across browser refresh\n- Logout clears all tokens".to_string(),
             completed: false,
             branches: vec![],
-            comments: vec![],
+            comments: comments(),
+            tasks: tasks(),
+            story_links: vec![blocked_by_pooling(LinkSide::Object)],
+            blocked: true,
+            blocker: false,
+            completed_at: None,
             epic_id: Some(10),
-            iteration_id: Some(1),
+            iteration_id: Some(ITERATION_ID),
             app_url: "https://app.shortcut.com/example/story/101".to_string(),
+            workflow_id: 1,
+            workflow_state_id: 11,
+            story_type: StoryType::Feature,
         },
         Story {
             id: 102,
             name: "Fix pagination bug on search results".to_string(),
+            owner_ids: vec![Uuid::nil()],
+            group_id: Some(GROUP_ID),
+            updated_at: None,
+            estimate: Some(2),
+            labels: vec![],
+            deadline: None,
             description: "When there are more than 100 results, the pagination breaks and shows duplicate items on page 2.".to_string(),
             completed: false,
             branches: vec![],
             comments: vec![],
+            tasks: vec![],
+            story_links: vec![],
+            blocked: false,
+            blocker: false,
+            completed_at: None,
             epic_id: None,
-            iteration_id: Some(1),
+            iteration_id: Some(ITERATION_ID),
             app_url: "https://app.shortcut.com/example/story/102".to_string(),
+            workflow_id: 1,
+            workflow_state_id: 10,
+            story_type: StoryType::Bug,
         },
         Story {
             id: 103,
             name: "Add dark mode support".to_string(),
+            owner_ids: vec![Uuid::nil()],
+            group_id: Some(GROUP_ID),
+            updated_at: None,
+            estimate: Some(3),
+            labels: vec![],
+            deadline: None,
             description: "Implement system-aware dark mode with manual toggle.\n\nDesign specs in Figma.".to_string(),
             completed: false,
             branches: vec![],
             comments: vec![],
+            tasks: vec![],
+            story_links: vec![],
+            blocked: false,
+            blocker: false,
+            completed_at: None,
             epic_id: Some(10),
-            iteration_id: Some(1),
+            iteration_id: Some(ITERATION_ID),
             app_url: "https://app.shortcut.com/example/story/103".to_string(),
+            workflow_id: 1,
+            workflow_state_id: 12,
+            story_type: StoryType::Feature,
         },
         Story {
             id: 104,
             name: "Refactor database connection pooling".to_string(),
+            owner_ids: vec![Uuid::nil()],
+            group_id: Some(GROUP_ID),
+            updated_at: None,
+            estimate: Some(8),
+            labels: vec![],
+            deadline: None,
             description: "Current implementation creates new connections for each request. Switch to connection pooling with configurable limits.\n\nBenchmark before/after.".to_string(),
             completed: false,
             branches: vec![],
             comments: vec![],
+            tasks: vec![],
+            story_links: vec![blocked_by_pooling(LinkSide::Subject)],
+            blocked: false,
+            blocker: true,
+            completed_at: None,
             epic_id: Some(20),
-            iteration_id: Some(1),
+            iteration_id: Some(ITERATION_ID),
             app_url: "https://app.shortcut.com/example/story/104".to_string(),
+            workflow_id: 1,
+            workflow_state_id: 10,
+            story_type: StoryType::Chore,
         },
         Story {
             id: 105,
             name: "Write API documentation".to_string(),
+            owner_ids: vec![Uuid::nil()],
+            group_id: Some(GROUP_ID),
+            updated_at: None,
+            estimate: Some(1),
+            labels: vec![],
+            deadline: None,
             description: "Document all public endpoints with examples.".to_string(),
             completed: false,
             branches: vec![],
             comments: vec![],
+            tasks: vec![],
+            story_links: vec![],
+            blocked: false,
+            blocker: false,
+            completed_at: None,
             epic_id: None,
-            iteration_id: Some(1),
+            iteration_id: Some(ITERATION_ID),
             app_url: "https://app.shortcut.com/example/story/105".to_string(),
+            workflow_id: 1,
+            workflow_state_id: 11,
+            story_type: StoryType::Feature,
+        },
+        Story {
+            id: 106,
+            name: "Upgrade CI runners".to_string(),
+            owner_ids: vec![TEAMMATE_ID],
+            group_id: Some(GROUP_ID),
+            updated_at: None,
+            estimate: None,
+            labels: vec![],
+            deadline: None,
+            description: "Move CI to the new runner images.".to_string(),
+            completed: false,
+            branches: vec![],
+            comments: vec![],
+            tasks: vec![],
+            story_links: vec![],
+            blocked: false,
+            blocker: false,
+            completed_at: None,
+            epic_id: Some(20),
+            iteration_id: Some(ITERATION_ID),
+            app_url: "https://app.shortcut.com/example/story/106".to_string(),
+            workflow_id: 1,
+            workflow_state_id: 11,
+            story_type: StoryType::Chore,
         },
     ]
 }
@@ -13,6 +13,7 @@ pub enum Key {
     ViewNext,
     ViewPrev,
     DailyNote,
+    Refresh,
     // Story list
     Description,     // Space
     IterationNote,   // i
@@ -22,6 +23,8 @@ pub enum Key {
     Tmux,            // t
     SelectStory,     // a
     ToggleFinished,  // f
+    ChangeState,     // s
+    ToggleTeam,      // T
 }
 
 impl Key {
@@ -35,6 +38,9 @@ impl Key {
             KeyCode::Char('?') => Some(Key::HelpPanel),
             KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Some(Key::Quit),
             KeyCode::Char('d') => Some(Key::DailyNote),
+            KeyCode::Char('r') if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                Some(Key::Refresh)
+            }
             KeyCode::Char(' ') => Some(Key::Description),
             KeyCode::Char('i') => Some(Key::IterationNote),
             KeyCode::Char('n') => Some(Key::OpenNote),
@@ -43,6 +49,8 @@ impl Key {
             KeyCode::Char('t') => Some(Key::Tmux),
             KeyCode::Char('a') => Some(Key::SelectStory),
             KeyCode::Char('f') => Some(Key::ToggleFinished),
+            KeyCode::Char('s') => Some(Key::ChangeState),
+            KeyCode::Char('T') => Some(Key::ToggleTeam),
             _ => None,
         }
     }
@@ -56,6 +64,7 @@ impl Key {
             Key::ViewNext => "Next view",
             Key::ViewPrev => "Previous view",
             Key::DailyNote => "Open daily note",
+            Key::Refresh => "Refresh from Shortcut",
             Key::Description => "Show story description",
             Key::IterationNote => "Open iteration note",
             Key::OpenNote => "Open story note",
@@ -64,6 +73,8 @@ impl Key {
             Key::Tmux => "Open tmux session",
             Key::SelectStory => "Select as active story",
             Key::ToggleFinished => "Toggle show finished",
+            Key::ChangeState => "Change story state",
+            Key::ToggleTeam => "Toggle team stories",
         }
     }
 }
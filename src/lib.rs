@@ -1,4 +1,4 @@
-use std::{fs, process::Command};
+use std::{fs, process::Command, sync::Arc};
 
 use anyhow::Context;
 use ratatui::DefaultTerminal;
@@ -6,21 +6,28 @@ use uuid::Uuid;
 
 use crate::{
     api::{
+        ApiClient,
+        backend::{Backend, BackendSource},
+        error::ApiResult,
+        fake::{FakeBackend, Fixture},
         story::{Story, get_story_associated_iteration},
-        user::get_user_id_from_api,
+        workflow::{WorkflowStateType, get_workflow},
     },
     app::{
         App,
-        cmd::{open_mux_session, open_note_in_editor},
+        cmd::{edit_in_tempfile, open_mux_session, open_note_in_editor},
     },
     cache::Cache,
     cli::Commands,
     config::Config,
     note::Note,
+    story_edit::NewStoryFields,
 };
 
+pub mod activity;
 pub mod api;
 pub mod app;
+pub mod burndown;
 pub mod cache;
 pub mod cli;
 pub mod config;
@@ -29,7 +36,11 @@ pub mod dummy;
 pub mod error;
 pub mod keybindings;
 pub mod macros;
+pub mod merge;
 pub mod note;
+pub mod outbox;
+pub mod persist;
+pub mod story_edit;
 pub mod text_utils;
 pub mod time;
 pub mod tmux;
@@ -46,18 +57,49 @@ macro_rules! no_active_story {
     };
 }
 
-pub async fn get_user_id(saved_user_id: Option<Uuid>, api_token: &str) -> anyhow::Result<Uuid> {
+pub async fn get_user_id(saved_user_id: Option<Uuid>, api_client: &ApiClient) -> ApiResult<Uuid> {
     let id = if let Some(id) = saved_user_id {
         id
     } else {
-        get_user_id_from_api(api_token).await?
+        api_client.get_user_id_from_api().await?
     };
 
     Ok(id)
 }
 
-pub async fn run(terminal: &mut DefaultTerminal) -> anyhow::Result<()> {
-    let mut app = App::init().await?;
+/// Builds the backend described by `source`. API errors are kept as `ApiError` so callers can
+/// downcast them.
+pub async fn connect(
+    source: &BackendSource,
+    config: &Config,
+    saved_user_id: Option<Uuid>,
+) -> anyhow::Result<Backend> {
+    match source {
+        BackendSource::Fake { fixture } => {
+            let fixture = match fixture {
+                Some(path) => Fixture::read(path)?,
+                None => dummy::fixture(),
+            };
+            Ok(Arc::new(FakeBackend::new(fixture)))
+        }
+        BackendSource::Shortcut { recording } => {
+            let mut api_client = ApiClient::new(
+                config.api_token.clone(),
+                config.api_base_url.clone(),
+                Uuid::nil(),
+            )
+            .with_recording(recording.clone());
+            api_client.user_id = get_user_id(saved_user_id, &api_client).await?;
+            Ok(Arc::new(api_client))
+        }
+    }
+}
+
+pub async fn run(
+    terminal: &mut DefaultTerminal,
+    backend_source: BackendSource,
+) -> anyhow::Result<()> {
+    let mut app = App::init(backend_source).await?;
     app.main_loop(terminal).await?;
     app.config.write()?;
 
@@ -66,8 +108,9 @@ pub async fn run(terminal: &mut DefaultTerminal) -> anyhow::Result<()> {
 
 pub async fn handle_command(
     command: Commands,
-    cache: Cache,
+    mut cache: Cache,
     config: &Config,
+    backend_source: &BackendSource,
 ) -> anyhow::Result<()> {
     match command {
         Commands::Note => {
@@ -104,7 +147,7 @@ pub async fn handle_command(
         }
 
         Commands::ClearCache => {
-            let cache_file = Cache::get_cache_file(config.cache_dir.clone());
+            let cache_file = Cache::get_cache_file(cache.cache_dir.clone());
             fs::remove_file(cache_file)?;
             Ok(())
         }
@@ -146,5 +189,66 @@ pub async fn handle_command(
                 no_active_story!();
             }
         }
+
+        Commands::New { active } => {
+            let backend = connect(backend_source, config, cache.user_id).await?;
+
+            let iteration_id = cache
+                .active_story
+                .as_ref()
+                .and_then(|s| s.iteration_id)
+                .or_else(|| cache.current_iterations.iter().flatten().next().map(|it| it.id));
+            let epic_id = cache.active_story.as_ref().and_then(|s| s.epic_id);
+            let mut template = NewStoryFields::new(iteration_id, epic_id);
+            template.set_active = active;
+
+            let Some(new) = story_edit::edit_until_valid(
+                template.to_file()?,
+                |text| edit_in_tempfile(&config.editor, text),
+                NewStoryFields::parse,
+            )?
+            else {
+                println!("Nothing changed, no story created");
+                return Ok(());
+            };
+
+            let story = backend.create_story(new.to_new_story(backend.user_id())).await?;
+            println!("Created sc-{} {}", story.id, story.app_url);
+
+            if let Some(stories) = &mut cache.iteration_stories {
+                stories.push(story.clone());
+            }
+            if new.set_active {
+                cache.active_story = Some(story);
+            }
+            cache.write().await?;
+
+            Ok(())
+        }
+
+        Commands::State { name } => {
+            if let Some(story) = &mut cache.active_story {
+                let backend = connect(backend_source, config, cache.user_id).await?;
+
+                let workflows = backend.get_workflows().await?;
+                let workflow = get_workflow(story.workflow_id, &workflows)
+                    .with_context(|| format!("No workflow found for story {}", &story.name))?;
+                let state = workflow.find_state_by_name(&name).with_context(|| {
+                    format!("No state matching '{}' in workflow {}", name, workflow.name)
+                })?;
+
+                backend.update_story_state(story.id, state.id).await?;
+                println!("Moved sc-{} to {}", story.id, state.name);
+
+                story.workflow_state_id = state.id;
+                story.completed = state.state_type == WorkflowStateType::Done;
+                cache.workflows = workflows;
+                cache.write().await?;
+
+                Ok(())
+            } else {
+                no_active_story!();
+            }
+        }
     }
 }
@@ -8,17 +8,18 @@ async fn main() -> anyhow::Result<()> {
     check_worktree_dependencies()?;
 
     let args = Cli::parse();
+    let backend_source = args.backend_source();
     if let Some(cmd) = args.command {
         let config = Config::read()?;
-        let cache = Cache::read(config.cache_dir.clone()).await;
-        shortcut_notes::handle_command(cmd, cache, &config).await?;
+        let cache = Cache::read(backend_source.cache_dir(&config.cache_dir)).await;
+        shortcut_notes::handle_command(cmd, cache, &config, &backend_source).await?;
         config.write()?;
         return Ok(());
     }
 
     // need to do the ratatui stuff manually since we are using await in the main
     let mut terminal = ratatui::init();
-    let result = shortcut_notes::run(&mut terminal).await;
+    let result = shortcut_notes::run(&mut terminal, backend_source).await;
     ratatui::restore();
 
     result?;
@@ -0,0 +1,124 @@
+/// Marks the start of a conflict, our side follows
+pub const CONFLICT_START: &str = "<<<<<<< yours";
+pub const CONFLICT_SEPARATOR: &str = "=======";
+/// Marks the end of a conflict, after Shortcut's side
+pub const CONFLICT_END: &str = ">>>>>>> shortcut";
+
+pub struct Merged {
+    pub text: String,
+    pub conflicts: usize,
+}
+
+/// Line based three-way merge of `ours` and `theirs`, which both started out as `base`.
+/// Changes to different lines are combined, changes to the same lines are kept between git
+/// style conflict markers.
+pub fn merge(base: &str, ours: &str, theirs: &str) -> Merged {
+    let base: Vec<_> = base.split('\n').collect();
+    let ours: Vec<_> = ours.split('\n').collect();
+    let theirs: Vec<_> = theirs.split('\n').collect();
+
+    let ours_matches = matching_lines(&base, &ours);
+    let theirs_matches = matching_lines(&base, &theirs);
+
+    let mut lines = Vec::new();
+    let mut conflicts = 0;
+    let (mut b, mut o, mut t) = (0, 0, 0);
+
+    loop {
+        // the next base line both sides kept, everything before it is a changed chunk
+        let sync = (b..base.len()).find_map(|i| Some((i, ours_matches[i]?, theirs_matches[i]?)));
+        let (base_end, ours_end, theirs_end) =
+            sync.unwrap_or((base.len(), ours.len(), theirs.len()));
+
+        let (base_chunk, ours_chunk, theirs_chunk) =
+            (&base[b..base_end], &ours[o..ours_end], &theirs[t..theirs_end]);
+
+        if ours_chunk == base_chunk || ours_chunk == theirs_chunk {
+            lines.extend_from_slice(theirs_chunk);
+        } else if theirs_chunk == base_chunk {
+            lines.extend_from_slice(ours_chunk);
+        } else {
+            conflicts += 1;
+            lines.push(CONFLICT_START);
+            lines.extend_from_slice(ours_chunk);
+            lines.push(CONFLICT_SEPARATOR);
+            lines.extend_from_slice(theirs_chunk);
+            lines.push(CONFLICT_END);
+        }
+
+        let Some((base_end, ours_end, theirs_end)) = sync else {
+            break;
+        };
+        lines.push(base[base_end]);
+        (b, o, t) = (base_end + 1, ours_end + 1, theirs_end + 1);
+    }
+
+    Merged {
+        text: lines.join("\n"),
+        conflicts,
+    }
+}
+
+pub fn has_conflict_markers(text: &str) -> bool {
+    text.lines()
+        .any(|line| line == CONFLICT_START || line == CONFLICT_END)
+}
+
+/// For each line of `a`, the line of `b` it lines up with in a longest common subsequence
+fn matching_lines(a: &[&str], b: &[&str]) -> Vec<Option<usize>> {
+    // lengths[i][j] is the LCS length of a[i..] and b[j..]
+    let mut lengths = vec![vec![0u32; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lengths[i][j] = if a[i] == b[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut matches = vec![None; a.len()];
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            matches[i] = Some(j);
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edits_to_different_lines_are_combined() {
+        let base = "# Story\n\nSteps\n- one\n- two\n";
+        let ours = "# Story\n\nSteps\n- one\n- two\n- three\n";
+        let theirs = "# Story updated\n\nSteps\n- one\n- two\n";
+
+        let merged = merge(base, ours, theirs);
+        assert_eq!(merged.conflicts, 0);
+        assert_eq!(merged.text, "# Story updated\n\nSteps\n- one\n- two\n- three\n");
+    }
+
+    #[test]
+    fn edits_to_the_same_line_conflict() {
+        let merged = merge("a\nb\nc", "a\nmine\nc", "a\ntheirs\nc");
+
+        assert_eq!(merged.conflicts, 1);
+        assert_eq!(
+            merged.text,
+            "a\n<<<<<<< yours\nmine\n=======\ntheirs\n>>>>>>> shortcut\nc"
+        );
+        assert!(has_conflict_markers(&merged.text));
+    }
+}
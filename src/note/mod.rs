@@ -7,6 +7,7 @@ use std::{
 use crate::note::frontmatter::Frontmatter;
 
 pub mod frontmatter;
+pub mod tasks;
 
 pub struct Note {
     pub frontmatter: Frontmatter,
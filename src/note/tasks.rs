@@ -0,0 +1,440 @@
+use std::{collections::HashMap, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api::{
+        backend::Backend,
+        story::{
+            Story,
+            task::{StoryTask, ordered_tasks},
+        },
+    },
+    note::Note,
+    outbox::Mutation,
+    persist,
+};
+
+pub const HEADING: &str = "## Tasks";
+const BLOCK_START: &str = "<!-- shortcut tasks: synced with the story, keep the task ids -->";
+const BLOCK_END: &str = "<!-- end shortcut tasks -->";
+const ID_PREFIX: &str = "<!-- task:";
+
+/// A `- [ ]` item in a note's task block. Items added in the note have no id until Shortcut
+/// has created them.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct NoteTask {
+    pub id: Option<i32>,
+    pub description: String,
+    pub complete: bool,
+}
+
+impl NoteTask {
+    fn from_task(task: &StoryTask) -> Self {
+        Self {
+            id: Some(task.id),
+            description: task.description.clone(),
+            complete: task.complete,
+        }
+    }
+
+    fn to_line(&self) -> String {
+        let checkbox = if self.complete { "[x]" } else { "[ ]" };
+        match self.id {
+            Some(id) => format!("- {} {} {}{} -->", checkbox, self.description, ID_PREFIX, id),
+            None => format!("- {} {}", checkbox, self.description),
+        }
+    }
+
+    fn parse_line(line: &str) -> Option<Self> {
+        let item = line.trim().strip_prefix("- ").or(line.trim().strip_prefix("* "))?;
+        let (complete, rest) = if let Some(rest) = item.strip_prefix("[ ]") {
+            (false, rest)
+        } else {
+            (true, item.strip_prefix("[x]").or(item.strip_prefix("[X]"))?)
+        };
+
+        let (description, id) = match rest.rsplit_once(ID_PREFIX) {
+            Some((description, id)) => {
+                let id = id.trim().strip_suffix("-->")?.trim().parse().ok();
+                (description, id)
+            }
+            None => (rest, None),
+        };
+
+        let description = description.trim();
+        (!description.is_empty()).then(|| Self {
+            id,
+            description: description.to_string(),
+            complete,
+        })
+    }
+
+    fn matches(&self, task: &StoryTask) -> bool {
+        self.description == task.description && self.complete == task.complete
+    }
+}
+
+/// The items in the note's task block, `None` if it doesn't have one
+pub fn read_block(note: &str) -> Option<Vec<NoteTask>> {
+    let lines: Vec<_> = note.lines().collect();
+    let (start, end) = find_block(&lines)?;
+    Some(lines[start + 1..end].iter().filter_map(|l| NoteTask::parse_line(l)).collect())
+}
+
+/// Replaces the items in the note's task block, adding the block at the end if it has none
+pub fn write_block(note: &str, tasks: &[NoteTask]) -> String {
+    let mut block = vec![BLOCK_START.to_string()];
+    block.extend(tasks.iter().map(NoteTask::to_line));
+    block.push(BLOCK_END.to_string());
+
+    let lines: Vec<_> = note.lines().collect();
+    match find_block(&lines) {
+        Some((start, end)) => {
+            let mut out: Vec<_> = lines[..start].iter().map(|l| l.to_string()).collect();
+            out.extend(block);
+            out.extend(lines[end + 1..].iter().map(|l| l.to_string()));
+            out.join("\n") + "\n"
+        }
+        None => format!("{}\n\n{}\n{}\n", note.trim_end(), HEADING, block.join("\n")),
+    }
+}
+
+fn find_block(lines: &[&str]) -> Option<(usize, usize)> {
+    let start = lines.iter().position(|l| l.trim() == BLOCK_START)?;
+    let end = start + lines[start..].iter().position(|l| l.trim() == BLOCK_END)?;
+    Some((start, end))
+}
+
+#[derive(Default, Debug)]
+pub struct TaskSync {
+    /// What the block should contain now
+    pub tasks: Vec<NoteTask>,
+    /// Changes made in the note to send to Shortcut
+    pub mutations: Vec<Mutation>,
+    /// Note changes that lost out to changes made in Shortcut
+    pub conflicts: Vec<String>,
+}
+
+impl TaskSync {
+    /// What Shortcut has once the mutations are sent, to sync against next time. Tasks being
+    /// created start out incomplete.
+    pub fn base(&self) -> Vec<NoteTask> {
+        self.tasks
+            .iter()
+            .map(|t| NoteTask {
+                complete: t.complete && t.id.is_some(),
+                ..t.clone()
+            })
+            .collect()
+    }
+}
+
+/// Three-way sync of the note's task block (`local`) with the story's tasks in Shortcut
+/// (`remote`), both of which started out as `base`. Changes on one side are taken over by the
+/// other. When both sides changed the same task, Shortcut's version wins and the lost change
+/// is reported as a conflict.
+pub fn sync(base: &[NoteTask], local: &[NoteTask], remote: &[StoryTask]) -> TaskSync {
+    let remote = ordered_tasks(remote);
+    let find_remote = |id| remote.iter().find(|t| t.id == id).copied();
+
+    // tasks created from the note come back from Shortcut with an id, pick it up by text
+    let known = |id| base.iter().chain(local).any(|t| t.id == Some(id));
+    let mut unmatched: Vec<_> = remote.iter().filter(|t| !known(t.id)).copied().collect();
+    let mut created = HashMap::new();
+    for task in base.iter().chain(local).filter(|t| t.id.is_none()) {
+        if created.contains_key(&task.description) {
+            continue;
+        }
+        if let Some(i) = unmatched.iter().position(|t| t.description == task.description) {
+            created.insert(task.description.clone(), unmatched.remove(i).id);
+        }
+    }
+    let with_id = |task: &NoteTask| NoteTask {
+        id: task.id.or(created.get(&task.description).copied()),
+        ..task.clone()
+    };
+    let base: Vec<_> = base.iter().map(with_id).collect();
+    let local: Vec<_> = local.iter().map(with_id).collect();
+
+    let mut sync = TaskSync::default();
+
+    for task in &local {
+        let Some(id) = task.id else {
+            // only sent once, it stays in the base until Shortcut has created it
+            if !base.iter().any(|b| b.id.is_none() && b.description == task.description) {
+                sync.mutations.push(Mutation::CreateTask {
+                    description: task.description.clone(),
+                });
+            }
+            sync.tasks.push(task.clone());
+            continue;
+        };
+
+        let base_task = base.iter().find(|t| t.id == Some(id));
+        let Some(remote_task) = find_remote(id) else {
+            if base_task.is_some_and(|b| b != task) {
+                sync.conflicts.push(format!(
+                    "\"{}\" was deleted in Shortcut, your change to it was dropped",
+                    task.description
+                ));
+            }
+            continue;
+        };
+        // without a base, anything that differs was changed in the note
+        let base_task = base_task.cloned().unwrap_or_else(|| NoteTask::from_task(remote_task));
+
+        // two-sided changes to a checkbox always end up agreeing, only the text can conflict
+        let complete = if task.complete != base_task.complete {
+            task.complete
+        } else {
+            remote_task.complete
+        };
+        if complete != remote_task.complete {
+            sync.mutations.push(Mutation::SetTaskComplete {
+                task_id: id,
+                complete,
+            });
+        }
+
+        let local_renamed = task.description != base_task.description;
+        let remote_renamed = remote_task.description != base_task.description;
+        let description = match (local_renamed, remote_renamed) {
+            (true, false) => {
+                sync.mutations.push(Mutation::RenameTask {
+                    task_id: id,
+                    description: task.description.clone(),
+                    original: remote_task.description.clone(),
+                });
+                task.description.clone()
+            }
+            (true, true) if task.description != remote_task.description => {
+                sync.conflicts.push(format!(
+                    "\"{}\" was renamed to \"{}\" in Shortcut, your rename was dropped",
+                    base_task.description, remote_task.description
+                ));
+                remote_task.description.clone()
+            }
+            _ => remote_task.description.clone(),
+        };
+
+        sync.tasks.push(NoteTask {
+            id: Some(id),
+            description,
+            complete,
+        });
+    }
+
+    // removed from the note
+    for base_task in &base {
+        let Some(id) = base_task.id else {
+            continue;
+        };
+        if local.iter().any(|t| t.id == Some(id)) {
+            continue;
+        }
+        let Some(remote_task) = find_remote(id) else {
+            continue;
+        };
+
+        if base_task.matches(remote_task) {
+            sync.mutations.push(Mutation::DeleteTask { task_id: id });
+        } else {
+            sync.conflicts.push(format!(
+                "\"{}\" changed in Shortcut, so it wasn't deleted",
+                remote_task.description
+            ));
+            sync.tasks.push(NoteTask::from_task(remote_task));
+        }
+    }
+
+    // added in Shortcut
+    for task in remote {
+        let listed = base.iter().chain(&local).any(|t| t.id == Some(task.id));
+        if !listed {
+            sync.tasks.push(NoteTask::from_task(task));
+        }
+    }
+
+    sync
+}
+
+/// Syncs the task block of the story's note, if it has a note. The block is added when the
+/// story has tasks and the note doesn't have one yet. When the block was edited since the last
+/// sync the story is fetched again first, so the edits are compared against what Shortcut has
+/// now rather than the last refresh.
+///
+/// Returns the mutations to send and any conflicts, the note is already updated. Store
+/// `TaskSync::base` with `save_base` once Shortcut has the mutations.
+pub async fn sync_story_note(
+    backend: &Backend,
+    notes_dir: &Path,
+    cache_dir: &Path,
+    story: &Story,
+) -> anyhow::Result<Option<TaskSync>> {
+    let path = Note::new(&notes_dir, story.id, story.name.clone(), String::new(), None).path;
+    let Ok(note) = tokio::fs::read_to_string(&path).await else {
+        return Ok(None);
+    };
+
+    let base_path = cache_dir.join("note_tasks.json");
+    let bases: HashMap<i32, Vec<NoteTask>> = match tokio::fs::read_to_string(&base_path).await {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
+        Err(_) => HashMap::new(),
+    };
+    let base = bases.get(&story.id).cloned();
+
+    let local = read_block(&note);
+    if local.is_none() && story.tasks.is_empty() {
+        return Ok(None);
+    }
+
+    // offline the last refresh has to do, the mutations get queued anyway
+    let edited = local.is_some() && local != base;
+    let fresh = match edited {
+        true => backend.get_story(story.id).await.ok(),
+        false => None,
+    };
+    let remote = fresh.as_ref().unwrap_or(story);
+
+    let base = base.unwrap_or_default();
+    let local = local.unwrap_or_else(|| base.clone());
+    let sync = sync(&base, &local, &remote.tasks);
+
+    let updated = write_block(&note, &sync.tasks);
+    if updated != note {
+        tokio::fs::write(&path, updated).await?;
+    }
+
+    Ok(Some(sync))
+}
+
+/// Stores what the story's note block was last synced to, the base for the next sync
+pub async fn save_base(
+    cache_dir: &Path,
+    story_id: i32,
+    base: Vec<NoteTask>,
+) -> anyhow::Result<()> {
+    persist::locked_update(cache_dir.join("note_tasks.json"), move |current| {
+        let mut bases: HashMap<i32, Vec<NoteTask>> =
+            current.and_then(|c| serde_json::from_str(c).ok()).unwrap_or_default();
+        bases.insert(story_id, base);
+        Ok((serde_json::to_string_pretty(&bases)?, ()))
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: i32, description: &str, complete: bool) -> StoryTask {
+        StoryTask {
+            id,
+            description: description.to_string(),
+            complete,
+            position: id,
+        }
+    }
+
+    fn note_task(id: Option<i32>, description: &str, complete: bool) -> NoteTask {
+        NoteTask {
+            id,
+            description: description.to_string(),
+            complete,
+        }
+    }
+
+    #[test]
+    fn block_round_trips_through_a_note() {
+        let tasks = vec![
+            note_task(Some(1), "Write tests", true),
+            note_task(None, "Update docs", false),
+        ];
+        let note = write_block("---\nstory_id: sc-1\n---\nSome notes\n", &tasks);
+        assert_eq!(read_block(&note), Some(tasks.clone()));
+
+        let edited = note.replace("- [ ] Update docs", "- [x] Update docs") + "More notes\n";
+        let rewritten = write_block(&edited, &tasks);
+        assert!(rewritten.ends_with("<!-- end shortcut tasks -->\nMore notes\n"));
+        assert_eq!(rewritten.matches(HEADING).count(), 1);
+    }
+
+    #[test]
+    fn changes_on_each_side_are_combined() {
+        let base = vec![
+            note_task(Some(1), "Write tests", false),
+            note_task(Some(2), "Update docs", false),
+            note_task(Some(3), "Release", false),
+        ];
+        let local = vec![
+            note_task(Some(1), "Write tests", true),
+            note_task(Some(2), "Update docs", false),
+            note_task(None, "Announce", false),
+        ];
+        let remote = vec![
+            task(1, "Write tests", false),
+            task(2, "Update the docs", false),
+            task(3, "Release", false),
+            task(4, "Tag version", false),
+        ];
+
+        let sync = sync(&base, &local, &remote);
+        assert_eq!(
+            sync.mutations,
+            vec![
+                Mutation::SetTaskComplete {
+                    task_id: 1,
+                    complete: true
+                },
+                Mutation::CreateTask {
+                    description: "Announce".to_string()
+                },
+                Mutation::DeleteTask { task_id: 3 },
+            ]
+        );
+        assert_eq!(
+            sync.tasks,
+            vec![
+                note_task(Some(1), "Write tests", true),
+                note_task(Some(2), "Update the docs", false),
+                note_task(None, "Announce", false),
+                note_task(Some(4), "Tag version", false),
+            ]
+        );
+        assert!(sync.conflicts.is_empty());
+    }
+
+    #[test]
+    fn created_tasks_pick_up_their_id_and_are_sent_once() {
+        let local = vec![note_task(None, "Announce", true)];
+        let first = sync(&[], &local, &[]);
+        assert_eq!(first.mutations.len(), 1);
+
+        // still waiting for Shortcut, nothing is sent again
+        let waiting = sync(&first.base(), &local, &[]);
+        assert!(waiting.mutations.is_empty());
+
+        let created = sync(&first.base(), &local, &[task(7, "Announce", false)]);
+        assert_eq!(created.tasks, vec![note_task(Some(7), "Announce", true)]);
+        assert_eq!(
+            created.mutations,
+            vec![Mutation::SetTaskComplete {
+                task_id: 7,
+                complete: true
+            }]
+        );
+    }
+
+    #[test]
+    fn shortcut_wins_conflicting_renames() {
+        let base = vec![note_task(Some(1), "Write tests", false)];
+        let local = vec![note_task(Some(1), "Write unit tests", false)];
+        let remote = vec![task(1, "Write integration tests", false)];
+
+        let sync = sync(&base, &local, &remote);
+        assert!(sync.mutations.is_empty());
+        assert_eq!(sync.tasks, vec![note_task(Some(1), "Write integration tests", false)]);
+        assert_eq!(sync.conflicts.len(), 1);
+    }
+}
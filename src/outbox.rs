@@ -0,0 +1,334 @@
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    api::{
+        backend::Backend,
+        error::{ApiError, ApiResult},
+        story::{Story, StoryUpdate, comment::StoryComment, task::{StoryTask, TaskUpdate}},
+    },
+    persist,
+};
+
+/// A change to a story, along with what it looked like when the change was made so replaying
+/// it later can tell whether someone else changed it in the meantime
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Mutation {
+    UpdateDescription {
+        description: String,
+        original: String,
+    },
+    UpdateState {
+        workflow_state_id: i32,
+        original: i32,
+    },
+    CreateComment {
+        text: String,
+        parent_id: Option<i32>,
+    },
+    UpdateComment {
+        comment_id: i32,
+        text: String,
+        original: String,
+    },
+    /// `original` has the values the changed fields had before, see `StoryUpdate::original_values`
+    UpdateStory {
+        update: StoryUpdate,
+        original: StoryUpdate,
+    },
+    CreateTask {
+        description: String,
+    },
+    /// Ticks or unticks a task, conflicts if someone else already changed it
+    SetTaskComplete {
+        task_id: i32,
+        complete: bool,
+    },
+    /// Changes a task's text, conflicts if someone else already did
+    RenameTask {
+        task_id: i32,
+        description: String,
+        original: String,
+    },
+    DeleteTask {
+        task_id: i32,
+    },
+}
+
+/// What Shortcut sent back for a mutation
+pub enum Saved {
+    Nothing,
+    Comment(StoryComment),
+    Task(StoryTask),
+    Story(Box<Story>),
+}
+
+/// A mutation that couldn't be sent because we were offline
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PendingMutation {
+    pub id: Uuid,
+    pub story_id: i32,
+    pub mutation: Mutation,
+    pub queued_at: NaiveDateTime,
+}
+
+impl PendingMutation {
+    pub fn new(story_id: i32, mutation: Mutation) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            story_id,
+            mutation,
+            queued_at: crate::time::now_naive(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum ReplayOutcome {
+    Sent,
+    /// The story changed in Shortcut since the mutation was queued. Text we would have
+    /// overwritten is saved to `saved_to` instead.
+    Conflict { saved_to: Option<PathBuf> },
+    /// Shortcut can't take it right now, it stays queued along with everything after it
+    Deferred(ApiError),
+    /// Shortcut won't ever take it, its text is saved to `saved_to` like a conflict's
+    Failed {
+        error: ApiError,
+        saved_to: Option<PathBuf>,
+    },
+}
+
+/// Errors worth sending the mutation again for later, rather than giving up on it
+fn is_transient(error: &ApiError) -> bool {
+    matches!(
+        error,
+        ApiError::Network(_)
+            | ApiError::RateLimited { .. }
+            | ApiError::Server { .. }
+            | ApiError::Unauthorized
+    )
+}
+
+impl Mutation {
+    /// Whether `remote` no longer matches what the mutation was made against
+    pub fn conflicts_with(&self, remote: &Story) -> bool {
+        match self {
+            Mutation::UpdateDescription { original, .. } => remote.description != *original,
+            Mutation::UpdateState { original, .. } => remote.workflow_state_id != *original,
+            // comments only ever add to the thread
+            Mutation::CreateComment { .. } => false,
+            Mutation::UpdateComment {
+                comment_id,
+                original,
+                ..
+            } => !remote
+                .comments
+                .iter()
+                .any(|c| c.id == *comment_id && c.text.as_deref() == Some(original.as_str())),
+            Mutation::UpdateStory { original, .. } => !original.matches(remote),
+            Mutation::CreateTask { .. } => false,
+            Mutation::SetTaskComplete { task_id, complete } => !remote
+                .tasks
+                .iter()
+                .any(|t| t.id == *task_id && t.complete != *complete),
+            Mutation::RenameTask {
+                task_id, original, ..
+            } => !remote
+                .tasks
+                .iter()
+                .any(|t| t.id == *task_id && t.description == *original),
+            // already gone is as good as deleted
+            Mutation::DeleteTask { .. } => false,
+        }
+    }
+
+    /// Text the user wrote, kept when the mutation can't be sent so it isn't lost
+    fn text(&self) -> Option<&str> {
+        match self {
+            Mutation::UpdateDescription { description, .. } => Some(description),
+            Mutation::UpdateState { .. }
+            | Mutation::SetTaskComplete { .. }
+            | Mutation::DeleteTask { .. } => None,
+            Mutation::CreateTask { description } | Mutation::RenameTask { description, .. } => {
+                Some(description)
+            }
+            Mutation::CreateComment { text, .. } | Mutation::UpdateComment { text, .. } => {
+                Some(text)
+            }
+            Mutation::UpdateStory { update, .. } => update.description.as_deref(),
+        }
+    }
+
+    pub async fn send(&self, backend: &Backend, story_id: i32) -> ApiResult<Saved> {
+        match self {
+            Mutation::UpdateDescription { description, .. } => {
+                backend
+                    .update_story_description(story_id, description.clone())
+                    .await?;
+                Ok(Saved::Nothing)
+            }
+            Mutation::UpdateState {
+                workflow_state_id, ..
+            } => {
+                backend
+                    .update_story_state(story_id, *workflow_state_id)
+                    .await?;
+                Ok(Saved::Nothing)
+            }
+            Mutation::CreateComment { text, parent_id } => backend
+                .create_story_comment(story_id, text.clone(), *parent_id)
+                .await
+                .map(Saved::Comment),
+            Mutation::UpdateComment {
+                comment_id, text, ..
+            } => backend
+                .update_story_comment(story_id, *comment_id, text.clone())
+                .await
+                .map(Saved::Comment),
+            Mutation::UpdateStory { update, .. } => backend
+                .update_story(story_id, update.clone())
+                .await
+                .map(|story| Saved::Story(Box::new(story))),
+            Mutation::CreateTask { description } => backend
+                .create_story_task(story_id, description.clone())
+                .await
+                .map(Saved::Task),
+            Mutation::SetTaskComplete { task_id, complete } => {
+                let update = TaskUpdate {
+                    complete: Some(*complete),
+                    ..TaskUpdate::default()
+                };
+                backend
+                    .update_story_task(story_id, *task_id, update)
+                    .await
+                    .map(Saved::Task)
+            }
+            Mutation::RenameTask {
+                task_id,
+                description,
+                ..
+            } => {
+                let update = TaskUpdate {
+                    description: Some(description.clone()),
+                    ..TaskUpdate::default()
+                };
+                backend
+                    .update_story_task(story_id, *task_id, update)
+                    .await
+                    .map(Saved::Task)
+            }
+            Mutation::DeleteTask { task_id } => {
+                match backend.delete_story_task(story_id, *task_id).await {
+                    Ok(()) | Err(ApiError::NotFound) => Ok(Saved::Nothing),
+                    Err(error) => Err(error),
+                }
+            }
+        }
+    }
+}
+
+/// Replays the outbox in order, checking each story for conflicts first. Stops at the first
+/// transient error, e.g. still being offline, the mutations after it stay queued.
+pub async fn replay(
+    backend: &Backend,
+    pending: Vec<PendingMutation>,
+    cache_dir: &Path,
+) -> Vec<(Uuid, ReplayOutcome)> {
+    let mut outcomes = Vec::new();
+
+    for item in pending {
+        let save_text = async || match item.mutation.text() {
+            Some(text) => save_conflict(cache_dir, item.story_id, text).await,
+            None => None,
+        };
+
+        let sent = match backend.get_story(item.story_id).await {
+            Ok(remote) if item.mutation.conflicts_with(&remote) => {
+                outcomes.push((item.id, ReplayOutcome::Conflict { saved_to: save_text().await }));
+                continue;
+            }
+            Ok(_) => item.mutation.send(backend, item.story_id).await,
+            Err(error) => Err(error),
+        };
+
+        match sent {
+            Ok(_) => outcomes.push((item.id, ReplayOutcome::Sent)),
+            Err(error) if is_transient(&error) => {
+                outcomes.push((item.id, ReplayOutcome::Deferred(error)));
+                break;
+            }
+            Err(error) => {
+                let saved_to = save_text().await;
+                outcomes.push((item.id, ReplayOutcome::Failed { error, saved_to }));
+            }
+        }
+    }
+
+    outcomes
+}
+
+/// Saves text that couldn't be sent to Shortcut to `<cache_dir>/conflicts`, so it isn't lost
+pub async fn save_conflict(cache_dir: &Path, story_id: i32, text: &str) -> Option<PathBuf> {
+    let path = cache_dir
+        .join("conflicts")
+        .join(format!("sc-{}-{}.md", story_id, Uuid::new_v4()));
+
+    tokio::fs::create_dir_all(path.parent()?).await.ok()?;
+    tokio::fs::write(&path, text).await.ok()?;
+    Some(path)
+}
+
+pub async fn load_outbox(cache_dir: &Path) -> Vec<PendingMutation> {
+    let path = cache_dir.join("outbox.json");
+    match tokio::fs::read_to_string(&path).await {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
+        Err(_) => Vec::new(),
+    }
+}
+
+pub async fn save_outbox(cache_dir: &Path, outbox: &[PendingMutation]) -> anyhow::Result<()> {
+    let path = cache_dir.join("outbox.json");
+    let content = serde_json::to_string_pretty(outbox)?;
+    persist::locked_update(path, move |_| Ok((content, ()))).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::story::create_test_story;
+
+    fn story() -> Story {
+        Story {
+            id: 1,
+            description: "Changed remotely".to_string(),
+            workflow_state_id: 2,
+            ..create_test_story("Story")
+        }
+    }
+
+    #[test]
+    fn mutations_conflict_when_the_story_moved_on() {
+        let remote = story();
+
+        let stale = Mutation::UpdateDescription {
+            description: "Mine".to_string(),
+            original: "Original".to_string(),
+        };
+        let current = Mutation::UpdateState {
+            workflow_state_id: 3,
+            original: 2,
+        };
+        let comment = Mutation::CreateComment {
+            text: "On the train".to_string(),
+            parent_id: None,
+        };
+
+        assert!(stale.conflicts_with(&remote));
+        assert!(!current.conflicts_with(&remote));
+        assert!(!comment.conflicts_with(&remote));
+    }
+}
@@ -0,0 +1,175 @@
+use std::{
+    fs::{self, File},
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::{Map, Value};
+
+/// Reads `path`, lets `update` build the new contents from what is on disk now, and atomically
+/// replaces the file with them. All of it happens under an advisory lock on `<path>.lock`, so a
+/// TUI and a CLI invocation writing at the same time take turns instead of interleaving.
+///
+/// `update` gets `None` when the file doesn't exist yet, and returns the contents to write
+/// along with anything the caller wants back.
+pub async fn locked_update<T: Send + 'static>(
+    path: PathBuf,
+    update: impl FnOnce(Option<&str>) -> anyhow::Result<(String, T)> + Send + 'static,
+) -> anyhow::Result<T> {
+    tokio::task::spawn_blocking(move || {
+        let dir = path.parent().context("File to update has no parent dir")?;
+        fs::create_dir_all(dir)?;
+
+        let lock_file = File::create(lock_path(&path))?;
+        lock_file.lock().context("Failed to lock file")?;
+
+        let current = match fs::read_to_string(&path) {
+            Ok(contents) => Some(contents),
+            Err(e) if e.kind() == ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+
+        let (contents, result) = update(current.as_deref())?;
+        write_atomic(&path, contents.as_bytes())?;
+
+        // dropping the file releases the lock as well, this just makes it explicit
+        lock_file.unlock()?;
+        Ok(result)
+    })
+    .await?
+}
+
+fn lock_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".lock");
+    path.with_file_name(name)
+}
+
+/// Writes to a temp file in the same dir and renames it over `path`, so readers only ever see
+/// the old or the new contents, never a truncated file
+fn write_atomic(path: &Path, contents: &[u8]) -> anyhow::Result<()> {
+    let dir = path.parent().context("File to write has no parent dir")?;
+    let mut file = tempfile::NamedTempFile::new_in(dir)?;
+    file.write_all(contents)?;
+    file.as_file().sync_all()?;
+    file.persist(path)?;
+    Ok(())
+}
+
+/// Three-way merge of two JSON objects that both started out as `base`. Fields we changed win,
+/// every other field is taken from `theirs` so changes made by another process survive.
+pub fn merge_fields(
+    base: &Map<String, Value>,
+    ours: &Map<String, Value>,
+    theirs: &Map<String, Value>,
+) -> Map<String, Value> {
+    let mut merged = theirs.clone();
+
+    for (key, value) in ours {
+        if base.get(key) != Some(value) || !theirs.contains_key(key) {
+            merged.insert(key.clone(), value.clone());
+        }
+    }
+
+    merged
+}
+
+/// Three-way merge of lists of items with an `id` field, using the same rule as
+/// `merge_fields` per item. Items either side removed stay removed unless the other side
+/// changed them, items only `theirs` has are added after ours.
+pub fn merge_by_id<T: Serialize + DeserializeOwned>(
+    base: &[T],
+    ours: &[T],
+    theirs: &[T],
+) -> anyhow::Result<Vec<T>> {
+    let to_values = |items: &[T]| -> anyhow::Result<Vec<Value>> {
+        items
+            .iter()
+            .map(|item| serde_json::to_value(item).map_err(Into::into))
+            .collect()
+    };
+    let (base, ours, theirs) = (to_values(base)?, to_values(ours)?, to_values(theirs)?);
+    let find = |items: &[Value], id: &Value| items.iter().find(|v| v["id"] == *id).cloned();
+
+    let mut merged = Vec::new();
+
+    for item in &ours {
+        let id = &item["id"];
+        match (find(&base, id), find(&theirs, id)) {
+            // untouched by us, so whatever they did wins, including removing it
+            (Some(base_item), theirs_item) if base_item == *item => merged.extend(theirs_item),
+            _ => merged.push(item.clone()),
+        }
+    }
+
+    for item in &theirs {
+        let id = &item["id"];
+        let ours_has = ours.iter().any(|v| v["id"] == *id);
+        let base_item = find(&base, id);
+
+        // new on their side, or we removed it but they changed it since
+        if !ours_has && base_item.as_ref() != Some(item) {
+            merged.push(item.clone());
+        }
+    }
+
+    merged
+        .into_iter()
+        .map(|value| serde_json::from_value(value).map_err(Into::into))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn object(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(map) => map,
+            _ => panic!("not an object"),
+        }
+    }
+
+    #[test]
+    fn fields_only_we_changed_are_overwritten() {
+        let base = object(json!({"active_story": 1, "user_id": "a", "epics": []}));
+        let ours = object(json!({"active_story": 1, "user_id": "a", "epics": [7]}));
+        let theirs = object(json!({"active_story": 2, "user_id": "a", "epics": []}));
+
+        assert_eq!(
+            merge_fields(&base, &ours, &theirs),
+            object(json!({"active_story": 2, "user_id": "a", "epics": [7]}))
+        );
+    }
+
+    #[test]
+    fn lists_keep_changes_from_both_sides() {
+        let base = vec![json!({"id": 1, "done": false}), json!({"id": 2, "done": false})];
+        // we completed 1 and added 3, they removed 2 and added 4
+        let ours = vec![
+            json!({"id": 1, "done": true}),
+            json!({"id": 2, "done": false}),
+            json!({"id": 3, "done": false}),
+        ];
+        let theirs = vec![json!({"id": 1, "done": false}), json!({"id": 4, "done": false})];
+
+        let ids: Vec<_> = merge_by_id(&base, &ours, &theirs)
+            .unwrap()
+            .into_iter()
+            .map(|v| (v["id"].clone(), v["done"].clone()))
+            .collect();
+
+        assert_eq!(
+            ids,
+            vec![
+                (json!(1), json!(true)),
+                (json!(3), json!(false)),
+                (json!(4), json!(false)),
+            ]
+        );
+    }
+}
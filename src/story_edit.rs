@@ -0,0 +1,270 @@
+use anyhow::Context;
+use chrono::{NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use uuid::Uuid;
+
+use crate::api::{
+    label::Label,
+    story::{NewStory, Story, StoryType, StoryUpdate},
+};
+
+/// The fields of a story that are edited together in one temp file, as YAML frontmatter with
+/// the description as the body
+#[derive(Deserialize, Serialize, Clone, PartialEq, Debug)]
+pub struct StoryFields {
+    pub name: String,
+    pub story_type: StoryType,
+    #[serde(default)]
+    pub estimate: Option<i32>,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    #[serde(default)]
+    pub deadline: Option<NaiveDate>,
+    #[serde(skip)]
+    pub description: String,
+}
+
+impl StoryFields {
+    pub fn from_story(story: &Story) -> Self {
+        Self {
+            name: story.name.clone(),
+            story_type: story.story_type,
+            estimate: story.estimate,
+            labels: story.labels.iter().map(|l| l.name.clone()).collect(),
+            deadline: story.deadline.map(|d| d.date_naive()),
+            description: story.description.clone(),
+        }
+    }
+
+    pub fn to_file(&self) -> anyhow::Result<String> {
+        to_file(self, &self.description)
+    }
+
+    pub fn parse(contents: &str) -> anyhow::Result<Self> {
+        let (mut fields, body): (StoryFields, _) = parse(contents)?;
+        fields.validate()?;
+        fields.description = body.to_string();
+        Ok(fields)
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        if self.name.trim().is_empty() {
+            anyhow::bail!("The story needs a name");
+        }
+        Ok(())
+    }
+
+    /// Only the fields that differ from `story`. Labels keep their colour if the story already
+    /// had them.
+    pub fn changes_from(&self, story: &Story) -> StoryUpdate {
+        let current = Self::from_story(story);
+
+        let labels = self.labels.iter().map(|name| {
+            story
+                .labels
+                .iter()
+                .find(|l| l.name == *name)
+                .cloned()
+                .unwrap_or_else(|| Label {
+                    name: name.clone(),
+                    color: None,
+                })
+        });
+
+        StoryUpdate {
+            name: changed(&self.name, &current.name),
+            story_type: changed(&self.story_type, &current.story_type),
+            estimate: changed(&self.estimate, &current.estimate),
+            labels: changed(&self.labels, &current.labels).map(|_| labels.collect()),
+            deadline: changed(&self.deadline, &current.deadline)
+                .map(|deadline| deadline.map(|d| d.and_time(NaiveTime::MIN).and_utc())),
+            // editors like to add a newline at the end of the file
+            description: changed(&self.description.trim_end(), &current.description.trim_end())
+                .map(|_| self.description.clone()),
+        }
+    }
+}
+
+/// Template for a new story, with where it goes prefilled
+#[derive(Deserialize, Serialize, Clone, PartialEq, Debug)]
+pub struct NewStoryFields {
+    #[serde(flatten)]
+    pub fields: StoryFields,
+    #[serde(default)]
+    pub iteration_id: Option<i32>,
+    #[serde(default)]
+    pub epic_id: Option<i32>,
+    /// Make it the active story once it's created
+    #[serde(default)]
+    pub set_active: bool,
+}
+
+impl NewStoryFields {
+    pub fn new(iteration_id: Option<i32>, epic_id: Option<i32>) -> Self {
+        Self {
+            fields: StoryFields {
+                name: String::new(),
+                story_type: StoryType::default(),
+                estimate: None,
+                labels: Vec::new(),
+                deadline: None,
+                description: String::new(),
+            },
+            iteration_id,
+            epic_id,
+            set_active: false,
+        }
+    }
+
+    pub fn to_file(&self) -> anyhow::Result<String> {
+        to_file(self, &self.fields.description)
+    }
+
+    pub fn parse(contents: &str) -> anyhow::Result<Self> {
+        let (mut new, body): (NewStoryFields, _) = parse(contents)?;
+        new.fields.validate()?;
+        new.fields.description = body.to_string();
+        Ok(new)
+    }
+
+    /// The story to create, owned by `owner_id` so it shows up in our list
+    pub fn to_new_story(&self, owner_id: Uuid) -> NewStory {
+        let fields = &self.fields;
+        NewStory {
+            name: fields.name.trim().to_string(),
+            story_type: fields.story_type,
+            description: fields.description.trim_end().to_string(),
+            estimate: fields.estimate,
+            labels: fields
+                .labels
+                .iter()
+                .map(|name| Label {
+                    name: name.clone(),
+                    color: None,
+                })
+                .collect(),
+            deadline: fields.deadline.map(|d| d.and_time(NaiveTime::MIN).and_utc()),
+            iteration_id: self.iteration_id,
+            epic_id: self.epic_id,
+            owner_ids: vec![owner_id],
+        }
+    }
+}
+
+fn to_file<T: Serialize>(fields: &T, body: &str) -> anyhow::Result<String> {
+    Ok(format!("---\n{}---\n{}", serde_yaml::to_string(fields)?, body))
+}
+
+/// Splits off and parses the frontmatter, returning it with the body after it
+fn parse<T: DeserializeOwned>(contents: &str) -> anyhow::Result<(T, &str)> {
+    let rest = contents
+        .strip_prefix("---\n")
+        .context("The file should start with a --- line")?;
+    let (yaml, body) = rest
+        .split_once("\n---\n")
+        .or_else(|| rest.strip_suffix("\n---").map(|yaml| (yaml, "")))
+        .context("The frontmatter should end with a --- line")?;
+
+    Ok((serde_yaml::from_str(yaml)?, body))
+}
+
+/// Opens `text` with `edit` until `parse` accepts the result, putting the error at the top of
+/// the file each time it doesn't. Saving the file without changes gives up and returns `None`.
+pub fn edit_until_valid<T>(
+    mut text: String,
+    mut edit: impl FnMut(&str) -> anyhow::Result<String>,
+    parse: impl Fn(&str) -> anyhow::Result<T>,
+) -> anyhow::Result<Option<T>> {
+    loop {
+        let edited = edit(&text)?;
+        if edited == text {
+            return Ok(None);
+        }
+
+        match parse(&edited) {
+            Ok(parsed) => return Ok(Some(parsed)),
+            Err(e) => text = annotate_error(&edited, &e),
+        }
+    }
+}
+
+fn changed<T: PartialEq + Clone>(edited: &T, current: &T) -> Option<T> {
+    (edited != current).then(|| edited.clone())
+}
+
+/// Puts the error at the top of the frontmatter as a YAML comment
+fn annotate_error(contents: &str, error: &anyhow::Error) -> String {
+    let body = contents.strip_prefix("---\n").unwrap_or(contents);
+    // the previous error is replaced rather than piling up
+    let body = match body.split_once('\n') {
+        Some((first, rest)) if first.starts_with("# Error: ") => rest,
+        _ => body,
+    };
+    format!("---\n# Error: {:#}\n{}", error, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::story::create_test_story;
+
+    fn story() -> Story {
+        Story {
+            id: 1,
+            description: "Steps to reproduce".to_string(),
+            estimate: Some(2),
+            labels: vec![Label {
+                name: "backend".to_string(),
+                color: Some("#ff0000".to_string()),
+            }],
+            ..create_test_story("Login fails")
+        }
+    }
+
+    #[test]
+    fn only_edited_fields_are_sent() {
+        let story = story();
+        let file = StoryFields::from_story(&story).to_file().unwrap();
+        let edited = file
+            .replace("estimate: 2", "estimate: 3")
+            .replace("- backend", "- backend\n- urgent")
+            + "\n";
+
+        let update = StoryFields::parse(&edited).unwrap().changes_from(&story);
+        assert_eq!(
+            update,
+            StoryUpdate {
+                estimate: Some(Some(3)),
+                labels: Some(vec![
+                    story.labels[0].clone(),
+                    Label {
+                        name: "urgent".to_string(),
+                        color: None,
+                    },
+                ]),
+                ..StoryUpdate::default()
+            }
+        );
+    }
+
+    #[test]
+    fn new_story_template_round_trips() {
+        let mut new = NewStoryFields::new(Some(7), None);
+        let file = new.to_file().unwrap();
+        assert!(NewStoryFields::parse(&file).is_err(), "a story needs a name");
+
+        new.fields.name = "Add dark mode".to_string();
+        new.fields.description = "Users keep asking".to_string();
+        let parsed = NewStoryFields::parse(&new.to_file().unwrap()).unwrap();
+        assert_eq!(parsed, new);
+        assert_eq!(parsed.to_new_story(Uuid::nil()).iteration_id, Some(7));
+    }
+
+    #[test]
+    fn unchanged_file_has_no_changes() {
+        let story = story();
+        let file = StoryFields::from_story(&story).to_file().unwrap();
+
+        assert!(StoryFields::parse(&file).unwrap().changes_from(&story).is_empty());
+    }
+}
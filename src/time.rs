@@ -11,6 +11,11 @@ pub fn now_naive() -> NaiveDateTime {
     now().naive_local()
 }
 
+/// The offset `today` and `now_naive` are in
+pub fn offset() -> FixedOffset {
+    *now().offset()
+}
+
 fn now() -> chrono::DateTime<FixedOffset> {
     let local = Local::now().fixed_offset();
 
@@ -1,9 +1,11 @@
-use std::path::PathBuf;
+use std::path::Path;
 
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::persist;
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Todo {
     pub id: Uuid,
@@ -23,7 +25,7 @@ impl Todo {
     }
 }
 
-pub async fn load_todos(cache_dir: &PathBuf) -> Vec<Todo> {
+pub async fn load_todos(cache_dir: &Path) -> Vec<Todo> {
     let path = cache_dir.join("todos.json");
     match tokio::fs::read_to_string(&path).await {
         Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
@@ -31,12 +33,24 @@ pub async fn load_todos(cache_dir: &PathBuf) -> Vec<Todo> {
     }
 }
 
-pub async fn save_todos(cache_dir: &PathBuf, todos: &[Todo]) -> anyhow::Result<()> {
+/// Saves `todos`, keeping changes another process made to the file since it last matched
+/// `synced`. Returns the todos as saved, which become the next `synced`.
+pub async fn save_todos(
+    cache_dir: &Path,
+    synced: &[Todo],
+    todos: &[Todo],
+) -> anyhow::Result<Vec<Todo>> {
     let path = cache_dir.join("todos.json");
-    if let Some(parent) = path.parent() {
-        tokio::fs::create_dir_all(parent).await?;
-    }
-    let content = serde_json::to_string_pretty(todos)?;
-    tokio::fs::write(&path, content).await?;
-    Ok(())
+    let (synced, todos) = (synced.to_vec(), todos.to_vec());
+
+    persist::locked_update(path, move |current| {
+        let merged = match current.map(serde_json::from_str::<Vec<Todo>>) {
+            Some(Ok(on_disk)) => persist::merge_by_id(&synced, &todos, &on_disk)?,
+            None => persist::merge_by_id(&synced, &todos, &[])?,
+            // nothing to merge with, reading it as an empty list would delete every todo
+            Some(Err(_)) => todos,
+        };
+        Ok((serde_json::to_string_pretty(&merged)?, merged))
+    })
+    .await
 }
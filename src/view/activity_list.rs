@@ -0,0 +1,172 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Color, Style},
+    symbols::border,
+    text::{Line, Span},
+    widgets::{Block, Padding, Paragraph, StatefulWidget, Widget, WidgetRef},
+};
+use tui_widget_list::{ListBuilder, ListState, ListView};
+
+use crate::{
+    activity::{Activity, ActivityKind},
+    api::{user::get_member_name, workflow::get_workflow_state},
+    app::model::{ActivityListState, DataState},
+    time::today,
+};
+
+pub struct ActivityListView<'a> {
+    data: &'a DataState,
+    state: &'a ActivityListState,
+}
+
+impl<'a> ActivityListView<'a> {
+    pub fn new(data: &'a DataState, state: &'a ActivityListState) -> Self {
+        Self { data, state }
+    }
+
+    /// One line summary of what changed, using member and state names where we know them
+    fn describe(&self, kind: &ActivityKind) -> String {
+        let state_name = |id| {
+            get_workflow_state(id, &self.data.workflows)
+                .map(|s| s.name.clone())
+                .unwrap_or_else(|| "unknown state".to_string())
+        };
+        let member_name = |id| get_member_name(id, &self.data.members).unwrap_or("Someone");
+
+        match kind {
+            ActivityKind::NewComment { author_id, text } => {
+                let first_line = text.lines().next().unwrap_or_default();
+                format!("{} commented: {}", member_name(*author_id), first_line)
+            }
+            ActivityKind::DescriptionEdited => "Description edited".to_string(),
+            ActivityKind::StateChanged { from, to } => {
+                format!("Moved from {} to {}", state_name(*from), state_name(*to))
+            }
+            ActivityKind::OwnerAdded { owner_id } => {
+                format!("{} was added as an owner", member_name(*owner_id))
+            }
+        }
+    }
+}
+
+impl WidgetRef for ActivityListView<'_> {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        let activity = &self.data.activity;
+        let unread = activity.iter().filter(|a| !a.read).count();
+
+        let block = Block::bordered()
+            .border_set(border::THICK)
+            .title(format!(" {} unread ", unread))
+            .title_bottom(" Enter open • m mark all read ")
+            .padding(Padding::vertical(1));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        if activity.is_empty() {
+            let message = "Nothing has changed on your stories yet.";
+            let paragraph = Paragraph::new(message)
+                .style(Style::default().fg(Color::DarkGray))
+                .alignment(Alignment::Center);
+
+            if inner.height > 0 {
+                let centered_area =
+                    Rect::new(inner.x, inner.y + inner.height / 2, inner.width, 1);
+                paragraph.render(centered_area, buf);
+            }
+            return;
+        }
+
+        let rows: Vec<_> = activity
+            .iter()
+            .map(|a| (a.clone(), self.describe(&a.kind)))
+            .collect();
+        let selected_id = self.state.selected_id;
+
+        let builder = ListBuilder::new(move |context| {
+            let (activity, summary) = &rows[context.index];
+            let widget = ActivityItemWidget {
+                activity: activity.clone(),
+                summary: summary.clone(),
+                is_selected: selected_id.is_some_and(|id| id == activity.id),
+            };
+            (widget, 2)
+        });
+
+        let list = ListView::new(builder, activity.len());
+
+        let mut list_state = ListState::default();
+        list_state.select(
+            selected_id.and_then(|id| activity.iter().position(|a| a.id == id)),
+        );
+
+        StatefulWidget::render(list, inner, buf, &mut list_state);
+    }
+}
+
+struct ActivityItemWidget {
+    activity: Activity,
+    summary: String,
+    is_selected: bool,
+}
+
+impl Widget for ActivityItemWidget {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.height < 1 {
+            return;
+        }
+
+        let base_style = if self.activity.read {
+            Style::default().fg(Color::DarkGray)
+        } else {
+            Style::default()
+        };
+
+        let marker = if self.activity.read {
+            Span::raw("  ")
+        } else {
+            Span::styled("● ", Style::default().fg(Color::Magenta))
+        };
+
+        // today's activity only needs the time
+        let at = if self.activity.at.date() == today() {
+            self.activity.at.format("%H:%M").to_string()
+        } else {
+            self.activity.at.format("%b %-d %H:%M").to_string()
+        };
+
+        let name_style = if self.is_selected {
+            base_style.bold()
+        } else {
+            base_style
+        };
+
+        let spans = vec![
+            marker,
+            Span::styled(format!("{} ", at), Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                format!("sc-{} ", self.activity.story_id),
+                Style::default().fg(if self.activity.read {
+                    Color::DarkGray
+                } else {
+                    Color::Blue
+                }),
+            ),
+            Span::styled(self.activity.story_name.clone(), name_style),
+            Span::styled(format!("  {}", self.summary), base_style),
+        ];
+
+        buf.set_line(area.x, area.y, &Line::from(spans), area.width);
+
+        // Render divider on second line
+        if area.height >= 2 {
+            let divider_style = if self.is_selected {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+            let divider = Line::from("─".repeat(area.width as usize)).style(divider_style);
+            buf.set_line(area.x, area.y + 1, &divider, area.width);
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Color, Style},
+    symbols::Marker,
+    text::Line,
+    widgets::{
+        Axis, Block, Chart, Dataset, GraphType, LegendPosition, Paragraph, Widget, WidgetRef,
+    },
+};
+
+use crate::{
+    api::{iteration::Iteration, story::Story},
+    burndown::Burndown,
+};
+
+/// Burndown, or burnup, of the points of our stories in an iteration, with the ideal line
+pub struct BurndownView<'a> {
+    iteration: Option<&'a Iteration>,
+    stories: Vec<&'a Story>,
+    burnup: bool,
+}
+
+impl<'a> BurndownView<'a> {
+    pub fn new(iteration: Option<&'a Iteration>, stories: Vec<&'a Story>, burnup: bool) -> Self {
+        Self {
+            iteration,
+            stories,
+            burnup,
+        }
+    }
+}
+
+impl WidgetRef for BurndownView<'_> {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        let kind = if self.burnup { "Burnup" } else { "Burndown" };
+        let title = match self.iteration {
+            Some(iteration) => format!(" {} · {} ", kind, iteration.name),
+            None => format!(" {} ", kind),
+        };
+        let block = Block::bordered()
+            .title(title)
+            .title_bottom(Line::from(" b burndown/burnup ").right_aligned());
+
+        let Some(iteration) = self.iteration else {
+            let inner = block.inner(area);
+            block.render(area, buf);
+            render_message("Select an iteration", inner, buf);
+            return;
+        };
+
+        let burndown = Burndown::new(
+            iteration,
+            &self.stories,
+            crate::time::today(),
+            crate::time::offset(),
+        );
+        if burndown.total == 0.0 {
+            let inner = block.inner(area);
+            block.render(area, buf);
+            render_message("None of your stories in this iteration are estimated", inner, buf);
+            return;
+        }
+
+        // the legend goes in the corner the lines stay out of
+        let (actual, ideal, legend_position) = if self.burnup {
+            (&burndown.done, burndown.ideal_done(), LegendPosition::TopLeft)
+        } else {
+            (&burndown.remaining, burndown.ideal_remaining(), LegendPosition::BottomLeft)
+        };
+        let done = burndown.done.last().map(|(_, p)| *p).unwrap_or(0.0);
+
+        let datasets = vec![
+            Dataset::default()
+                .name("ideal")
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().dark_gray())
+                .data(&ideal),
+            Dataset::default()
+                .name(format!("{}/{} pts done", done, burndown.total))
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(Color::Green))
+                .data(actual),
+        ];
+
+        let date_format = "%b %d";
+        let x_axis = Axis::default()
+            .bounds([0.0, burndown.days.max(1.0)])
+            .labels([
+                iteration.start_date.format(date_format).to_string(),
+                iteration.end_date.format(date_format).to_string(),
+            ])
+            .style(Style::default().gray());
+        let y_axis = Axis::default()
+            .bounds([0.0, burndown.total])
+            .labels(["0".to_string(), burndown.total.to_string()])
+            .style(Style::default().gray());
+
+        Chart::new(datasets)
+            .block(block)
+            .legend_position(Some(legend_position))
+            .x_axis(x_axis)
+            .y_axis(y_axis)
+            .render(area, buf);
+    }
+}
+
+fn render_message(message: &str, area: Rect, buf: &mut Buffer) {
+    if area.height == 0 {
+        return;
+    }
+    let centered = Rect::new(area.x, area.y + area.height / 2, area.width, 1);
+    Paragraph::new(message)
+        .style(Style::default().gray())
+        .alignment(Alignment::Center)
+        .render(centered, buf);
+}
@@ -1,21 +1,244 @@
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Direction, Layout, Rect, Size},
-    style::{Modifier, Style},
-    text::{Line, Text},
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
     widgets::{Block, BorderType, Paragraph, StatefulWidget, Widget, Wrap},
 };
+use textwrap::wrap;
 use tui_scrollview::{ScrollView, ScrollViewState};
 
-use crate::api::story::Story;
+use crate::api::{
+    story::{
+        Story,
+        comment::comment_threads,
+        link::ordered_links,
+        task::{ordered_tasks, task_counts},
+    },
+    user::{WorkspaceMember, get_member_name, get_owners},
+};
+
+const REPLY_INDENT: usize = 4;
 
 pub struct DescriptionModal<'a> {
     story: &'a Story,
+    members: &'a [WorkspaceMember],
+    selected_comment_id: Option<i32>,
+    selected_task_id: Option<i32>,
+    selected_link_id: Option<i32>,
+    known_stories: Vec<&'a Story>,
 }
 
 impl<'a> DescriptionModal<'a> {
-    pub fn new(story: &'a Story) -> Self {
-        Self { story }
+    pub fn new(
+        story: &'a Story,
+        members: &'a [WorkspaceMember],
+        selected_comment_id: Option<i32>,
+    ) -> Self {
+        Self {
+            story,
+            members,
+            selected_comment_id,
+            selected_task_id: None,
+            selected_link_id: None,
+            known_stories: Vec::new(),
+        }
+    }
+
+    /// Selected linked story, and the loaded stories to show linked stories' names from
+    pub fn with_links(
+        mut self,
+        selected_link_id: Option<i32>,
+        known_stories: Vec<&'a Story>,
+    ) -> Self {
+        self.selected_link_id = selected_link_id;
+        self.known_stories = known_stories;
+        self
+    }
+
+    pub fn with_selected_task(mut self, selected_task_id: Option<i32>) -> Self {
+        self.selected_task_id = selected_task_id;
+        self
+    }
+
+    /// "Owners: Jane Doe (@jane), Alex (@alex)" followed by a blank line
+    fn owner_lines(&self) -> Vec<Line<'static>> {
+        let owners = get_owners(&self.story.owner_ids, self.members);
+        let names = if owners.is_empty() {
+            "None".to_string()
+        } else {
+            owners
+                .iter()
+                .map(|m| format!("{} (@{})", m.profile.name, m.profile.mention_name))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        vec![
+            Line::from(vec![
+                Span::styled("Owners: ", Style::default().dark_gray()),
+                Span::raw(names),
+            ]),
+            Line::default(),
+        ]
+    }
+
+    /// Linked stories, what blocks this one first. Nothing when there are no links.
+    fn relation_lines(&self) -> Vec<Line<'static>> {
+        if self.story.story_links.is_empty() {
+            return vec![];
+        }
+
+        let links = ordered_links(&self.story.story_links);
+        let mut lines = vec![
+            Line::from(format!("── Relations ({}) ──", links.len()))
+                .style(Style::default().dark_gray()),
+        ];
+
+        for link in links {
+            let other_id = link.other_story_id();
+            let is_selected = self.selected_link_id == Some(link.id);
+
+            let label_style = if link.is_blocked_by() {
+                Style::default().fg(Color::Red)
+            } else if link.is_blocking() {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default().dark_gray()
+            };
+            let name = self
+                .known_stories
+                .iter()
+                .find(|s| s.id == other_id)
+                .map(|s| s.name.clone())
+                .unwrap_or_default();
+            let name_style = if is_selected {
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+
+            lines.push(Line::from(vec![
+                Span::styled(format!("{:<17}", link.label()), label_style),
+                Span::styled(format!("sc-{} ", other_id), Style::default().fg(Color::Blue)),
+                Span::styled(name, name_style),
+            ]));
+        }
+
+        lines.push(Line::default());
+        lines
+    }
+
+    /// The checklist, nothing when the story has no tasks
+    fn task_lines(&self, width: u16) -> Vec<Line<'static>> {
+        if self.story.tasks.is_empty() {
+            return vec![];
+        }
+
+        let (done, total) = task_counts(&self.story.tasks);
+        let mut lines = vec![
+            Line::default(),
+            Line::from(format!("── Tasks ({}/{}) ──", done, total))
+                .style(Style::default().dark_gray()),
+        ];
+
+        let text_width = (width as usize).saturating_sub(4).max(1);
+        for task in ordered_tasks(&self.story.tasks) {
+            let is_selected = self.selected_task_id == Some(task.id);
+            let (checkbox, checkbox_style) = if task.complete {
+                ("[x] ", Style::default().fg(Color::Green))
+            } else {
+                ("[ ] ", Style::default().dark_gray())
+            };
+            let text_style = match (is_selected, task.complete) {
+                (true, _) => Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                (false, true) => Style::default().gray(),
+                (false, false) => Style::default(),
+            };
+
+            for (i, wrapped) in wrap(&task.description, text_width).into_iter().enumerate() {
+                let prefix = if i == 0 {
+                    Span::styled(checkbox, checkbox_style)
+                } else {
+                    Span::raw("    ")
+                };
+                lines.push(Line::from(vec![
+                    prefix,
+                    Span::styled(wrapped.into_owned(), text_style),
+                ]));
+            }
+        }
+
+        lines
+    }
+
+    /// Renders the comment threads as pre-wrapped lines, replies indented under their parent
+    fn comment_lines(&self, width: u16) -> Vec<Line<'static>> {
+        let threads = comment_threads(&self.story.comments);
+
+        let header = format!("── Comments ({}) ──", threads.len());
+        let mut lines = vec![
+            Line::default(),
+            Line::from(header).style(Style::default().dark_gray()),
+        ];
+
+        if threads.is_empty() {
+            lines.push(Line::from("No comments").style(Style::default().gray()));
+            return lines;
+        }
+
+        for (depth, comment) in threads {
+            let indent = " ".repeat(depth * REPLY_INDENT);
+            let is_selected = self.selected_comment_id == Some(comment.id);
+
+            let author = get_member_name(comment.author_id, self.members)
+                .unwrap_or("Unknown member")
+                .to_string();
+            let created = comment
+                .created_at
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string();
+
+            let marker = if depth > 0 { "↳ " } else { "" };
+            let author_style = if is_selected {
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().add_modifier(Modifier::BOLD)
+            };
+
+            lines.push(Line::default());
+            lines.push(Line::from(vec![
+                Span::raw(format!("{}{}", indent, marker)),
+                Span::styled(author, author_style),
+                Span::styled(format!(" · {}", created), Style::default().dark_gray()),
+            ]));
+
+            let text = if comment.deleted {
+                "[deleted]".to_string()
+            } else {
+                comment.text.clone().unwrap_or_default()
+            };
+            let text_width = (width as usize).saturating_sub(indent.len() + 2).max(1);
+            let gutter = if is_selected { "┃ " } else { "│ " };
+            let gutter_style = if is_selected {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default().dark_gray()
+            };
+
+            for paragraph in text.trim().lines() {
+                for wrapped in wrap(paragraph, text_width) {
+                    lines.push(Line::from(vec![
+                        Span::raw(indent.clone()),
+                        Span::styled(gutter, gutter_style),
+                        Span::raw(wrapped.into_owned()),
+                    ]));
+                }
+            }
+        }
+
+        lines
     }
 }
 
@@ -39,7 +262,10 @@ impl StatefulWidget for DescriptionModal<'_> {
         // Outer block with border
         let block = Block::bordered()
             .border_type(BorderType::Rounded)
-            .title_bottom(" j/k scroll • g/G top/bottom • q close ");
+            .title_bottom(
+                " j/k scroll • [/] comment • c new • r reply • e edit • {/} task • x toggle • \
+                 a add • D delete • </> link • Enter open • n note • q close ",
+            );
 
         block.render(area, buf);
 
@@ -64,10 +290,38 @@ impl StatefulWidget for DescriptionModal<'_> {
         let paragraph = Paragraph::new(Text::from(description)).wrap(Wrap { trim: false });
 
         let content_width = content_area.width;
-        let total_lines = paragraph.line_count(content_width) as u16;
+        let owners = Paragraph::new(self.owner_lines()).wrap(Wrap { trim: false });
+        let owner_lines = owners.line_count(content_width) as u16;
+        let description_lines = paragraph.line_count(content_width) as u16;
+
+        let relations = Paragraph::new(self.relation_lines());
+        let relation_lines = relations.line_count(content_width) as u16;
+
+        let tasks = Paragraph::new(self.task_lines(content_width));
+        let task_lines = tasks.line_count(content_width) as u16;
+
+        let comments = Paragraph::new(self.comment_lines(content_width));
+        let comment_lines = comments.line_count(content_width) as u16;
+        let total_lines =
+            owner_lines + relation_lines + description_lines + task_lines + comment_lines;
 
         let mut scroll_view = ScrollView::new(Size::new(content_width, total_lines));
-        scroll_view.render_widget(paragraph, Rect::new(0, 0, content_width, total_lines));
+        scroll_view.render_widget(owners, Rect::new(0, 0, content_width, owner_lines));
+        scroll_view.render_widget(
+            relations,
+            Rect::new(0, owner_lines, content_width, relation_lines),
+        );
+        let description_top = owner_lines + relation_lines;
+        scroll_view.render_widget(
+            paragraph,
+            Rect::new(0, description_top, content_width, description_lines),
+        );
+        let tasks_top = description_top + description_lines;
+        scroll_view.render_widget(tasks, Rect::new(0, tasks_top, content_width, task_lines));
+        scroll_view.render_widget(
+            comments,
+            Rect::new(0, tasks_top + task_lines, content_width, comment_lines),
+        );
         scroll_view.render(content_area, buf, state);
 
         // Bottom divider
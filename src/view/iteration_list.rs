@@ -6,15 +6,17 @@ use ratatui::{
 };
 
 use crate::{
-    api::iteration::Iteration,
+    api::{iteration::Iteration, story::Story},
     app::{model::IterationListState, pane::iteration_list::filter_items},
     custom_list::LinearList,
+    view::burndown::BurndownView,
 };
 
 pub struct IterationListView<'a> {
     pub active_iterations: &'a [Iteration],
     pub all_iterations: &'a [Iteration],
     pub state: &'a IterationListState,
+    pub stories: &'a [Story],
 }
 
 impl<'a> IterationListView<'a> {
@@ -27,8 +29,15 @@ impl<'a> IterationListView<'a> {
             active_iterations,
             all_iterations,
             state,
+            stories: &[],
         }
     }
+
+    /// Our stories, charted for the selected iteration
+    pub fn with_stories(mut self, stories: &'a [Story]) -> Self {
+        self.stories = stories;
+        self
+    }
 }
 
 impl WidgetRef for IterationListView<'_> {
@@ -52,7 +61,20 @@ impl WidgetRef for IterationListView<'_> {
             .style(bar_style)
             .render(chunks[0], buf);
 
-        let content_area = chunks[1];
+        let [content_area, chart_area] =
+            Layout::horizontal([Constraint::Percentage(45), Constraint::Percentage(55)])
+                .areas(chunks[1]);
+
+        let selected = self.state.selected_id.and_then(|id| {
+            self.active_iterations
+                .iter()
+                .chain(self.all_iterations)
+                .find(|it| it.id == id)
+        });
+        let stories = selected
+            .map(|it| self.stories.iter().filter(|s| s.iteration_id == Some(it.id)).collect())
+            .unwrap_or_default();
+        BurndownView::new(selected, stories, self.state.burnup).render_ref(chart_area, buf);
 
         // Build filtered lists for each section
         let active_visible: Vec<Iteration> = filter_items(self.active_iterations, query)
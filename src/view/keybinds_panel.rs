@@ -13,6 +13,7 @@ const LINES: &[&str] = &[
     "  Tab / L        Next view",
     "  BackTab / H    Prev view",
     "  d              Open daily note",
+    "  r              Refresh from Shortcut",
     "─────────────────────────────────────",
     " Story List",
     "  Space          Show description",
@@ -24,10 +25,41 @@ const LINES: &[&str] = &[
     "  t              Tmux session",
     "  a              Select active story",
     "  f              Toggle finished",
+    "  s              Change state",
+    "  T              Toggle team stories",
+    "─────────────────────────────────────",
+    " Description",
+    "  [ / ]          Select comment",
+    "  c              New comment",
+    "  r              Reply to comment",
+    "  e              Edit my comment",
+    "  { / }          Select task",
+    "  x              Toggle task",
+    "  a              Add tasks",
+    "  D              Delete task",
+    "  < / >          Select linked story",
+    "  Enter          Open linked story",
+    "  n              Open linked story's note",
+    "─────────────────────────────────────",
+    " Iterations",
+    "  Enter          Open note",
+    "  b              Toggle burndown/burnup",
+    "─────────────────────────────────────",
+    " Activity",
+    "  Enter          Open story",
+    "  m              Mark all read",
     "─────────────────────────────────────",
     " Notes",
     "  Enter          Open note",
     "─────────────────────────────────────",
+    " Search",
+    "  type           Filter everything",
+    "  ↑↓             Navigate results",
+    "  Enter          Open note / run query",
+    "  Esc            Stop typing / clear",
+    "  Ctrl+R         Toggle Shortcut search",
+    "  a / o / Space  Active / browser / desc",
+    "─────────────────────────────────────",
     "       ? / Esc / q  close",
 ];
 
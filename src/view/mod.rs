@@ -1,14 +1,18 @@
 pub mod action_item_builder;
+pub mod activity_list;
 pub mod add_todo_modal;
+pub mod burndown;
 pub mod create_note_modal;
 pub mod epic_list;
 pub mod keybinds_panel;
 pub mod description_modal;
 pub mod navbar;
 pub mod notes_list;
+pub mod search;
 pub mod story_item_builder;
 pub mod story_list;
 pub mod todos_list;
+pub mod token_prompt;
 pub mod iteration_list;
 
 pub use action_item_builder::ActionItemWidget;
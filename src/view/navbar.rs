@@ -1,3 +1,4 @@
+use chrono::NaiveDateTime;
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
@@ -14,6 +15,10 @@ pub struct NavBar {
     active_view: ViewType,
     loading: LoadingState,
     has_stories: bool,
+    rate_limited_until: Option<NaiveDateTime>,
+    last_refreshed: Option<NaiveDateTime>,
+    offline: bool,
+    pending: usize,
     tick: usize,
 }
 
@@ -22,16 +27,29 @@ impl NavBar {
         active_view: ViewType,
         loading: LoadingState,
         has_stories: bool,
+        rate_limited_until: Option<NaiveDateTime>,
+        last_refreshed: Option<NaiveDateTime>,
         tick: usize,
     ) -> Self {
         Self {
             active_view,
             loading,
             has_stories,
+            rate_limited_until,
+            last_refreshed,
+            offline: false,
+            pending: 0,
             tick,
         }
     }
 
+    /// Shows that Shortcut can't be reached and how many changes are waiting to be sent
+    pub fn with_sync_status(mut self, offline: bool, pending: usize) -> Self {
+        self.offline = offline;
+        self.pending = pending;
+        self
+    }
+
     fn spinner_char(&self) -> char {
         SPINNER_CHARS[self.tick % SPINNER_CHARS.len()]
     }
@@ -70,8 +88,23 @@ impl WidgetRef for NavBar {
         let paragraph = Paragraph::new(line);
         paragraph.render(inner, buf);
 
+        let rate_limit_secs = self
+            .rate_limited_until
+            .map(|until| (until - crate::time::now_naive()).num_seconds())
+            .filter(|secs| *secs > 0);
+
+        // Rate limit countdown takes the spinner's place, nothing will load until it ends
+        if let Some(secs) = rate_limit_secs {
+            let text = format!("Rate limited, retry in {}s", secs);
+            let width = text.len() as u16;
+
+            if inner.width > width {
+                let area = Rect::new(inner.x + inner.width - width, inner.y, width, 1);
+                Paragraph::new(Line::from(Span::styled(text, Style::default().yellow())))
+                    .render(area, buf);
+            }
         // Show spinner on right when loading AND we have cached stories displayed
-        if self.loading.is_loading() && self.has_stories {
+        } else if self.loading.is_loading() && self.has_stories {
             let loading_text = format!("{} {}", self.spinner_char(), self.loading.label());
             let loading_span = Span::styled(loading_text.clone(), Style::default().gray());
             let loading_width = loading_text.len() as u16;
@@ -86,6 +119,39 @@ impl WidgetRef for NavBar {
                 let loading_paragraph = Paragraph::new(Line::from(loading_span));
                 loading_paragraph.render(loading_area, buf);
             }
+        } else {
+            let mut status = Vec::new();
+            if self.offline {
+                status.push(Span::styled("Offline", Style::default().red()));
+            }
+            if self.pending > 0 {
+                status.push(Span::styled(
+                    format!("{} pending", self.pending),
+                    Style::default().yellow(),
+                ));
+            }
+            if let Some(last_refreshed) = self.last_refreshed {
+                status.push(Span::styled(
+                    format!("Updated {}", last_refreshed.format("%H:%M")),
+                    Style::default().dark_gray(),
+                ));
+            }
+
+            let mut spans = Vec::new();
+            for (i, span) in status.into_iter().enumerate() {
+                if i > 0 {
+                    spans.push(Span::styled(" · ", Style::default().dark_gray()));
+                }
+                spans.push(span);
+            }
+
+            let line = Line::from(spans);
+            let width = line.width() as u16;
+
+            if width > 0 && inner.width > width {
+                let area = Rect::new(inner.x + inner.width - width, inner.y, width, 1);
+                Paragraph::new(line).render(area, buf);
+            }
         }
     }
 }
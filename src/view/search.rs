@@ -0,0 +1,128 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::Style,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Padding, Paragraph, Widget, WidgetRef},
+};
+
+use crate::app::{
+    model::{DataState, SearchState},
+    pane::search::{visible_results, SearchResult},
+};
+
+pub struct SearchView<'a> {
+    data: &'a DataState,
+    state: &'a SearchState,
+}
+
+impl<'a> SearchView<'a> {
+    pub fn new(data: &'a DataState, state: &'a SearchState) -> Self {
+        Self { data, state }
+    }
+}
+
+/// A row of the results list: either a group heading or a result
+enum Row<'a> {
+    Header(&'static str),
+    Result(&'a SearchResult),
+}
+
+fn rows(results: &[SearchResult]) -> Vec<Row<'_>> {
+    let mut rows = Vec::new();
+    let mut current_group = None;
+
+    for result in results {
+        let group = result.hit.group_label();
+        if current_group != Some(group) {
+            rows.push(Row::Header(group));
+            current_group = Some(group);
+        }
+        rows.push(Row::Result(result));
+    }
+
+    rows
+}
+
+impl WidgetRef for SearchView<'_> {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        let chunks = Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).split(area);
+
+        let query = &self.state.query;
+        let display = if query.is_empty() && !self.state.search_active {
+            "/ to search".to_string()
+        } else {
+            format!("{}_", query)
+        };
+        let bar_style = if self.state.search_active {
+            Style::new().yellow()
+        } else {
+            Style::new().dark_gray()
+        };
+        let title = if self.state.remote {
+            " Search Shortcut (Enter to search, Ctrl+R for local) "
+        } else {
+            " Search (Ctrl+R for Shortcut) "
+        };
+        Paragraph::new(display)
+            .block(Block::bordered().title(title))
+            .style(bar_style)
+            .render(chunks[0], buf);
+
+        let block = Block::bordered()
+            .border_set(border::THICK)
+            .padding(Padding::vertical(1));
+        let inner = block.inner(chunks[1]);
+        block.render(chunks[1], buf);
+
+        let results = visible_results(self.state, self.data);
+        if results.is_empty() {
+            let message = if self.state.remote_loading {
+                "Searching Shortcut..."
+            } else if self.state.remote && self.state.remote_query.is_none() {
+                "Search every story in the workspace, e.g. owner:jane state:\"In Review\"."
+            } else if query.is_empty() {
+                "Search stories, epics, iterations and notes."
+            } else {
+                "No results."
+            };
+            let paragraph = Paragraph::new(message)
+                .style(Style::default().gray())
+                .alignment(Alignment::Center);
+
+            if inner.height > 0 {
+                let centered = Rect::new(inner.x, inner.y + inner.height / 2, inner.width, 1);
+                paragraph.render(centered, buf);
+            }
+            return;
+        }
+
+        let rows = rows(&results);
+
+        // Keep the selected result in view
+        let height = inner.height as usize;
+        let selected_row = rows.iter().position(|row| {
+            matches!(row, Row::Result(r) if self.state.selected.as_ref() == Some(&r.hit))
+        });
+        let scroll = selected_row
+            .map(|idx| (idx + 1).saturating_sub(height))
+            .unwrap_or(0);
+
+        for (y, row) in (inner.y..inner.y + inner.height).zip(rows.iter().skip(scroll)) {
+            let line = match row {
+                Row::Header(title) => {
+                    Line::from(format!(" ── {} ──", title)).style(Style::default().dark_gray())
+                }
+                Row::Result(result) => {
+                    if self.state.selected.as_ref() == Some(&result.hit) {
+                        Line::from(format!("▌ {}", result.label)).style(Style::default().yellow().bold())
+                    } else {
+                        Line::from(format!("  {}", result.label))
+                    }
+                }
+            };
+            buf.set_line(inner.x, y, &line, inner.width);
+        }
+    }
+}
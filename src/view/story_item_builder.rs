@@ -6,20 +6,34 @@ use ratatui::{
     widgets::Widget,
 };
 
-use crate::api::story::Story;
+use unicode_ellipsis::truncate_str;
+
+use crate::api::{
+    story::{Story, task::task_counts},
+    user::WorkspaceMember,
+    workflow::{WorkflowState, WorkflowStateType},
+};
+
+const OWNER_COLUMN_WIDTH: usize = 16;
 
 /// Renders a single story item with divider at the bottom
 pub struct StoryItemWidget<'a> {
     story: &'a Story,
+    state: Option<&'a WorkflowState>,
+    owners: Vec<&'a WorkspaceMember>,
     is_active: bool,
     is_selected: bool,
     _width: u16,
     is_completed: bool,
+    owner_column: bool,
+    unread: usize,
 }
 
 impl<'a> StoryItemWidget<'a> {
     pub fn new(
         story: &'a Story,
+        state: Option<&'a WorkflowState>,
+        owners: Vec<&'a WorkspaceMember>,
         is_active: bool,
         is_selected: bool,
         width: u16,
@@ -27,13 +41,30 @@ impl<'a> StoryItemWidget<'a> {
     ) -> Self {
         Self {
             story,
+            state,
+            owners,
             is_active,
             is_selected,
             _width: width,
             is_completed,
+            owner_column: false,
+            unread: 0,
         }
     }
 
+    /// Shows owner names in a fixed width column in front of the story, for lists with
+    /// everyone's stories
+    pub fn with_owner_column(mut self, owner_column: bool) -> Self {
+        self.owner_column = owner_column;
+        self
+    }
+
+    /// Number of unread activity entries for the story, marked after its state
+    pub fn with_unread(mut self, unread: usize) -> Self {
+        self.unread = unread;
+        self
+    }
+
     /// Calculate the total height including divider
     pub fn height(&self) -> u16 {
         2
@@ -54,8 +85,6 @@ impl Widget for StoryItemWidget<'_> {
         if area.height >= 2 {
             let divider_style = if self.is_selected {
                 Style::default().fg(Color::Yellow)
-            } else if self.is_completed {
-                Style::default().dark_gray()
             } else {
                 Style::default().dark_gray()
             };
@@ -66,6 +95,22 @@ impl Widget for StoryItemWidget<'_> {
 }
 
 impl StoryItemWidget<'_> {
+    /// First names of the owners, padded or truncated to `OWNER_COLUMN_WIDTH`
+    fn owner_column_text(&self) -> String {
+        let names = if self.owners.is_empty() {
+            "Unowned".to_string()
+        } else {
+            self.owners
+                .iter()
+                .map(|m| m.profile.name.split_whitespace().next().unwrap_or_default())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        let truncated = truncate_str(&names, OWNER_COLUMN_WIDTH - 1);
+        format!("{:<width$}", truncated, width = OWNER_COLUMN_WIDTH)
+    }
+
     fn render_story_line(&self) -> Line<'static> {
         let mut spans = Vec::new();
 
@@ -88,6 +133,17 @@ impl StoryItemWidget<'_> {
             spans.push(Span::raw("  "));
         }
 
+        if self.owner_column {
+            spans.push(Span::styled(
+                self.owner_column_text(),
+                Style::default().fg(if self.is_completed {
+                    Color::DarkGray
+                } else {
+                    Color::Cyan
+                }),
+            ));
+        }
+
         // Story ID
         let id_color = if self.is_completed {
             Color::DarkGray
@@ -107,6 +163,96 @@ impl StoryItemWidget<'_> {
         };
         spans.push(Span::styled(self.story.name.clone(), name_style));
 
+        // Workflow state
+        if let Some(state) = self.state {
+            let state_color = if self.is_completed {
+                Color::DarkGray
+            } else {
+                match state.state_type {
+                    WorkflowStateType::Started => Color::Yellow,
+                    WorkflowStateType::Done => Color::Green,
+                    _ => Color::Gray,
+                }
+            };
+            spans.push(Span::styled(
+                format!(" [{}]", state.name),
+                Style::default().fg(state_color),
+            ));
+        }
+
+        if let Some(estimate) = self.story.estimate {
+            let color = if self.is_completed {
+                Color::DarkGray
+            } else {
+                Color::LightBlue
+            };
+            let unit = if estimate == 1 { "pt" } else { "pts" };
+            spans.push(Span::styled(
+                format!(" {} {}", estimate, unit),
+                Style::default().fg(color),
+            ));
+        }
+
+        let (blocked_by, blocking) = self.story.blocker_counts();
+        if blocked_by > 0 {
+            let color = if self.is_completed {
+                Color::DarkGray
+            } else {
+                Color::Red
+            };
+            spans.push(Span::styled(
+                format!(" ⊘ blocked by {}", blocked_by),
+                Style::default().fg(color),
+            ));
+        }
+        if blocking > 0 {
+            let color = if self.is_completed {
+                Color::DarkGray
+            } else {
+                Color::Yellow
+            };
+            spans.push(Span::styled(
+                format!(" ⚑ blocks {}", blocking),
+                Style::default().fg(color),
+            ));
+        }
+
+        if !self.story.tasks.is_empty() {
+            let (done, total) = task_counts(&self.story.tasks);
+            let color = if self.is_completed {
+                Color::DarkGray
+            } else if done == total {
+                Color::Green
+            } else {
+                Color::Gray
+            };
+            spans.push(Span::styled(
+                format!(" ✓ {}/{}", done, total),
+                Style::default().fg(color),
+            ));
+        }
+
+        if self.unread > 0 {
+            spans.push(Span::styled(
+                format!(" ● {} new", self.unread),
+                Style::default().fg(Color::Magenta),
+            ));
+        }
+
+        // Owner initials
+        if !self.owner_column && !self.owners.is_empty() {
+            let initials: Vec<_> = self.owners.iter().map(|m| m.profile.initials()).collect();
+            let owner_color = if self.is_completed {
+                Color::DarkGray
+            } else {
+                Color::Cyan
+            };
+            spans.push(Span::styled(
+                format!(" {}", initials.join(" ")),
+                Style::default().fg(owner_color),
+            ));
+        }
+
         Line::from(spans)
     }
 }
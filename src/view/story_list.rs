@@ -3,26 +3,62 @@ use std::collections::HashMap;
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Constraint, Layout, Rect},
-    style::Style,
+    style::{Color, Style},
     symbols::border,
-    text::Line,
-    widgets::{Block, Padding, Paragraph, StatefulWidget, Widget, WidgetRef},
+    text::{Line, Span},
+    widgets::{Block, Gauge, Padding, Paragraph, StatefulWidget, Widget, WidgetRef},
 };
 use tui_widget_list::{ListBuilder, ListState, ListView};
 
 use crate::{
-    api::{iteration::Iteration, story::Story},
-    app::model::{LoadingState, StoryListState},
+    activity::unread_count,
+    api::{
+        iteration::Iteration,
+        story::{Story, story_points},
+        user::get_owners,
+        workflow::get_workflow_state,
+    },
+    app::model::{DataState, LoadingState, StoryListState},
 };
 
 use super::story_item_builder::StoryItemWidget;
 
+const GAUGE_WIDTH: u16 = 24;
 const SPINNER_CHARS: &[char] = &['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
 
 /// Represents a group of stories belonging to the same iteration
 pub struct IterationSection<'a> {
     pub iteration: Option<&'a Iteration>,
     pub stories: Vec<&'a Story>,
+    /// Done and total points, including finished stories that are hidden
+    pub points: (i32, i32),
+}
+
+impl<'a> IterationSection<'a> {
+    fn new(
+        iteration: Option<&'a Iteration>,
+        mut stories: Vec<&'a Story>,
+        show_finished: bool,
+    ) -> Option<Self> {
+        let points = story_points(stories.iter().copied());
+
+        // Filter out completed stories if show_finished is false
+        if !show_finished {
+            stories.retain(|s| !s.completed);
+        }
+        if stories.is_empty() {
+            return None;
+        }
+
+        // Sort: unfinished first, then completed
+        stories.sort_by_key(|s| s.completed);
+
+        Some(IterationSection {
+            iteration,
+            stories,
+            points,
+        })
+    }
 }
 
 /// Groups stories by their iteration, sorted by iteration start date
@@ -34,10 +70,6 @@ fn group_stories_by_iteration<'a>(
     // Build a HashMap grouping stories by iteration_id
     let mut grouped: HashMap<Option<i32>, Vec<&'a Story>> = HashMap::new();
     for story in stories {
-        // Filter out completed stories if show_finished is false
-        if !show_finished && story.completed {
-            continue;
-        }
         grouped.entry(story.iteration_id).or_default().push(story);
     }
 
@@ -49,57 +81,37 @@ fn group_stories_by_iteration<'a>(
         sorted_iterations.sort_by_key(|it| it.start_date);
 
         for iteration in sorted_iterations {
-            if let Some(mut stories) = grouped.remove(&Some(iteration.id)) {
-                // Sort: unfinished first, then completed
-                stories.sort_by_key(|s| s.completed);
-
-                sections.push(IterationSection {
-                    iteration: Some(iteration),
-                    stories,
-                });
+            if let Some(stories) = grouped.remove(&Some(iteration.id)) {
+                sections.extend(IterationSection::new(Some(iteration), stories, show_finished));
             }
         }
     }
 
     // Add "No Iteration" section at the end if there are stories without an iteration
-    if let Some(mut stories) = grouped.remove(&None) {
-        stories.sort_by_key(|s| s.completed);
-
-        sections.push(IterationSection {
-            iteration: None,
-            stories,
-        });
+    if let Some(stories) = grouped.remove(&None) {
+        sections.extend(IterationSection::new(None, stories, show_finished));
     }
 
     sections
 }
 
 pub struct StoryListView<'a> {
-    stories: &'a [Story],
-    iterations: Option<&'a [Iteration]>,
+    data: &'a DataState,
     state: &'a StoryListState,
-    active_story: Option<&'a Story>,
-    is_focused: bool,
     loading: LoadingState,
     tick: usize,
 }
 
 impl<'a> StoryListView<'a> {
     pub fn new(
-        stories: &'a [Story],
-        iterations: Option<&'a [Iteration]>,
+        data: &'a DataState,
         state: &'a StoryListState,
-        active_story: Option<&'a Story>,
-        is_focused: bool,
         loading: LoadingState,
         tick: usize,
     ) -> Self {
         Self {
-            stories,
-            iterations,
+            data,
             state,
-            active_story,
-            is_focused,
             loading,
             tick,
         }
@@ -113,13 +125,17 @@ impl<'a> StoryListView<'a> {
 impl<'a> WidgetRef for StoryListView<'a> {
     fn render_ref(&self, area: Rect, buf: &mut Buffer) {
         // Handle loading and empty states with a single bordered block
-        if self.stories.is_empty() {
+        let stories = self.data.listed_stories(self.state.show_team);
+
+        if stories.is_empty() {
             let block = Block::bordered().border_set(border::THICK);
             let inner = block.inner(area);
             block.render(area, buf);
 
             let message = if self.loading.is_loading() {
                 format!("{} {}", self.spinner_char(), self.loading.label())
+            } else if self.state.show_team {
+                "No team stories in this iteration.".to_string()
             } else {
                 "No stories assigned in this iteration.".to_string()
             };
@@ -137,7 +153,11 @@ impl<'a> WidgetRef for StoryListView<'a> {
         }
 
         // Group stories by iteration
-        let sections = group_stories_by_iteration(self.stories, self.iterations, self.state.show_finished);
+        let sections = group_stories_by_iteration(
+            stories,
+            self.data.current_iterations.as_deref(),
+            self.state.show_finished,
+        );
 
         // Calculate layout constraints for sections:
         // header (1) + bordered list (items*2 + 2 for border) + spacing (1)
@@ -161,16 +181,50 @@ impl<'a> WidgetRef for StoryListView<'a> {
             let header_area = section_areas[area_index];
             area_index += 1;
 
-            let header_text = if let Some(iteration) = section.iteration {
+            let mut header_text = if let Some(iteration) = section.iteration {
                 iteration.name.clone()
             } else {
                 "No Iteration".to_string()
             };
+            if self.state.show_team {
+                header_text = format!("Team · {}", header_text);
+            }
 
             let header_style = Style::default().dark_gray();
-            let display = format!(" ── {} ──", header_text);
-            let title_line = Line::from(display).style(header_style);
-            buf.set_line(header_area.x, header_area.y, &title_line, header_area.width);
+            let title = format!(" ── {} ──", header_text);
+            let mut title_spans = vec![Span::styled(title, header_style)];
+            if let Some(iteration) = section.iteration {
+                let days = iteration.days_remaining(crate::time::today());
+                let days_text = match days {
+                    0 => " ended".to_string(),
+                    1 => " last day".to_string(),
+                    _ => format!(" {} days left", days),
+                };
+                let days_style = if days <= 2 {
+                    Style::default().yellow()
+                } else {
+                    Style::default().gray()
+                };
+                title_spans.push(Span::styled(days_text, days_style));
+            }
+
+            let (done, total) = section.points;
+            let [title_area, gauge_area] = Layout::horizontal([
+                Constraint::Min(0),
+                Constraint::Length(if total > 0 { GAUGE_WIDTH } else { 0 }),
+            ])
+            .areas(header_area);
+
+            let title_line = Line::from(title_spans);
+            buf.set_line(title_area.x, title_area.y, &title_line, title_area.width);
+
+            if total > 0 {
+                Gauge::default()
+                    .ratio((done as f64 / total as f64).clamp(0.0, 1.0))
+                    .label(format!("{}/{} pts", done, total))
+                    .gauge_style(Style::default().fg(Color::Green).bg(Color::DarkGray))
+                    .render(gauge_area, buf);
+            }
 
             // Render bordered stories list
             let list_area = section_areas[area_index];
@@ -183,7 +237,11 @@ impl<'a> WidgetRef for StoryListView<'a> {
             list_block.render(list_area, buf);
 
             let section_stories: Vec<_> = section.stories.to_vec();
-            let active_story = self.active_story;
+            let active_story = self.data.active_story.as_ref();
+            let workflows = &self.data.workflows;
+            let members = &self.data.members;
+            let activity = &self.data.activity;
+            let show_team = self.state.show_team;
             let width = stories_area.width;
 
             let builder = ListBuilder::new(move |context| {
@@ -193,13 +251,19 @@ impl<'a> WidgetRef for StoryListView<'a> {
                     None => false,
                 };
                 let is_completed = story.completed;
+                let state = get_workflow_state(story.workflow_state_id, workflows);
+                let owners = get_owners(&story.owner_ids, members);
                 let widget = StoryItemWidget::new(
                     story,
+                    state,
+                    owners,
                     is_active,
                     context.is_selected,
                     width,
                     is_completed,
-                );
+                )
+                .with_owner_column(show_team)
+                .with_unread(unread_count(activity, story.id));
                 let height = widget.height();
 
                 (widget, height)
@@ -0,0 +1,54 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::Style,
+    text::Line,
+    widgets::{Block, BorderType, Clear, Padding, Paragraph, Widget, Wrap},
+};
+
+const PROMPT_WIDTH: u16 = 56;
+const PROMPT_HEIGHT: u16 = 9;
+
+/// Shown when Shortcut rejects the API token
+pub struct TokenPrompt<'a> {
+    config_path: &'a str,
+}
+
+impl<'a> TokenPrompt<'a> {
+    pub fn new(config_path: &'a str) -> Self {
+        Self { config_path }
+    }
+}
+
+impl Widget for TokenPrompt<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let width = PROMPT_WIDTH.min(area.width);
+        let height = PROMPT_HEIGHT.min(area.height);
+        let x = area.x + (area.width.saturating_sub(width)) / 2;
+        let y = area.y + (area.height.saturating_sub(height)) / 2;
+        let rect = Rect::new(x, y, width, height);
+
+        Clear.render(rect, buf);
+
+        let lines = vec![
+            Line::raw("Shortcut rejected your API token."),
+            Line::raw(""),
+            Line::raw(format!("Update api_token in {}", self.config_path))
+                .style(Style::default().gray()),
+            Line::raw(""),
+            Line::raw("e edit config • Esc dismiss").style(Style::default().dark_gray()),
+        ];
+
+        Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().red())
+                    .title(" Invalid API token ")
+                    .padding(Padding::horizontal(1)),
+            )
+            .render(rect, buf);
+    }
+}